@@ -1,9 +1,14 @@
 use actix::{Actor, System};
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{Context, Result};
+use clap::{value_parser, Parser};
 use client::websocket::WebsocketActor;
-use log::error;
-use std::{fs::File, path::PathBuf, str::FromStr};
+use log::{error, warn};
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use tokio_tungstenite::Connector;
 use url::Url;
 use uuid::Uuid;
 
@@ -12,18 +17,34 @@ use simplelog::{
 };
 
 use client::music_actor::MusicActor;
-use common::terminal::highlight::Theme;
+use common::{
+    heartbeat::{Heartbeat, DEFAULT_HEARTBEAT_INTERVAL_SECS, DEFAULT_HEARTBEAT_TIMEOUT_SECS},
+    messages::{network::TryJoinRequest, protocol::Capability},
+    syntax,
+    terminal::{
+        highlight::Theme,
+        ui_theme::{self, UiTheme},
+    },
+    tls,
+};
+
+const DEFAULT_RESUME_TOKEN_FILE: &str = "clihoot_resume_token";
 
 fn url_parser(arg: &str) -> Result<Url, String> {
-    let destination_addr = format!("ws://{arg}");
+    // plain websocket is used unless the scheme is given
+    let destination_addr = if arg.starts_with("ws://") || arg.starts_with("wss://") {
+        arg.to_string()
+    } else {
+        format!("ws://{arg}")
+    };
     Ok(Url::from_str(destination_addr.as_str())
-        .map_err(|_| "This is not valid url. Help: <host>:[port]")?)
+        .map_err(|_| "This is not valid url. Help: [ws:// | wss://]<host>:[port]")?)
 }
 
 #[derive(Parser)]
 #[clap(version = "1.0", author = "Pavol Kycina")]
 pub struct Args {
-    /// Url of the clihoot server
+    /// Url of the clihoot server, use wss://<host>:[port] if the server uses TLS
     #[clap(short, long, default_value="localhost:8080", value_parser=url_parser)]
     addr: Url,
 
@@ -35,9 +56,81 @@ pub struct Args {
     #[clap(short, long)]
     log_file: Option<PathBuf>,
 
-    /// Theme for syntax highlighting of code in questions
-    #[clap(short('t'), long, default_value_t, value_enum)]
+    /// Theme for syntax highlighting of code in questions. One of: default, eighties-dark,
+    /// mocha-dark, ocean-dark, ocean-light, inspired-git-hub, solarized-dark, solarized-light,
+    /// or a name of a .tmTheme file (without the extension) from the syntax directory
+    #[clap(short('t'), long, default_value_t)]
     syntax_theme: Theme,
+
+    /// Color theme of the user interface
+    #[clap(short, long, default_value_t, value_enum)]
+    ui_theme: UiTheme,
+
+    /// Directory with custom .sublime-syntax and .tmTheme files
+    /// (syntaxes used in the quiz are also received from the server)
+    #[clap(long)]
+    syntax_dir: Option<PathBuf>,
+
+    /// PEM certificate of the authority which signed the certificate of a wss:// server,
+    /// the well-known authorities are trusted if not given
+    #[clap(long)]
+    ca_cert: Option<PathBuf>,
+
+    /// SHA-256 fingerprint of the certificate of a wss:// server (shown on the teacher's
+    /// screen), only the certificate with this fingerprint is trusted
+    #[clap(long, conflicts_with = "ca_cert")]
+    fingerprint: Option<String>,
+
+    /// How often (in seconds) the client checks that the server is still alive
+    #[clap(
+        long,
+        default_value_t = DEFAULT_HEARTBEAT_INTERVAL_SECS,
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    heartbeat_interval: u64,
+
+    /// After how many seconds without any message from the server the connection is considered lost
+    #[clap(
+        long,
+        default_value_t = DEFAULT_HEARTBEAT_TIMEOUT_SECS,
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    heartbeat_timeout: u64,
+
+    /// Send all messages as JSON text instead of the binary `MessagePack` (useful for debugging)
+    #[clap(long)]
+    json: bool,
+
+    /// PIN of the game (shown on the teacher's screen), needed when the server hosts more games,
+    /// it is asked for when it is needed and not given
+    #[clap(long)]
+    pin: Option<String>,
+
+    /// Join code of the game (shown on the teacher's screen), needed when the teacher
+    /// protected the game with one, it is asked for when it is needed and not given
+    #[clap(long)]
+    join_code: Option<String>,
+
+    /// File with the secret which lets the player continue with their points when the teacher
+    /// resumes the game after the server died, it is created when it does not exist
+    #[clap(long, default_value = DEFAULT_RESUME_TOKEN_FILE)]
+    resume_token_file: PathBuf,
+}
+
+/// Reads the resume token from the file, a new one is generated and saved there when there is none
+fn load_resume_token(path: &Path) -> Result<String> {
+    if let Ok(token) = fs::read_to_string(path) {
+        let token = token.trim();
+        if !token.is_empty() {
+            return Ok(token.to_string());
+        }
+    }
+
+    let token = Uuid::new_v4().simple().to_string();
+    fs::write(path, &token)
+        .with_context(|| format!("Cannot save the resume token to {}", path.display()))?;
+
+    Ok(token)
 }
 
 fn main() -> Result<()> {
@@ -62,8 +155,35 @@ fn main() -> Result<()> {
         ),
     ])?;
 
+    if let Some(syntax_dir) = &args.syntax_dir {
+        syntax::load_custom_dir(syntax_dir)?;
+    }
+    args.syntax_theme.ensure_loaded()?;
+    ui_theme::init(args.ui_theme);
+
+    let connector = tls::client_config(args.ca_cert.as_deref(), args.fingerprint.as_deref())?
+        .map(Connector::Rustls);
+
     let url = args.addr;
     let silent = args.silent;
+    let heartbeat = Heartbeat::from_secs(args.heartbeat_interval, args.heartbeat_timeout);
+
+    let mut capabilities = Capability::supported();
+    if args.json {
+        capabilities.retain(|capability| *capability != Capability::MessagePack);
+    }
+    // without the token the player can still join, only not continue in a resumed game
+    let resume_token = load_resume_token(&args.resume_token_file)
+        .map_err(|err| warn!("{err:#}"))
+        .ok();
+
+    let request = TryJoinRequest {
+        capabilities,
+        pin: args.pin,
+        join_code: args.join_code,
+        resume_token,
+        ..TryJoinRequest::new(uuid)
+    };
 
     let sys = actix::System::new();
 
@@ -72,7 +192,15 @@ fn main() -> Result<()> {
 
         // start websocket actor
         let Ok(websocket_actor) =
-            WebsocketActor::new(url.clone(), uuid, addr_music_actor, args.syntax_theme).await
+            WebsocketActor::new(
+                url.clone(),
+                connector,
+                request,
+                addr_music_actor,
+                args.syntax_theme,
+                heartbeat,
+            )
+            .await
         else {
             error!(
                 "I can't contact the specified clihoot server on address: '{url}' I am sorry 😿\n[HINT] You can use --addr to specify the clihoot address."
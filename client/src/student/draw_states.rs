@@ -1,14 +1,20 @@
 use ratatui::{
     prelude::*,
-    widgets::{Block, List, ListItem, Paragraph},
+    widgets::{Block, BorderType, List, ListItem, Paragraph, Wrap},
 };
 
 use common::{
-    constants::COLORS,
-    terminal::render::{self, get_bordered_block, list_layout},
+    constants::AVATARS,
+    messages::network::{ChoiceReport, PlayerData},
+    terminal::{
+        render::{self, get_bordered_block, list_layout},
+        ui_theme::palette,
+    },
 };
 
-use crate::student::states::{ColorSelectionState, NameSelectionState};
+use crate::student::states::{
+    ColorSelectionState, NameSelectionState, ReportState, StudentIdSelectionState,
+};
 
 pub fn render_name_selection(frame: &mut Frame, state: &NameSelectionState, quiz_name: &str) {
     let layout = list_layout(
@@ -25,7 +31,7 @@ pub fn render_name_selection(frame: &mut Frame, state: &NameSelectionState, quiz
 
     let paragraph_name = Paragraph::new(format!("{}|", state.name)).block(get_bordered_block());
     let paragraph_used_name = Paragraph::new("Name already used")
-        .fg(Color::Red)
+        .style(palette().error)
         .block(Block::default());
 
     frame.render_widget(paragraph_name, layout[1]);
@@ -34,26 +40,174 @@ pub fn render_name_selection(frame: &mut Frame, state: &NameSelectionState, quiz
     }
 }
 
-pub fn render_color_selection(frame: &mut Frame, state: &mut ColorSelectionState, quiz_name: &str) {
+pub fn render_student_id_selection(
+    frame: &mut Frame,
+    state: &StudentIdSelectionState,
+    quiz_name: &str,
+) {
+    let layout = list_layout(
+        frame,
+        vec![
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Percentage(80),
+        ],
+        "Student ID (only the teacher sees it): ",
+        " Welcome! ",
+        quiz_name,
+    );
+
+    let paragraph_id = Paragraph::new(format!("{}|", state.student_id)).block(get_bordered_block());
+    frame.render_widget(paragraph_id, layout[1]);
+
+    if let Some(error) = &state.error {
+        let paragraph_error = Paragraph::new(error.as_str())
+            .style(palette().error)
+            .block(Block::default());
+        frame.render_widget(paragraph_error, layout[2]);
+    }
+}
+
+fn selection_list<'a>(items: Vec<ListItem<'a>>, title: &'a str, focused: bool) -> List<'a> {
+    let mut block = get_bordered_block().title(title);
+    if focused {
+        block = block.border_type(BorderType::Double);
+    }
+
+    List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
+        .highlight_symbol(if focused { ">> " } else { "   " })
+}
+
+pub fn render_color_selection(
+    frame: &mut Frame,
+    state: &mut ColorSelectionState,
+    players: &[PlayerData],
+    name: &str,
+    quiz_name: &str,
+) {
     let layout = list_layout(
         frame,
-        vec![Constraint::Length(1), Constraint::Percentage(90)],
-        "Color: ",
+        vec![
+            Constraint::Length(1),
+            Constraint::Percentage(85),
+            Constraint::Length(1),
+        ],
+        "Color and avatar (←→ to switch): ",
         " Welcome! ",
         quiz_name,
     );
 
-    let items: Vec<_> = COLORS
+    let lists_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(layout[1]);
+
+    let (color, avatar) = (state.color(), state.avatar());
+    let is_taken = |color, avatar| players.iter().any(|p| p.looks_like(color, avatar));
+    let taken_mark = |taken| if taken { " (taken)" } else { "" };
+
+    let color_items: Vec<_> = palette()
+        .colors
         .iter()
-        .map(|color| ListItem::new(format!("{color:?}")).style(style::Style::default().fg(*color)))
+        .map(|named| {
+            let taken = taken_mark(is_taken(named.color, avatar));
+            ListItem::new(format!("{}{taken}", named.name))
+                .style(style::Style::default().fg(named.color))
+        })
+        .collect();
+
+    let avatar_items: Vec<_> = std::iter::once(None)
+        .chain(AVATARS.iter().copied().map(Some))
+        .map(|avatar| {
+            let taken = taken_mark(is_taken(color, avatar));
+            ListItem::new(format!("{}{taken}", avatar.unwrap_or("(none)")))
+        })
         .collect();
 
-    let list = List::new(items)
+    let color_list = selection_list(color_items, " Color ", !state.avatar_list_focused);
+    let avatar_list = selection_list(avatar_items, " Avatar ", state.avatar_list_focused);
+
+    frame.render_stateful_widget(color_list, lists_layout[0], &mut state.list_state);
+    frame.render_stateful_widget(avatar_list, lists_layout[1], &mut state.avatar_list_state);
+
+    let bottom_line = if state.combination_taken {
+        Paragraph::new("This color and avatar are already taken, choose another combination")
+            .style(palette().error)
+    } else {
+        let preview = match avatar {
+            Some(avatar) => format!("{avatar} {name}"),
+            None => name.to_string(),
+        };
+        Paragraph::new(format!("You will look like this: {preview}")).fg(color)
+    };
+    frame.render_widget(bottom_line, layout[2]);
+}
+
+pub fn render_report(frame: &mut Frame, state: &mut ReportState, quiz_name: &str) {
+    let report = &state.report;
+    let layout = list_layout(
+        frame,
+        vec![
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ],
+        &format!(
+            "Rank {} of {}, {} points, {:.0} % correct",
+            report.rank,
+            report.players_count,
+            report.total_points,
+            report.accuracy()
+        ),
+        " Your game ",
+        quiz_name,
+    );
+
+    let mut lines = Vec::new();
+    for (index, question) in report.questions.iter().enumerate() {
+        let (mark, style) = if question.is_correct {
+            ("✔", palette().correct_item)
+        } else {
+            ("✘", palette().error)
+        };
+        let answer = match &question.selected_choices {
+            Some(choices) if !choices.is_empty() => choice_texts(choices),
+            _ => "(no answer)".to_string(),
+        };
+
+        lines.push(Line::styled(
+            format!("{mark} {}. {}", index + 1, question.question_text.trim()),
+            style,
+        ));
+        lines.push(Line::from(format!("    Your answer: {answer}")));
+        lines.push(Line::from(format!(
+            "    Correct answer: {}",
+            choice_texts(&question.correct_choices)
+        )));
+        lines.push(Line::from(format!("    Points: {}", question.points)));
+        lines.push(Line::default());
+    }
+
+    // the scroll is clamped here, the input handler does not know the number of lines
+    let max_scroll = u16::try_from(lines.len().saturating_sub(1)).unwrap_or(u16::MAX);
+    state.scroll = state.scroll.min(max_scroll);
+
+    let paragraph = Paragraph::new(lines)
         .block(get_bordered_block())
-        .highlight_style(Style::default().add_modifier(Modifier::ITALIC))
-        .highlight_symbol(">> ");
+        .wrap(Wrap { trim: false })
+        .scroll((state.scroll, 0));
+    frame.render_widget(paragraph, layout[1]);
 
-    frame.render_stateful_widget(list, layout[1], &mut state.list_state);
+    let status = match &state.saved {
+        None => Paragraph::new("↑↓ PgUp PgDn to scroll, s to save the report"),
+        Some(Ok(path)) => Paragraph::new(format!("Report saved to {}", path.display())),
+        Some(Err(err)) => {
+            Paragraph::new(format!("Cannot save the report: {err}")).style(palette().error)
+        }
+    };
+    frame.render_widget(status, layout[2]);
 }
 
 pub fn render_help(frame: &mut Frame) {
@@ -63,7 +217,12 @@ pub fn render_help(frame: &mut Frame) {
         ("SPACE", "Select an option"),
         ("h", "Show this help"),
         ("↑↓ | ws", "Move up and down"),
-        ("←→ | ad", "Move left and right"),
+        (
+            "←→ | ad",
+            "Move left and right, switch between colors and avatars",
+        ),
+        ("PgUp PgDn", "Scroll the code or the game report"),
+        ("s", "Save the game report to a Markdown file"),
     ];
     render::help(frame, &help_text);
 }
@@ -72,3 +231,18 @@ pub fn render_multichoice_popup(frame: &mut Frame) {
     let message = "Are you sure you want to submit an empty answer?\n You will get 0 points!";
     render::confirm(frame, message);
 }
+
+/// The choices on a single line, the choices without text are shown by the first line of their code,
+/// the whole code is in the saved report
+fn choice_texts(choices: &[ChoiceReport]) -> String {
+    let texts: Vec<&str> = choices
+        .iter()
+        .map(|choice| match &choice.code {
+            Some(code) if choice.text.trim().is_empty() => {
+                code.code.lines().next().unwrap_or_default().trim()
+            }
+            _ => choice.text.trim(),
+        })
+        .collect();
+    texts.join(", ")
+}
@@ -12,12 +12,14 @@ use common::{
 use crate::student::{
     draw_states::{
         render_color_selection, render_help, render_multichoice_popup, render_name_selection,
+        render_report, render_student_id_selection,
     },
     states::StudentTerminalState,
     terminal::StudentTerminal,
 };
 
 impl TerminalDraw for StudentTerminal {
+    #[allow(clippy::too_many_lines)]
     fn redraw<B: Backend>(&mut self, term: &mut Terminal<B>) -> anyhow::Result<()> {
         term.draw(|frame| {
             if frame.size().height < MINIMAL_SCREEN_HEIGHT
@@ -40,8 +42,17 @@ impl TerminalDraw for StudentTerminal {
                 StudentTerminalState::NameSelection(state) => {
                     render_name_selection(frame, state, &self.quiz_name);
                 }
+                StudentTerminalState::StudentIdSelection(state) => {
+                    render_student_id_selection(frame, state, &self.quiz_name);
+                }
                 StudentTerminalState::ColorSelection(state) => {
-                    render_color_selection(frame, state, &self.quiz_name);
+                    render_color_selection(
+                        frame,
+                        state,
+                        &self.players,
+                        &self.name,
+                        &self.quiz_name,
+                    );
                 }
                 StudentTerminalState::WaitingForGame(state) => {
                     render::waiting(
@@ -52,6 +63,14 @@ impl TerminalDraw for StudentTerminal {
                         &self.quiz_name,
                     );
                 }
+                StudentTerminalState::WaitingForApproval => {
+                    render::simple_message(
+                        frame,
+                        " Welcome! ",
+                        "Waiting for the teacher to let you in...",
+                        &self.quiz_name,
+                    );
+                }
                 StudentTerminalState::Question(state) => {
                     if frame.size().height < MINIMAL_QUESTION_HEIGHT
                         || frame.size().width < MINIMAL_QUESTION_WIDTH
@@ -72,7 +91,9 @@ impl TerminalDraw for StudentTerminal {
                             usize::try_from(state.duration_from_start.num_seconds())
                                 .unwrap_or(usize::MAX),
                             state.answered,
-                            self.syntax_theme,
+                            &self.syntax_theme,
+                            &mut state.code_scroll,
+                            &mut state.code_cache,
                             &self.quiz_name,
                         );
                         if state.multichoice_popup_visible {
@@ -94,7 +115,9 @@ impl TerminalDraw for StudentTerminal {
                         render::question::answers(
                             frame,
                             &state.answers,
-                            self.syntax_theme,
+                            &self.syntax_theme,
+                            &mut state.code_scroll,
+                            &mut state.code_cache,
                             &self.quiz_name,
                         );
                     }
@@ -108,7 +131,9 @@ impl TerminalDraw for StudentTerminal {
                         &self.quiz_name,
                     );
                 }
-
+                StudentTerminalState::Report(state) => {
+                    render_report(frame, state, &self.quiz_name);
+                }
                 StudentTerminalState::EndGame => {
                     render::end_game(frame, &self.quiz_name);
                 }
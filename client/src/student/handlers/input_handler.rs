@@ -1,16 +1,20 @@
+use std::{fs, path::PathBuf};
+
 use actix::Addr;
 use crossterm::event::KeyCode;
 use ratatui::widgets::ListState;
 
 use common::{
-    constants::{COLORS, MAXIMAL_NAME_LENGTH},
+    constants::{AVATARS, CODE_SCROLL_LINES, MAXIMAL_NAME_LENGTH, MAXIMAL_STUDENT_ID_LENGTH},
     messages::{
-        network::{AnswerSelected, JoinRequest, PlayerData},
+        network::{AnswerSelected, JoinRequest, PersonalReport, PlayerData},
         ClientNetworkMessage,
     },
+    questions::CodeBlock,
     terminal::{
         actor::TerminalHandleInput,
-        input_utils::move_in_list,
+        input_utils::{move_in_list, scroll_code},
+        ui_theme::palette,
         widgets::choice::{Grid, SelectorState},
     },
 };
@@ -20,8 +24,8 @@ use crate::{
     music_actor::{MusicActor, SoundEffectMessage},
     student::{
         states::{
-            ColorSelectionState, NameSelectionState, QuestionState, StudentTerminalState,
-            WaitingForGameState,
+            ColorSelectionState, NameSelectionState, QuestionState, ReportState,
+            StudentIdSelectionState, StudentTerminalState, WaitingForGameState,
         },
         terminal::StudentTerminal,
     },
@@ -29,7 +33,7 @@ use crate::{
 };
 
 impl TerminalHandleInput for StudentTerminal {
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::collapsible_match)]
     fn handle_input(&mut self, key_code: KeyCode) {
         // hide help pop-up if it is visible and any key is pressed
         if self.help_visible {
@@ -38,7 +42,11 @@ impl TerminalHandleInput for StudentTerminal {
         }
 
         if key_code == KeyCode::Char('h')
-            && !matches!(self.state, StudentTerminalState::NameSelection { .. })
+            && !matches!(
+                self.state,
+                StudentTerminalState::NameSelection { .. }
+                    | StudentTerminalState::StudentIdSelection { .. }
+            )
         {
             self.music_address.do_send(SoundEffectMessage::Tap);
             self.help_visible = true;
@@ -61,42 +69,100 @@ impl TerminalHandleInput for StudentTerminal {
                 ) {
                     self.music_address.do_send(SoundEffectMessage::EnterPressed);
                     self.name = (*state.name).to_string();
-                    self.state = StudentTerminalState::ColorSelection(ColorSelectionState {
-                        list_state: ListState::default().with_selected(Some(0)),
-                    });
+                    self.state = if self.student_id_required {
+                        StudentTerminalState::StudentIdSelection(StudentIdSelectionState {
+                            student_id: self.student_id.clone(),
+                            error: None,
+                        })
+                    } else {
+                        StudentTerminalState::ColorSelection(ColorSelectionState::new(
+                            self.color_index,
+                            self.avatar.as_deref(),
+                            false,
+                        ))
+                    };
+                }
+            }
+            StudentTerminalState::StudentIdSelection(state) => {
+                if input_student_id(&mut state.student_id, key_code, &mut state.error) {
+                    self.music_address.do_send(SoundEffectMessage::EnterPressed);
+                    self.student_id = state.student_id.trim().to_string();
+                    self.state = StudentTerminalState::ColorSelection(ColorSelectionState::new(
+                        self.color_index,
+                        self.avatar.as_deref(),
+                        false,
+                    ));
                 }
             }
             StudentTerminalState::ColorSelection(state) => {
                 if key_code == KeyCode::Backspace {
-                    self.state = StudentTerminalState::NameSelection(NameSelectionState {
-                        name: self.name.to_string(),
-                        name_already_used: false,
-                    });
+                    self.state = if self.student_id_required {
+                        StudentTerminalState::StudentIdSelection(StudentIdSelectionState {
+                            student_id: self.student_id.clone(),
+                            error: None,
+                        })
+                    } else {
+                        StudentTerminalState::NameSelection(NameSelectionState {
+                            name: self.name.to_string(),
+                            name_already_used: false,
+                        })
+                    };
                     return;
                 }
 
-                let mut selected = state.list_state.selected().unwrap_or(0);
+                if matches!(
+                    key_code,
+                    KeyCode::Left | KeyCode::Right | KeyCode::Char('a' | 'd') | KeyCode::Tab
+                ) {
+                    self.music_address.do_send(SoundEffectMessage::Tap);
+                    state.avatar_list_focused = !state.avatar_list_focused;
+                    return;
+                }
 
                 if key_code == KeyCode::Enter {
+                    let (color, avatar) = (state.color(), state.avatar());
+                    // players that already joined are known, no need to ask the server
+                    if self
+                        .players
+                        .iter()
+                        .any(|player| player.uuid != self.uuid && player.looks_like(color, avatar))
+                    {
+                        state.combination_taken = true;
+                        return;
+                    }
+
                     self.music_address.do_send(SoundEffectMessage::EnterPressed);
-                    self.color = COLORS[selected];
+                    self.color_index = state.color_index();
+                    self.avatar = avatar.map(str::to_owned);
                     self.state = StudentTerminalState::WaitingForGame(WaitingForGameState {
                         list_state: ListState::default().with_selected(Some(0)),
                     });
                     self.ws_actor_address
                         .do_send(ClientNetworkMessage::JoinRequest(JoinRequest {
                             player_data: PlayerData {
-                                color: self.color,
+                                color,
+                                color_index: Some(self.color_index),
                                 uuid: self.uuid,
                                 nickname: self.name.to_string(),
+                                avatar: self.avatar.clone(),
                             },
+                            student_id: self.student_id_required.then(|| self.student_id.clone()),
                         }));
                     return;
                 }
 
-                let moved = move_in_list(&mut selected, COLORS.len(), key_code);
-                state.list_state.select(Some(selected));
+                let (list_state, list_size) = if state.avatar_list_focused {
+                    // the avatars are preceded by the "no avatar" item
+                    (&mut state.avatar_list_state, AVATARS.len() + 1)
+                } else {
+                    (&mut state.list_state, palette().colors.len())
+                };
+
+                let mut selected = list_state.selected().unwrap_or(0);
+                let moved = move_in_list(&mut selected, list_size, key_code);
+                list_state.select(Some(selected));
                 if moved {
+                    state.combination_taken = false;
                     self.music_address.do_send(SoundEffectMessage::Tap);
                 }
             }
@@ -110,6 +176,19 @@ impl TerminalHandleInput for StudentTerminal {
                 }
             }
             StudentTerminalState::Question(state) => {
+                // the code can be scrolled even before the choices are shown
+                if scroll_code(
+                    &mut state.code_scroll,
+                    state
+                        .question
+                        .code_block
+                        .as_ref()
+                        .map_or(0, CodeBlock::lines_count),
+                    key_code,
+                ) {
+                    return;
+                }
+
                 if (usize::try_from(state.duration_from_start.num_seconds()).unwrap_or(usize::MAX))
                     < state.question.show_choices_after
                 {
@@ -159,7 +238,31 @@ impl TerminalHandleInput for StudentTerminal {
                     &self.music_address,
                 );
             }
+            StudentTerminalState::Answers(state) => {
+                scroll_code(
+                    &mut state.code_scroll,
+                    state
+                        .answers
+                        .question
+                        .code_block
+                        .as_ref()
+                        .map_or(0, CodeBlock::lines_count),
+                    key_code,
+                );
+            }
             StudentTerminalState::Results(state) => {
+                if key_code == KeyCode::Enter && state.results.was_final_round {
+                    if let Some(report) = self.report.take() {
+                        self.music_address.do_send(SoundEffectMessage::Tap);
+                        self.state = StudentTerminalState::Report(ReportState {
+                            report,
+                            scroll: 0,
+                            saved: None,
+                        });
+                    }
+                    return;
+                }
+
                 let mut selected = state.table_state.selected().unwrap_or(0);
                 let moved = move_in_list(&mut selected, state.results.players.len(), key_code);
                 state.table_state.select(Some(selected));
@@ -168,11 +271,38 @@ impl TerminalHandleInput for StudentTerminal {
                     self.music_address.do_send(SoundEffectMessage::Tap);
                 }
             }
+            StudentTerminalState::Report(state) => match key_code {
+                KeyCode::Up => state.scroll = state.scroll.saturating_sub(1),
+                KeyCode::Down => state.scroll = state.scroll.saturating_add(1),
+                KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(CODE_SCROLL_LINES),
+                KeyCode::PageDown => {
+                    state.scroll = state.scroll.saturating_add(CODE_SCROLL_LINES);
+                }
+                KeyCode::Char('s') => {
+                    self.music_address.do_send(SoundEffectMessage::Tap);
+                    state.saved =
+                        Some(save_report(&state.report).map_err(|err| format!("{err:#}")));
+                }
+                _ => {}
+            },
             _ => {}
         };
     }
 }
 
+// the report is saved to the current directory, named after the quiz and the player
+fn save_report(report: &PersonalReport) -> anyhow::Result<PathBuf> {
+    let file_name: String = format!("{}_{}_report", report.quiz_name, report.nickname)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    let path = PathBuf::from(format!("{file_name}.md"));
+
+    fs::write(&path, report.to_markdown())?;
+
+    Ok(path)
+}
+
 fn name_in_players(name: &str, players: &[PlayerData]) -> bool {
     players.iter().any(|player| player.nickname == name)
 }
@@ -208,6 +338,30 @@ fn input_name(
     }
 }
 
+/// Returns whether the student confirmed the ID, the server checks it against the roster
+fn input_student_id(
+    student_id: &mut String,
+    key_code: KeyCode,
+    error: &mut Option<String>,
+) -> bool {
+    match key_code {
+        KeyCode::Backspace => {
+            student_id.pop();
+            *error = None;
+            false
+        }
+        KeyCode::Char(char) => {
+            if student_id.chars().count() < MAXIMAL_STUDENT_ID_LENGTH {
+                student_id.push(char);
+                *error = None;
+            }
+            false
+        }
+        KeyCode::Enter => !student_id.trim().is_empty(),
+        _ => false,
+    }
+}
+
 fn move_in_answers(
     key_code: KeyCode,
     choice_selector_state: &mut SelectorState,
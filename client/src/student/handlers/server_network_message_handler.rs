@@ -4,23 +4,30 @@ use std::collections::HashSet;
 use uuid::Uuid;
 
 use common::{
-    constants::NICKNAME_ALREADY_TAKEN_MSG,
+    constants::{
+        COLOR_AND_AVATAR_TAKEN_MSG, NICKNAME_ALREADY_TAKEN_MSG, STUDENT_ID_REQUIRED_MSG,
+        STUDENT_ID_TAKEN_MSG, UNKNOWN_STUDENT_ID_MSG,
+    },
     messages::{network::CanJoin, ServerNetworkMessage},
-    terminal::{actor::TerminalHandleServerNetworkMessage, widgets::choice::SelectorState},
+    terminal::{
+        actor::TerminalHandleServerNetworkMessage, highlight::CodeCache,
+        widgets::choice::SelectorState,
+    },
 };
 
 use crate::{
     music_actor::{MusicMessage, SoundEffectMessage},
     student::{
         states::{
-            AnswersState, ErrorState, NameSelectionState, QuestionState, ResultsState,
-            StudentTerminalState, WaitingForGameState,
+            AnswersState, ColorSelectionState, ErrorState, NameSelectionState, QuestionState,
+            ResultsState, StudentIdSelectionState, StudentTerminalState, WaitingForGameState,
         },
         terminal::StudentTerminal,
     },
 };
 
 impl TerminalHandleServerNetworkMessage for StudentTerminal {
+    #[allow(clippy::too_many_lines)]
     fn handle_network_message(
         &mut self,
         network_message: ServerNetworkMessage,
@@ -35,6 +42,25 @@ impl TerminalHandleServerNetworkMessage for StudentTerminal {
                             name: self.name.clone(),
                             name_already_used: true,
                         });
+                    } else if [
+                        STUDENT_ID_REQUIRED_MSG,
+                        UNKNOWN_STUDENT_ID_MSG,
+                        STUDENT_ID_TAKEN_MSG,
+                    ]
+                    .contains(&message.as_str())
+                    {
+                        self.state =
+                            StudentTerminalState::StudentIdSelection(StudentIdSelectionState {
+                                student_id: self.student_id.clone(),
+                                error: Some(message),
+                            });
+                    } else if message == COLOR_AND_AVATAR_TAKEN_MSG {
+                        self.state =
+                            StudentTerminalState::ColorSelection(ColorSelectionState::new(
+                                self.color_index,
+                                self.avatar.as_deref(),
+                                true,
+                            ));
                     } else {
                         self.state = StudentTerminalState::Error(ErrorState { message });
                     }
@@ -56,6 +82,8 @@ impl TerminalHandleServerNetworkMessage for StudentTerminal {
                     choice_grid: question.question.into(),
                     choice_selector_state: SelectorState::default(),
                     multichoice_popup_visible: false,
+                    code_scroll: 0,
+                    code_cache: CodeCache::default(),
                 });
             }
             ServerNetworkMessage::QuestionUpdate(update) => {
@@ -95,7 +123,11 @@ impl TerminalHandleServerNetworkMessage for StudentTerminal {
                     self.music_address.do_send(sound_to_play);
                 }
 
-                self.state = StudentTerminalState::Answers(AnswersState { answers: question });
+                self.state = StudentTerminalState::Answers(AnswersState {
+                    answers: question,
+                    code_scroll: 0,
+                    code_cache: CodeCache::default(),
+                });
             }
             ServerNetworkMessage::ShowLeaderboard(leaderboard) => {
                 debug!("Student: handling show leaderboard");
@@ -108,12 +140,25 @@ impl TerminalHandleServerNetworkMessage for StudentTerminal {
                 debug!("Student: handling players update");
                 self.players = update.players;
             }
+            ServerNetworkMessage::PlayersDelta(delta) => {
+                debug!("Student: handling players delta");
+                delta.apply(&mut self.players);
+            }
             ServerNetworkMessage::TeacherDisconnected(_) => {
                 debug!("Student: handling teacher disconnected");
                 self.state = StudentTerminalState::Error(ErrorState {
                     message: "Teacher disconnected from the game".to_string(),
                 });
             }
+            ServerNetworkMessage::PersonalReport(report) => {
+                debug!("Student: handling personal report");
+                // shown after the student leaves the final leaderboard
+                self.report = Some(report);
+            }
+            ServerNetworkMessage::WaitingForApproval(_) => {
+                debug!("Student: handling waiting for approval");
+                self.state = StudentTerminalState::WaitingForApproval;
+            }
             ServerNetworkMessage::TryJoinResponse(_) => {
                 debug!("Student: handling try join response");
                 unreachable!("Student should not receive TryJoinResponse");
@@ -27,7 +27,19 @@ impl TerminalHandleClientWebsocketStatus for StudentTerminal {
             }
             ClientWebsocketStatus::SocketClosed => {
                 self.music_address.do_send(MusicMessage::NoMusic);
-                self.state = StudentTerminalState::EndGame;
+                // the game is over, the student can keep reading the report
+                if !matches!(self.state, StudentTerminalState::Report(_)) {
+                    self.state = StudentTerminalState::EndGame;
+                }
+            }
+            ClientWebsocketStatus::ServerTimeout => {
+                self.music_address.do_send(MusicMessage::NoMusic);
+                if !matches!(self.state, StudentTerminalState::Report(_)) {
+                    self.state = StudentTerminalState::Error(ErrorState {
+                        message: "The server stopped responding, check your network connection"
+                            .to_string(),
+                    });
+                }
             }
             ClientWebsocketStatus::CloseFrameReceived(message) => {
                 self.music_address.do_send(MusicMessage::NoMusic);
@@ -1,8 +1,18 @@
-use ratatui::widgets::{ListState, TableState};
+use std::path::PathBuf;
+
+use ratatui::{
+    style::Color,
+    widgets::{ListState, TableState},
+};
 
 use common::{
-    messages::network::{NextQuestion, QuestionEnded, ShowLeaderboard},
-    terminal::widgets::choice::{Grid, SelectorState},
+    constants::AVATARS,
+    messages::network::{NextQuestion, PersonalReport, QuestionEnded, ShowLeaderboard},
+    terminal::{
+        highlight::CodeCache,
+        ui_theme::palette,
+        widgets::choice::{Grid, SelectorState},
+    },
 };
 
 #[derive(Debug)]
@@ -11,9 +21,50 @@ pub struct NameSelectionState {
     pub(super) name_already_used: bool,
 }
 
+#[derive(Debug)]
+pub struct StudentIdSelectionState {
+    pub(super) student_id: String,
+    /// why the server did not accept the ID
+    pub(super) error: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct ColorSelectionState {
     pub(super) list_state: ListState,
+    pub(super) avatar_list_state: ListState,
+    pub(super) avatar_list_focused: bool,
+    pub(super) combination_taken: bool,
+}
+
+impl ColorSelectionState {
+    pub(super) fn new(color_index: usize, avatar: Option<&str>, combination_taken: bool) -> Self {
+        let avatar_index = avatar
+            .and_then(|avatar| AVATARS.iter().position(|a| *a == avatar))
+            .map_or(0, |index| index + 1);
+
+        Self {
+            list_state: ListState::default().with_selected(Some(color_index)),
+            avatar_list_state: ListState::default().with_selected(Some(avatar_index)),
+            avatar_list_focused: false,
+            combination_taken,
+        }
+    }
+
+    pub(super) fn color_index(&self) -> usize {
+        self.list_state.selected().unwrap_or(0)
+    }
+
+    pub(super) fn color(&self) -> Color {
+        palette().colors[self.color_index()].color
+    }
+
+    // the first item of the avatar list means no avatar
+    pub(super) fn avatar(&self) -> Option<&'static str> {
+        match self.avatar_list_state.selected().unwrap_or(0) {
+            0 => None,
+            index => AVATARS.get(index - 1).copied(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -31,11 +82,15 @@ pub struct QuestionState {
     pub(super) choice_grid: Grid,
     pub(super) choice_selector_state: SelectorState,
     pub(super) multichoice_popup_visible: bool,
+    pub(super) code_scroll: u16,
+    pub(super) code_cache: CodeCache,
 }
 
 #[derive(Debug)]
 pub struct AnswersState {
     pub(super) answers: QuestionEnded,
+    pub(super) code_scroll: u16,
+    pub(super) code_cache: CodeCache,
 }
 
 #[derive(Debug)]
@@ -44,6 +99,14 @@ pub struct ResultsState {
     pub(super) table_state: TableState,
 }
 
+#[derive(Debug)]
+pub struct ReportState {
+    pub(super) report: PersonalReport,
+    pub(super) scroll: u16,
+    /// where the report was saved to, or why it could not be saved
+    pub(super) saved: Option<Result<PathBuf, String>>,
+}
+
 #[derive(Debug)]
 pub struct ErrorState {
     pub(super) message: String,
@@ -53,11 +116,16 @@ pub struct ErrorState {
 pub enum StudentTerminalState {
     StartGame,
     NameSelection(NameSelectionState),
+    /// only when the server has a class roster
+    StudentIdSelection(StudentIdSelectionState),
     ColorSelection(ColorSelectionState),
     WaitingForGame(WaitingForGameState),
+    /// the teacher decides whether the student can join
+    WaitingForApproval,
     Question(QuestionState),
     Answers(AnswersState),
     Results(ResultsState),
+    Report(ReportState),
     EndGame,
     Error(ErrorState),
 }
@@ -1,11 +1,10 @@
 use actix::prelude::*;
 use log::debug;
-use ratatui::style::Color;
 
 use uuid::Uuid;
 
 use common::{
-    messages::network::PlayerData,
+    messages::network::{PersonalReport, PlayerData},
     terminal::{
         actor::{TerminalActor, TerminalStop},
         highlight::Theme,
@@ -22,11 +21,18 @@ use crate::{
 pub struct StudentTerminal {
     pub uuid: Uuid,
     pub name: String,
-    pub color: Color,
+    /// index of the chosen color in the palette
+    pub color_index: usize,
+    pub avatar: Option<String>,
+    /// whether the server wants the ID of the student from the class roster
+    pub student_id_required: bool,
+    pub student_id: String,
     pub quiz_name: String,
     pub syntax_theme: Theme,
     pub help_visible: bool,
     pub players: Vec<PlayerData>,
+    /// summary of the game, received when the game ends
+    pub report: Option<PersonalReport>,
     pub ws_actor_address: Addr<WebsocketActor>,
     pub state: StudentTerminalState,
     pub music_address: Addr<MusicActor>,
@@ -37,6 +43,7 @@ impl StudentTerminal {
     pub fn new(
         uuid: Uuid,
         quiz_name: String,
+        student_id_required: bool,
         ws_addr: Addr<WebsocketActor>,
         music_address: Addr<MusicActor>,
         syntax_theme: Theme,
@@ -44,10 +51,14 @@ impl StudentTerminal {
         Self {
             uuid,
             name: String::new(),
-            color: Color::default(),
+            color_index: 0,
+            avatar: None,
+            student_id_required,
+            student_id: String::new(),
             quiz_name,
             help_visible: false,
             players: Vec::new(),
+            report: None,
             ws_actor_address: ws_addr,
             state: StudentTerminalState::StartGame,
             music_address,
@@ -67,6 +78,7 @@ impl TerminalStop for StudentTerminal {
 pub fn run_student(
     uuid: Uuid,
     quiz_name: String,
+    student_id_required: bool,
     ws_actor_addr: Addr<WebsocketActor>,
     music_actor_addr: &Addr<MusicActor>,
     syntax_theme: Theme,
@@ -74,6 +86,7 @@ pub fn run_student(
     let term = TerminalActor::new(StudentTerminal::new(
         uuid,
         quiz_name,
+        student_id_required,
         ws_actor_addr,
         music_actor_addr.clone(),
         syntax_theme,
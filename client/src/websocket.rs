@@ -3,25 +3,31 @@ use actix::{Actor, Context, Message};
 
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
-use log::{debug, info, warn};
+use log::{debug, error, info, warn};
 use std::cell::RefCell;
+use std::io::{IsTerminal, Write};
 use std::rc::Rc;
+use std::time::Instant;
 
 use url::Url;
-use uuid::Uuid;
 
 use tokio::net::TcpStream;
 use tokio_tungstenite::MaybeTlsStream;
-use tokio_tungstenite::{connect_async, tungstenite, WebSocketStream};
+use tokio_tungstenite::{connect_async_tls_with_config, tungstenite, Connector, WebSocketStream};
 use tungstenite::Error::ConnectionClosed;
 
 use common::{
+    constants::{JOIN_CODE_REQUIRED_MSG, PIN_REQUIRED_MSG, UNKNOWN_PIN_MSG, WRONG_JOIN_CODE_MSG},
+    heartbeat::Heartbeat,
     messages::{
+        encoding::{decode, Encoding},
         network::{self, CanJoin::No, TryJoinRequest},
+        protocol::Capability,
         status::ClientWebsocketStatus,
         ClientNetworkMessage, ServerNetworkMessage,
         ServerNetworkMessage::TryJoinResponse,
     },
+    syntax,
     terminal::highlight::Theme,
 };
 
@@ -35,6 +41,11 @@ pub struct Subscribe(pub Recipient<ServerNetworkMessage>);
 #[rtype(result = "()")]
 pub struct SubscribeStatus(pub Recipient<ClientWebsocketStatus>);
 
+/// Anything came from the server, so it is still alive
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+struct ServerActive;
+
 type Sender = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, tungstenite::protocol::Message>;
 type Receiver = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
 
@@ -48,23 +59,38 @@ pub struct WebsocketActor {
     subscribers_status: Vec<Recipient<ClientWebsocketStatus>>,
     music_actor_addr: Addr<MusicActor>,
     syntax_theme: Theme,
+    heartbeat: Heartbeat,
+    last_heard: Instant,
+    /// what the client announced to the server
+    capabilities: Vec<Capability>,
+    /// sent again with another PIN when the server does not find the game,
+    /// or with another join code when the code was wrong
+    request: TryJoinRequest,
+    /// how the messages for the server are encoded, JSON until the server answers
+    encoding: Encoding,
 }
 
 impl WebsocketActor {
+    /// Connects to the server, `connector` configures the TLS of a `wss://` url
+    /// (the well-known certificate authorities are trusted without it)
     pub async fn new(
         url: Url,
-        uuid: Uuid,
+        connector: Option<Connector>,
+        request: TryJoinRequest,
         music_actor_addr: Addr<MusicActor>,
         syntax_theme: Theme,
+        heartbeat: Heartbeat,
     ) -> anyhow::Result<Self> {
-        let (ws_stream, _) = connect_async(url).await?;
+        let (ws_stream, _) =
+            connect_async_tls_with_config(url.as_str(), None, false, connector).await?;
 
         let (tx, rx) = ws_stream.split();
         let tx_rc = Rc::new(RefCell::new(tx));
 
         send_message_directly(
             tx_rc.clone(),
-            ClientNetworkMessage::TryJoinRequest(TryJoinRequest { uuid }),
+            ClientNetworkMessage::TryJoinRequest(request.clone()),
+            Encoding::Json,
         )
         .await?;
 
@@ -75,9 +101,29 @@ impl WebsocketActor {
             subscribers_status: vec![],
             music_actor_addr,
             syntax_theme,
+            heartbeat,
+            last_heard: Instant::now(),
+            capabilities: request.capabilities.clone(),
+            request,
+            encoding: Encoding::Json,
         })
     }
 
+    fn check_heartbeat(&mut self, ctx: &mut <WebsocketActor as Actor>::Context) {
+        if self.heartbeat.is_stale(self.last_heard) {
+            warn!(
+                "nothing came from the server for {:?}",
+                self.heartbeat.timeout
+            );
+            ctx.notify(ClientWebsocketStatus::ServerTimeout);
+            return;
+        }
+
+        send_ping(Rc::clone(&self.ws_stream_tx))
+            .into_actor(self)
+            .wait(ctx);
+    }
+
     fn handle_try_join_response(
         &mut self,
         message: ServerNetworkMessage,
@@ -88,11 +134,49 @@ impl WebsocketActor {
             uuid,
             can_join,
             quiz_name,
+            custom_syntaxes,
+            protocol,
+            capabilities,
+            student_id_required,
         }) = message
         else {
             return;
         };
 
+        // the student can still enter the right PIN or join code,
+        // the game has not started for them yet
+        if let (No(reason), true) = (&can_join, std::io::stdin().is_terminal()) {
+            if reason == PIN_REQUIRED_MSG || reason == UNKNOWN_PIN_MSG {
+                self.ask_and_retry(
+                    reason.clone(),
+                    "Game PIN",
+                    |request, pin| request.pin = Some(pin),
+                    ctx,
+                );
+                return;
+            }
+            if reason == JOIN_CODE_REQUIRED_MSG || reason == WRONG_JOIN_CODE_MSG {
+                self.ask_and_retry(
+                    reason.clone(),
+                    "Join code",
+                    |request, join_code| request.join_code = Some(join_code),
+                    ctx,
+                );
+                return;
+            }
+        }
+
+        // the syntaxes have to be known before the first question arrives
+        if let Err(err) = syntax::add_custom_syntaxes(custom_syntaxes) {
+            error!("Unable to load custom syntaxes from the server: {err}");
+        }
+
+        self.encoding = Encoding::negotiate(&self.capabilities, &capabilities);
+        debug!(
+            "server speaks protocol {protocol}, messages are encoded as {:?}",
+            self.encoding
+        );
+
         if let No(reason) = can_join {
             info!("server does not allow us to join, reason: {}", reason);
         }
@@ -100,9 +184,10 @@ impl WebsocketActor {
         if let Ok(student_actor_addr) = run_student(
             uuid,
             quiz_name,
+            student_id_required,
             ctx.address(),
             &self.music_actor_addr,
-            self.syntax_theme,
+            self.syntax_theme.clone(),
         ) {
             // register student actor for network messages
             ctx.notify(Subscribe(student_actor_addr.clone().recipient()));
@@ -113,13 +198,62 @@ impl WebsocketActor {
     }
 }
 
+impl WebsocketActor {
+    /// Asks the student for the PIN or the join code of the game (`label`) and tries
+    /// to join again with it, the client stops if nothing is entered
+    fn ask_and_retry(
+        &mut self,
+        reason: String,
+        label: &'static str,
+        set: fn(&mut network::TryJoinRequest, String),
+        ctx: &mut <WebsocketActor as Actor>::Context,
+    ) {
+        // the terminal is not taken over by the student's screen yet
+        let prompt = tokio::task::spawn_blocking(move || {
+            println!("{reason}");
+            print!("{label}: ");
+            std::io::stdout().flush()?;
+
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer)?;
+            std::io::Result::Ok(answer.trim().to_owned())
+        });
+
+        // spawned, not waited for, so the heartbeat keeps going while the student types
+        prompt
+            .into_actor(self)
+            .map(move |answer, actor, ctx| {
+                let answer = match answer {
+                    Ok(Ok(answer)) if !answer.is_empty() => answer,
+                    _ => {
+                        info!("no {label} was entered, giving up");
+                        System::current().stop();
+                        return;
+                    }
+                };
+
+                set(&mut actor.request, answer);
+                let request = ClientNetworkMessage::TryJoinRequest(actor.request.clone());
+                send_message(
+                    Rc::clone(&actor.ws_stream_tx),
+                    request,
+                    Encoding::Json,
+                    ctx.address(),
+                )
+                .into_actor(actor)
+                .spawn(ctx);
+            })
+            .spawn(ctx);
+    }
+}
+
 // handler for message requests from another local actors
 impl Handler<ClientNetworkMessage> for WebsocketActor {
     type Result = ();
 
     fn handle(&mut self, msg: ClientNetworkMessage, ctx: &mut Context<Self>) {
         let ws_stream_tx = Rc::clone(&self.ws_stream_tx);
-        send_message(ws_stream_tx, msg, ctx.address())
+        send_message(ws_stream_tx, msg, self.encoding, ctx.address())
             .into_actor(self)
             .wait(ctx);
     }
@@ -131,28 +265,39 @@ This function try to send message to the server using websocket and if it fails,
 async fn send_message(
     stream_tx: Rc<RefCell<Sender>>,
     message: ClientNetworkMessage,
+    encoding: Encoding,
     my_address: Addr<WebsocketActor>,
 ) {
-    if let Err(_error) = send_message_directly(stream_tx, message).await {
+    if let Err(_error) = send_message_directly(stream_tx, message, encoding).await {
         debug!("websocket failed to send message");
         my_address.do_send(ClientWebsocketStatus::CantSendMessage);
     }
 }
 
+// the server answers with a pong, which keeps `last_heard` fresh
+#[allow(clippy::await_holding_refcell_ref)]
+async fn send_ping(stream_tx: Rc<RefCell<Sender>>) {
+    if let Err(error) = stream_tx
+        .borrow_mut()
+        .send(tungstenite::Message::Ping(tungstenite::Bytes::new()))
+        .await
+    {
+        debug!("websocket failed to send ping: {error}");
+    }
+}
+
 // I was not able to fix this.. I admit my weakness ... :-(
 #[allow(clippy::await_holding_refcell_ref)]
 async fn send_message_directly(
     stream_tx: Rc<RefCell<Sender>>,
     message: ClientNetworkMessage,
+    encoding: Encoding,
 ) -> anyhow::Result<()> {
-    let serialized_message = serde_json::to_string(&message)?;
+    let frame = encoding.encode(&message)?;
 
     debug!("client websocket actor: sending message");
 
-    stream_tx
-        .borrow_mut()
-        .send(tungstenite::Message::Text(serialized_message))
-        .await?;
+    stream_tx.borrow_mut().send(frame).await?;
 
     Ok(())
 }
@@ -187,6 +332,7 @@ impl Handler<ClientWebsocketStatus> for WebsocketActor {
             ClientWebsocketStatus::ListeningFail
             | ClientWebsocketStatus::CantSendMessage
             | ClientWebsocketStatus::SocketClosed
+            | ClientWebsocketStatus::ServerTimeout
             | ClientWebsocketStatus::CloseFrameReceived(_) => {
                 ctx.stop(); // stop websocket actor
             }
@@ -195,6 +341,14 @@ impl Handler<ClientWebsocketStatus> for WebsocketActor {
     }
 }
 
+impl Handler<ServerActive> for WebsocketActor {
+    type Result = ();
+
+    fn handle(&mut self, _: ServerActive, _: &mut Self::Context) {
+        self.last_heard = Instant::now();
+    }
+}
+
 impl Handler<Subscribe> for WebsocketActor {
     type Result = ();
 
@@ -235,6 +389,8 @@ impl Actor for WebsocketActor {
         }
         .into_actor(self)
         .spawn(ctx);
+
+        ctx.run_interval(self.heartbeat.interval, Self::check_heartbeat);
     }
 }
 
@@ -244,10 +400,12 @@ async fn listen_for_messages(
 ) -> anyhow::Result<()> {
     // listen for messages from server
     while let Ok(incoming_msg) = rx_stream.next().await.ok_or(ConnectionClosed)? {
+        // also pings and pongs count, the server is alive
+        websocket_actor_address.do_send(ServerActive);
+
         match incoming_msg {
-            tungstenite::Message::Text(text_msg) => {
-                let deserialized_msg: ServerNetworkMessage =
-                    serde_json::from_str(text_msg.as_str())?;
+            tungstenite::Message::Text(_) | tungstenite::Message::Binary(_) => {
+                let deserialized_msg: ServerNetworkMessage = decode(&incoming_msg)?;
                 websocket_actor_address.do_send(deserialized_msg);
             }
             tungstenite::Message::Close(content) => {
@@ -0,0 +1,132 @@
+//! Compares sending a message to a whole class the old way, serializing it for every socket,
+//! with serializing it once and sharing the frame (`SharedMessage`)
+//!
+//! Run with `cargo bench -p common --bench broadcast`, the allocations per socket
+//! are printed before the timings
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use common::{
+    constants::AVATARS,
+    messages::{
+        encoding::{Encoding, SharedMessage},
+        network::{PlayerData, PlayersUpdate},
+        ServerNetworkMessage,
+    },
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::style::Color;
+use uuid::Uuid;
+
+/// students in a big lecture
+const STUDENTS: usize = 200;
+
+/// Counts the allocations and the allocated bytes of the whole benchmark
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Prints how many allocations (and bytes) sending the message to one socket costs
+#[allow(clippy::cast_precision_loss)]
+fn print_allocations_per_socket(name: &str, send_to_class: impl FnOnce()) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+
+    send_to_class();
+
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes;
+    println!(
+        "{name}: {:.1} allocations, {} bytes per socket",
+        allocations as f64 / STUDENTS as f64,
+        bytes / STUDENTS
+    );
+}
+
+fn players_update() -> ServerNetworkMessage {
+    ServerNetworkMessage::PlayersUpdate(PlayersUpdate {
+        players: (0..STUDENTS)
+            .map(|i| PlayerData {
+                uuid: Uuid::new_v4(),
+                nickname: format!("student {i}"),
+                color: Color::Rgb(10, 20, 30),
+                color_index: None,
+                avatar: Some(AVATARS[i % AVATARS.len()].to_string()),
+            })
+            .collect(),
+    })
+}
+
+fn broadcast(c: &mut Criterion) {
+    let message = players_update();
+    let mut group = c.benchmark_group("broadcast PlayersUpdate");
+
+    for encoding in [Encoding::Json, Encoding::MessagePack] {
+        print_allocations_per_socket(&format!("per socket/{encoding:?}"), || {
+            for _ in 0..STUDENTS {
+                let message = black_box(&message).clone();
+                black_box(encoding.encode(&message).unwrap());
+            }
+        });
+        // the message is serialized once and the frames of all the sockets share
+        // the encoded bytes, see `SharedMessage::frame`
+        let cloned = message.clone();
+        print_allocations_per_socket(&format!("shared/{encoding:?}"), || {
+            let shared = SharedMessage::new(black_box(cloned));
+            for _ in 0..STUDENTS {
+                black_box(shared.clone().frame(encoding).unwrap());
+            }
+        });
+
+        group.bench_function(
+            BenchmarkId::new("per socket", format!("{encoding:?}")),
+            |b| {
+                b.iter(|| {
+                    for _ in 0..STUDENTS {
+                        let message = black_box(&message).clone();
+                        black_box(encoding.encode(&message).unwrap());
+                    }
+                });
+            },
+        );
+
+        group.bench_function(BenchmarkId::new("shared", format!("{encoding:?}")), |b| {
+            b.iter(|| {
+                let shared = SharedMessage::new(black_box(&message).clone());
+                for _ in 0..STUDENTS {
+                    black_box(shared.clone().frame(encoding).unwrap());
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, broadcast);
+criterion_main!(benches);
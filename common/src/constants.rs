@@ -1,5 +1,3 @@
-use ratatui::style::Color;
-
 pub const DEFAULT_QUIZ_NAME: &str = "Quiz";
 pub const LOBBY_LOCKED_MSG: &str = "The lobby is locked";
 pub const PLAYER_NOT_IN_WAITING_LIST_MSG: &str = "Player not in waiting list";
@@ -14,16 +12,26 @@ pub const MINIMAL_QUESTION_HEIGHT: u16 = 30;
 pub const MINIMAL_QUESTION_WIDTH: u16 = 70;
 pub const MAXIMAL_CHOICE_LENGTH: usize = 200;
 pub const MAXIMAL_QUESTION_LENGTH: usize = 200;
-pub const MAXIMAL_CODE_LENGTH: usize = 400;
+pub const MAXIMAL_CODE_LENGTH: usize = 20_000;
+pub const MAXIMAL_CODE_LINES: usize = 500;
+pub const CODE_SCROLL_LINES: u16 = 10;
 pub const MAXIMAL_NAME_LENGTH: usize = 20;
+pub const MAXIMAL_STUDENT_ID_LENGTH: usize = 32;
 pub const TICK_PERIOD_MS: u64 = 500;
 pub const PLAYER_KICKED_MESSAGE: &str = "You were kicked by the teacher";
-pub const COLORS: [Color; 7] = [
-    Color::Red,
-    Color::Blue,
-    Color::Green,
-    Color::Yellow,
-    Color::Magenta,
-    Color::Cyan,
-    Color::Gray,
+pub const COLOR_AND_AVATAR_TAKEN_MSG: &str = "Color and avatar combination already taken";
+pub const INVALID_AVATAR_MSG: &str = "Invalid avatar";
+pub const PIN_REQUIRED_MSG: &str = "The server hosts more than one game, enter the PIN of yours";
+pub const UNKNOWN_PIN_MSG: &str = "There is no game with this PIN";
+pub const JOIN_CODE_REQUIRED_MSG: &str = "The game is protected, enter its join code";
+pub const WRONG_JOIN_CODE_MSG: &str = "Wrong join code";
+pub const TOO_MANY_WRONG_JOIN_CODES_MSG: &str = "Too many wrong join codes";
+pub const JOIN_NOT_APPROVED_MSG: &str = "The teacher did not let you join";
+pub const APPROVAL_CLOSED_MSG: &str =
+    "The game has started, the teacher lets players in only before it";
+pub const STUDENT_ID_REQUIRED_MSG: &str = "Enter your student ID";
+pub const UNKNOWN_STUDENT_ID_MSG: &str = "There is no student with this ID in the class";
+pub const STUDENT_ID_TAKEN_MSG: &str = "Somebody has already joined with this student ID";
+pub const AVATARS: [&str; 16] = [
+    "★", "♦", "♣", "♠", "♥", "●", "▲", "■", "🐱", "🐶", "🦊", "🐼", "🐸", "🐧", "🦉", "🐢",
 ];
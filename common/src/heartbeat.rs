@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_HEARTBEAT_INTERVAL_SECS: u64 = 5;
+pub const DEFAULT_HEARTBEAT_TIMEOUT_SECS: u64 = 15;
+
+/// How often the other side of the websocket is pinged and how long it may stay silent
+/// before it is considered gone (e.g. a laptop with a closed lid)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Heartbeat {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(DEFAULT_HEARTBEAT_INTERVAL_SECS),
+            timeout: Duration::from_secs(DEFAULT_HEARTBEAT_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl Heartbeat {
+    #[must_use]
+    pub fn from_secs(interval: u64, timeout: u64) -> Self {
+        Self {
+            interval: Duration::from_secs(interval),
+            timeout: Duration::from_secs(timeout),
+        }
+    }
+
+    /// Whether nothing was heard from the other side for too long
+    #[must_use]
+    pub fn is_stale(&self, last_heard: Instant) -> bool {
+        last_heard.elapsed() > self.timeout
+    }
+}
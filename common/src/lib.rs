@@ -1,6 +1,9 @@
 pub mod constants;
+pub mod heartbeat;
 pub mod messages;
 pub mod questions;
+pub mod syntax;
 pub mod terminal;
 pub mod test_macros;
 pub mod test_utils;
+pub mod tls;
@@ -0,0 +1,111 @@
+use std::sync::{Arc, OnceLock};
+
+use anyhow::bail;
+use serde::{de::DeserializeOwned, Serialize};
+use tungstenite::Message;
+
+use super::protocol::Capability;
+
+/// How the messages are written to the websocket frames, JSON goes in text frames,
+/// `MessagePack` in binary frames, so the receiving side can always tell them apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+    /// `MessagePack` is used only when both sides announced it, JSON otherwise
+    #[must_use]
+    pub fn negotiate(ours: &[Capability], theirs: &[Capability]) -> Self {
+        let both = |capability| ours.contains(&capability) && theirs.contains(&capability);
+
+        if both(Capability::MessagePack) {
+            Self::MessagePack
+        } else {
+            Self::Json
+        }
+    }
+
+    /// Encodes the message to a websocket frame
+    /// # Errors
+    /// - if the message cannot be serialized
+    pub fn encode<T: Serialize>(self, message: &T) -> anyhow::Result<Message> {
+        Ok(match self {
+            Self::Json => Message::text(serde_json::to_string(message)?),
+            // with the field names, so the `#[serde(default)]` fields can be left out
+            Self::MessagePack => Message::binary(rmp_serde::to_vec_named(message)?),
+        })
+    }
+}
+
+/// Decodes a message from a text (JSON) or binary (`MessagePack`) frame,
+/// whatever the encoding negotiated for the other direction is
+/// # Errors
+/// - if the frame is not a text or binary frame
+/// - if the frame does not contain a valid message
+pub fn decode<T: DeserializeOwned>(frame: &Message) -> anyhow::Result<T> {
+    match frame {
+        Message::Text(text) => Ok(serde_json::from_str(text)?),
+        Message::Binary(bytes) => Ok(rmp_serde::from_slice(bytes)?),
+        _ => bail!("Expected a text or binary frame"),
+    }
+}
+
+/// A message sent to many clients, encoded at most once for each encoding,
+/// the clones share the message and the encoded frames
+#[derive(Debug)]
+pub struct SharedMessage<T> {
+    message: Arc<T>,
+    json: Arc<OnceLock<Message>>,
+    message_pack: Arc<OnceLock<Message>>,
+}
+
+// derived `Clone` would require `T: Clone`, but only the `Arc`s are cloned
+impl<T> Clone for SharedMessage<T> {
+    fn clone(&self) -> Self {
+        Self {
+            message: Arc::clone(&self.message),
+            json: Arc::clone(&self.json),
+            message_pack: Arc::clone(&self.message_pack),
+        }
+    }
+}
+
+impl<T: Serialize> SharedMessage<T> {
+    #[must_use]
+    pub fn new(message: T) -> Self {
+        Self {
+            message: Arc::new(message),
+            json: Arc::default(),
+            message_pack: Arc::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    /// Returns the frame of the message in the given encoding,
+    /// the message is serialized only the first time.
+    ///
+    /// The payload of the frame is reference counted (`Bytes`), so all the sockets
+    /// share the encoded bytes, a call only increments the count.
+    /// # Errors
+    /// - if the message cannot be serialized
+    pub fn frame(&self, encoding: Encoding) -> anyhow::Result<Message> {
+        let cell = match encoding {
+            Encoding::Json => &self.json,
+            Encoding::MessagePack => &self.message_pack,
+        };
+
+        if let Some(frame) = cell.get() {
+            return Ok(frame.clone());
+        }
+
+        let frame = encoding.encode(&*self.message)?;
+        Ok(cell.get_or_init(|| frame).clone())
+    }
+}
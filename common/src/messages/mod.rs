@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 use self::network::{
-    AnswerSelected, JoinRequest, JoinResponse, NextQuestion, PlayersUpdate, QuestionEnded,
-    QuestionUpdate, ShowLeaderboard, TeacherDisconnected, TryJoinRequest, TryJoinResponse,
+    AnswerSelected, JoinRequest, JoinResponse, NextQuestion, PersonalReport, PlayersDelta,
+    PlayersUpdate, QuestionEnded, QuestionUpdate, ShowLeaderboard, TeacherDisconnected,
+    TryJoinRequest, TryJoinResponse, WaitingForApproval,
 };
 use actix::Message;
 
+pub mod encoding;
 pub mod network;
+pub mod protocol;
 pub mod status;
 
 /// The messages that can be sent over the websocket FROM the client TO server
@@ -23,6 +26,7 @@ pub enum ClientNetworkMessage {
 #[rtype(result = "anyhow::Result<()>")]
 pub enum ServerNetworkMessage {
     PlayersUpdate(PlayersUpdate),
+    PlayersDelta(PlayersDelta),
     NextQuestion(NextQuestion),
     QuestionUpdate(QuestionUpdate),
     QuestionEnded(QuestionEnded),
@@ -30,4 +34,6 @@ pub enum ServerNetworkMessage {
     TeacherDisconnected(TeacherDisconnected),
     JoinResponse(JoinResponse),
     TryJoinResponse(TryJoinResponse),
+    PersonalReport(PersonalReport),
+    WaitingForApproval(WaitingForApproval),
 }
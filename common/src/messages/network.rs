@@ -1,9 +1,13 @@
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Write,
     ops::Deref,
 };
 
-use crate::questions::{Question, QuestionCensored};
+use crate::messages::protocol::{Capability, ProtocolVersion};
+use crate::questions::{CodeBlock, Question, QuestionCensored};
+use crate::syntax::CustomSyntax;
+use crate::terminal::ui_theme::palette;
 use actix::{
     dev::{MessageResponse, OneshotSender},
     prelude::Message,
@@ -20,6 +24,38 @@ pub struct PlayerData {
     pub uuid: Uuid,
     pub nickname: String,
     pub color: Color,
+    /// index of the color in the palette, every viewer shows it in their own theme;
+    /// the older clients send only the color
+    #[serde(default)]
+    pub color_index: Option<usize>,
+    // optional emoji or glyph shown in front of the nickname, one of `AVATARS`
+    #[serde(default)]
+    pub avatar: Option<String>,
+}
+
+impl PlayerData {
+    /// Nickname with the avatar in front of it (if the player has one)
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        match &self.avatar {
+            Some(avatar) => format!("{avatar} {}", self.nickname),
+            None => self.nickname.clone(),
+        }
+    }
+
+    /// Color of the player in the viewer's palette
+    #[must_use]
+    pub fn display_color(&self) -> Color {
+        self.color_index
+            .and_then(|index| palette().colors.get(index))
+            .map_or(self.color, |named| named.color)
+    }
+
+    // players with the same color and avatar would look the same in the lists
+    #[must_use]
+    pub fn looks_like(&self, color: Color, avatar: Option<&str>) -> bool {
+        self.display_color() == color && self.avatar.as_deref() == avatar
+    }
 }
 
 // these models (structs) describe messages used in network communication between client - server - teacher
@@ -28,6 +64,36 @@ pub struct PlayerData {
 #[rtype(result = "TryJoinResponse")]
 pub struct TryJoinRequest {
     pub uuid: Uuid,
+    // the clients from before the handshake send neither of these
+    #[serde(default)]
+    pub protocol: ProtocolVersion,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// PIN of the game to join, needed only when the server hosts more than one
+    #[serde(default)]
+    pub pin: Option<String>,
+    /// Join code of the game, needed only when the teacher protected the game with one
+    #[serde(default)]
+    pub join_code: Option<String>,
+    /// Secret kept by the client, proves that it is the same player when the game
+    /// is resumed from a snapshot, never sent to the other players
+    #[serde(default)]
+    pub resume_token: Option<String>,
+}
+
+impl TryJoinRequest {
+    /// Request with the protocol version and the capabilities of this build
+    #[must_use]
+    pub fn new(uuid: Uuid) -> Self {
+        Self {
+            uuid,
+            protocol: ProtocolVersion::CURRENT,
+            capabilities: Capability::supported(),
+            pin: None,
+            join_code: None,
+            resume_token: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -41,6 +107,16 @@ pub struct TryJoinResponse {
     pub uuid: Uuid,
     pub can_join: CanJoin,
     pub quiz_name: String,
+    // syntaxes used in the quiz that the client might not know
+    #[serde(default)]
+    pub custom_syntaxes: Vec<CustomSyntax>,
+    #[serde(default)]
+    pub protocol: ProtocolVersion,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+    /// Whether the player has to send its student ID from the class roster in the `JoinRequest`
+    #[serde(default)]
+    pub student_id_required: bool,
 }
 
 impl<A, M> MessageResponse<A, M> for TryJoinResponse
@@ -78,6 +154,9 @@ where
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JoinRequest {
     pub player_data: PlayerData,
+    /// ID of the student from the class roster, never shown to the other players
+    #[serde(default)]
+    pub student_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Message)]
@@ -86,6 +165,34 @@ pub struct PlayersUpdate {
     pub players: Vec<PlayerData>,
 }
 
+/// Changes of the list of players since the previous update, the clients with
+/// the `PlayersDelta` capability get these instead of the whole `PlayersUpdate`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct PlayersDelta {
+    pub joined: Vec<PlayerData>,
+    pub left: Vec<Uuid>,
+}
+
+impl PlayersDelta {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.joined.is_empty() && self.left.is_empty()
+    }
+
+    /// Applies the changes to the list of players, a joined player who is already
+    /// on the list is replaced, a left player who is not on the list is ignored
+    pub fn apply(&self, players: &mut Vec<PlayerData>) {
+        players.retain(|player| !self.left.contains(&player.uuid));
+
+        for joined in &self.joined {
+            match players.iter_mut().find(|player| player.uuid == joined.uuid) {
+                Some(player) => *player = joined.clone(),
+                None => players.push(joined.clone()),
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Message, PartialEq)]
 #[rtype(result = "anyhow::Result<()>")]
 pub struct NextQuestion {
@@ -147,6 +254,155 @@ pub struct ShowLeaderboard {
     pub was_final_round: bool,
 }
 
+/// A choice in the `QuestionReport`, with its code so the report makes sense on its own
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChoiceReport {
+    pub text: String,
+    #[serde(default)]
+    pub code: Option<CodeBlock>,
+}
+
+/// How the player did in a single question, part of the `PersonalReport`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QuestionReport {
+    pub question_text: String,
+    #[serde(default)]
+    pub code_block: Option<CodeBlock>,
+    /// the selected choices, `None` if the player did not answer
+    pub selected_choices: Option<Vec<ChoiceReport>>,
+    pub correct_choices: Vec<ChoiceReport>,
+    pub is_correct: bool,
+    pub points: usize,
+}
+
+/// Summary of the whole game for a single player, sent when the game ends
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Message)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct PersonalReport {
+    pub quiz_name: String,
+    pub nickname: String,
+    pub rank: usize,
+    pub players_count: usize,
+    pub total_points: usize,
+    pub questions: Vec<QuestionReport>,
+}
+
+impl PersonalReport {
+    #[must_use]
+    pub fn correct_answers(&self) -> usize {
+        self.questions.iter().filter(|q| q.is_correct).count()
+    }
+
+    /// Percentage of the correctly answered questions
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn accuracy(&self) -> f64 {
+        if self.questions.is_empty() {
+            return 0.0;
+        }
+        100.0 * self.correct_answers() as f64 / self.questions.len() as f64
+    }
+
+    /// The report as a Markdown document, for revising after the game
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# {}\n\n\
+             - Player: {}\n\
+             - Rank: {} of {}\n\
+             - Points: {}\n\
+             - Accuracy: {:.0} % ({} of {} questions)\n",
+            self.quiz_name,
+            self.nickname,
+            self.rank,
+            self.players_count,
+            self.total_points,
+            self.accuracy(),
+            self.correct_answers(),
+            self.questions.len(),
+        );
+
+        for (index, question) in self.questions.iter().enumerate() {
+            let result = if question.is_correct {
+                "correct"
+            } else {
+                "wrong"
+            };
+            let answer = match &question.selected_choices {
+                Some(choices) if !choices.is_empty() => choices_markdown(choices),
+                _ => " (no answer)\n".to_string(),
+            };
+
+            // writing to a String cannot fail
+            let _ = write!(
+                markdown,
+                "\n## Question {} ({result}, {} points)\n\n{}\n\n",
+                index + 1,
+                question.points,
+                question.question_text.trim(),
+            );
+            if let Some(code_block) = &question.code_block {
+                markdown.push_str(&fenced_code(code_block, ""));
+                markdown.push('\n');
+            }
+            let _ = write!(
+                markdown,
+                "- Your answer:{answer}- Correct answer:{}",
+                choices_markdown(&question.correct_choices)
+            );
+        }
+
+        markdown
+    }
+}
+
+/// The choices joined on a single line, or as a nested list when some of them have code
+fn choices_markdown(choices: &[ChoiceReport]) -> String {
+    if choices.iter().all(|choice| choice.code.is_none()) {
+        let texts: Vec<&str> = choices.iter().map(|choice| choice.text.as_str()).collect();
+        return format!(" {}\n", texts.join(", "));
+    }
+
+    let mut markdown = "\n".to_string();
+    for choice in choices {
+        let _ = writeln!(markdown, "  - {}", choice.text.trim());
+        if let Some(code) = &choice.code {
+            markdown.push_str(&fenced_code(code, "    "));
+        }
+    }
+    markdown
+}
+
+/// The code as a fenced block tagged with its language, the fence is longer
+/// than any run of backticks inside the code
+fn fenced_code(code_block: &CodeBlock, indent: &str) -> String {
+    let longest_run = code_block
+        .code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    let mut markdown = format!("{indent}{fence}{}\n", code_block.language);
+    for line in code_block.code.trim_end().lines() {
+        if line.is_empty() {
+            markdown.push('\n');
+        } else {
+            let _ = writeln!(markdown, "{indent}{line}");
+        }
+    }
+    let _ = writeln!(markdown, "{indent}{fence}");
+    markdown
+}
+
+/// Sent instead of the `JoinResponse` when the teacher approves the joining players,
+/// the `JoinResponse` comes when the teacher decides
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WaitingForApproval {
+    pub uuid: Uuid,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TeacherDisconnected {
     // no data
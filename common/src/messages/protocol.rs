@@ -0,0 +1,105 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::ServerNetworkMessage;
+
+/// Version of the network protocol, a new minor version only adds to the protocol
+/// (the server still talks to the clients of the previous minor version),
+/// a new major version breaks it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// the version of this build
+    pub const CURRENT: Self = Self { major: 1, minor: 1 };
+
+    /// the clients from before the handshake do not send their version
+    pub const LEGACY: Self = Self { major: 1, minor: 0 };
+
+    /// Checks whether a server of this version can talk to a client of the given version,
+    /// the error is the reason for the student
+    /// # Errors
+    /// - if the client is from another major version
+    /// - if the client is older than the previous minor version
+    /// - if the client is newer than the server
+    pub fn accepts(self, client: Self) -> Result<(), String> {
+        if client.major == self.major
+            && client.minor <= self.minor
+            && client.minor + 1 >= self.minor
+        {
+            return Ok(());
+        }
+
+        if client > self {
+            return Err(format!(
+                "Your client uses protocol {client}, which is newer than the protocol {self} \
+                of the server. Please use a client with protocol {self}"
+            ));
+        }
+
+        Err(format!(
+            "Your client uses protocol {client}, but the server needs protocol {self}. \
+            Please update your client to protocol {self}"
+        ))
+    }
+}
+
+impl Default for ProtocolVersion {
+    fn default() -> Self {
+        Self::LEGACY
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Optional features of the protocol, the messages of a feature are sent
+/// only to the clients which announced it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// the client shows the personal report sent at the end of the game
+    PersonalReport,
+    /// the client can use binary `MessagePack` frames instead of the JSON text frames
+    MessagePack,
+    /// the client applies the `PlayersDelta` to its list of players
+    PlayersDelta,
+    /// the client shows that it waits until the teacher lets it join
+    JoinApproval,
+    /// a capability of a newer client or server
+    #[serde(other)]
+    Unknown,
+}
+
+impl Capability {
+    /// the capabilities of this build
+    #[must_use]
+    pub fn supported() -> Vec<Self> {
+        vec![
+            Self::PersonalReport,
+            Self::MessagePack,
+            Self::PlayersDelta,
+            Self::JoinApproval,
+        ]
+    }
+}
+
+impl ServerNetworkMessage {
+    /// The capability the client needs to understand the message, `None` if every client does
+    #[must_use]
+    pub fn required_capability(&self) -> Option<Capability> {
+        match self {
+            ServerNetworkMessage::PersonalReport(_) => Some(Capability::PersonalReport),
+            ServerNetworkMessage::PlayersDelta(_) => Some(Capability::PlayersDelta),
+            ServerNetworkMessage::WaitingForApproval(_) => Some(Capability::JoinApproval),
+            _ => None,
+        }
+    }
+}
@@ -8,4 +8,6 @@ pub enum ClientWebsocketStatus {
     CantSendMessage,
     SocketClosed,
     CloseFrameReceived(String),
+    /// nothing came from the server for longer than the heartbeat timeout
+    ServerTimeout,
 }
@@ -6,8 +6,11 @@ use std::path::Path;
 use syntect::parsing::{SyntaxReference, SyntaxSet};
 use uuid::Uuid;
 
+use crate::syntax::{syntax_sets, SyntaxSets};
+
 use crate::constants::{
-    DEFAULT_QUIZ_NAME, MAXIMAL_CHOICE_LENGTH, MAXIMAL_CODE_LENGTH, MAXIMAL_QUESTION_LENGTH,
+    DEFAULT_QUIZ_NAME, MAXIMAL_CHOICE_LENGTH, MAXIMAL_CODE_LENGTH, MAXIMAL_CODE_LINES,
+    MAXIMAL_QUESTION_LENGTH,
 };
 
 fn falsy() -> bool {
@@ -85,6 +88,7 @@ impl From<Question> for QuestionCensored {
                 .map(|choice| ChoiceCensored {
                     id: choice.id,
                     text: choice.text.clone(),
+                    code: choice.code.clone(),
                 })
                 .collect(),
         }
@@ -111,12 +115,124 @@ impl Question {
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(try_from = "CodeBlockRepr")]
 pub struct CodeBlock {
-    #[serde(deserialize_with = "deserialize_language")]
     pub language: String,
 
-    #[serde(deserialize_with = "deserialize_code_text")]
     pub code: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlight_lines: Vec<LineRange>,
+}
+
+/// The code block as written in the file, the highlighted lines are checked against the code
+#[derive(Deserialize)]
+struct CodeBlockRepr {
+    #[serde(deserialize_with = "deserialize_language")]
+    language: String,
+
+    #[serde(deserialize_with = "deserialize_code_text")]
+    code: String,
+
+    #[serde(default)]
+    highlight_lines: Vec<LineRange>,
+}
+
+impl TryFrom<CodeBlockRepr> for CodeBlock {
+    type Error = String;
+
+    fn try_from(value: CodeBlockRepr) -> Result<Self, Self::Error> {
+        let code_block = Self {
+            language: value.language,
+            code: value.code,
+            highlight_lines: value.highlight_lines,
+        };
+
+        let lines_count = code_block.lines_count();
+        if let Some(range) = code_block
+            .highlight_lines
+            .iter()
+            .find(|range| range.end > lines_count)
+        {
+            return Err(format!(
+                "Line range {}-{} is beyond the {lines_count} lines of the code",
+                range.start, range.end
+            ));
+        }
+
+        Ok(code_block)
+    }
+}
+
+impl CodeBlock {
+    #[must_use]
+    pub fn lines_count(&self) -> usize {
+        self.code.lines().count()
+    }
+
+    #[must_use]
+    pub fn is_line_highlighted(&self, line_number: usize) -> bool {
+        self.highlight_lines
+            .iter()
+            .any(|range| range.contains(line_number))
+    }
+}
+
+/// Range of lines (numbered from 1, inclusive) of a code block,
+/// written either as a single number `3` or as a range `7-9`
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(try_from = "LineRangeRepr", into = "LineRangeRepr")]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    #[must_use]
+    pub fn contains(&self, line_number: usize) -> bool {
+        (self.start..=self.end).contains(&line_number)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum LineRangeRepr {
+    Single(usize),
+    Range(String),
+}
+
+impl TryFrom<LineRangeRepr> for LineRange {
+    type Error = String;
+
+    fn try_from(value: LineRangeRepr) -> Result<Self, Self::Error> {
+        let (start, end) = match value {
+            LineRangeRepr::Single(line) => (line, line),
+            LineRangeRepr::Range(range) => {
+                let Some((start, end)) = range.split_once('-') else {
+                    return Err(format!("Invalid line range \"{range}\""));
+                };
+                let start = start.trim().parse::<usize>().map_err(|e| e.to_string())?;
+                let end = end.trim().parse::<usize>().map_err(|e| e.to_string())?;
+                (start, end)
+            }
+        };
+
+        if start == 0 || start > end {
+            return Err(format!("Invalid line range {start}-{end}"));
+        }
+
+        Ok(Self { start, end })
+    }
+}
+
+impl From<LineRange> for LineRangeRepr {
+    fn from(value: LineRange) -> Self {
+        if value.start == value.end {
+            Self::Single(value.start)
+        } else {
+            Self::Range(format!("{}-{}", value.start, value.end))
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -124,9 +240,11 @@ pub struct Choice {
     // we want to be able to identify the choices even when the client shuffles them
     #[serde(default = "new_uuid")]
     pub id: Uuid,
-    // by design, no syntax highlighting for the choices
+    // by design, no syntax highlighting for the choice text, snippets go to `code`
     pub text: String,
     #[serde(default)]
+    pub code: Option<CodeBlock>,
+    #[serde(default)]
     pub is_correct: bool,
 }
 
@@ -134,18 +252,26 @@ pub struct Choice {
 pub struct ChoiceCensored {
     pub id: Uuid,
     pub text: String,
+    #[serde(default)]
+    pub code: Option<CodeBlock>,
 }
 
-pub fn find_syntax(language: &str, code: Option<&str>) -> anyhow::Result<SyntaxReference> {
-    let ss = SyntaxSet::load_defaults_newlines();
-
-    if let Some(syntax) = ss.find_syntax_by_token(language) {
-        return Ok(syntax.clone());
+/// Finds the syntax of the language (or of the code if the language is not known),
+/// returns the syntax together with the set it belongs to
+/// # Errors
+/// - if no syntax is found
+pub fn find_syntax<'a>(
+    syntax_sets: &'a SyntaxSets,
+    language: &str,
+    code: Option<&str>,
+) -> anyhow::Result<(&'a SyntaxSet, &'a SyntaxReference)> {
+    if let Some(found) = syntax_sets.find(|ss| ss.find_syntax_by_token(language)) {
+        return Ok(found);
     }
 
     if let Some(code) = code {
-        if let Some(syntax) = ss.find_syntax_by_first_line(code) {
-            return Ok(syntax.clone());
+        if let Some(found) = syntax_sets.find(|ss| ss.find_syntax_by_first_line(code)) {
+            return Ok(found);
         }
     }
 
@@ -158,7 +284,7 @@ where
 {
     let language: String = Deserialize::deserialize(deserializer)?;
 
-    find_syntax(&language, None)
+    find_syntax(&syntax_sets(), &language, None)
         .map(|_| language)
         .map_err(|err| de::Error::custom(err.to_string()))
 }
@@ -175,6 +301,12 @@ where
         )));
     }
 
+    if text.lines().count() > MAXIMAL_CODE_LINES {
+        return Err(de::Error::custom(format!(
+            "Code must have at most {MAXIMAL_CODE_LINES} lines"
+        )));
+    }
+
     Ok(text)
 }
 
@@ -248,4 +380,32 @@ impl QuestionSet {
             quiz_name: DEFAULT_QUIZ_NAME.to_owned(),
         }
     }
+
+    /// Names of the syntaxes used by the code in the questions and in their choices
+    #[must_use]
+    pub fn syntax_names(&self) -> Vec<String> {
+        let syntax_sets = syntax_sets();
+        let mut names: Vec<String> = Vec::new();
+
+        let code_blocks = self.questions.iter().flat_map(|question| {
+            question.code_block.iter().chain(
+                question
+                    .choices
+                    .iter()
+                    .filter_map(|choice| choice.code.as_ref()),
+            )
+        });
+
+        for block in code_blocks {
+            let Ok((_, syntax)) = find_syntax(&syntax_sets, &block.language, Some(&block.code))
+            else {
+                continue;
+            };
+            if !names.contains(&syntax.name) {
+                names.push(syntax.name.clone());
+            }
+        }
+
+        names
+    }
 }
@@ -0,0 +1,187 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, OnceLock, PoisonError, RwLock},
+};
+
+use anyhow::Context;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::{SyntaxDefinition, SyntaxReference, SyntaxSet, SyntaxSetBuilder};
+
+const SYNTAX_EXTENSION: &str = "sublime-syntax";
+
+/// Syntax definition that is not part of the syntect's defaults,
+/// kept together with its source, so the server can send it to the clients
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CustomSyntax {
+    pub name: String,
+    /// content of the `.sublime-syntax` file
+    pub definition: String,
+}
+
+/// The default syntaxes and the custom ones, the custom syntaxes are kept in a separate set,
+/// because rebuilding the whole default set with them takes a long time
+#[derive(Debug, Clone)]
+pub struct SyntaxSets {
+    pub default: Arc<SyntaxSet>,
+    pub custom: Arc<SyntaxSet>,
+}
+
+impl SyntaxSets {
+    /// Looks for a syntax in the custom set first and then in the default one,
+    /// returns the syntax together with the set it belongs to (needed for highlighting)
+    pub fn find<'a>(
+        &'a self,
+        find: impl Fn(&'a SyntaxSet) -> Option<&'a SyntaxReference>,
+    ) -> Option<(&'a SyntaxSet, &'a SyntaxReference)> {
+        [&*self.custom, &*self.default]
+            .into_iter()
+            .find_map(|ss| find(ss).map(|syntax| (ss, syntax)))
+    }
+}
+
+// loading the default syntaxes and themes takes a while, so they are loaded only once
+// (the first time they are needed) and then shared by the whole application,
+// custom syntaxes and themes can be added later and replace the shared sets
+struct Registry {
+    syntax_sets: SyntaxSets,
+    theme_set: Arc<ThemeSet>,
+    custom_syntaxes: Vec<CustomSyntax>,
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        RwLock::new(Registry {
+            syntax_sets: SyntaxSets {
+                default: Arc::new(SyntaxSet::load_defaults_newlines()),
+                custom: Arc::new(SyntaxSet::default()),
+            },
+            theme_set: Arc::new(ThemeSet::load_defaults()),
+            custom_syntaxes: Vec::new(),
+        })
+    })
+}
+
+#[must_use]
+pub fn syntax_sets() -> SyntaxSets {
+    let registry = registry().read().unwrap_or_else(PoisonError::into_inner);
+    registry.syntax_sets.clone()
+}
+
+#[must_use]
+pub fn theme_set() -> Arc<ThemeSet> {
+    let registry = registry().read().unwrap_or_else(PoisonError::into_inner);
+    Arc::clone(&registry.theme_set)
+}
+
+/// Custom syntaxes with the given names (the ones that are not loaded are skipped)
+#[must_use]
+pub fn custom_syntaxes<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<CustomSyntax> {
+    let registry = registry().read().unwrap_or_else(PoisonError::into_inner);
+    let mut syntaxes: Vec<CustomSyntax> = Vec::new();
+
+    for name in names {
+        let Some(syntax) = registry.custom_syntaxes.iter().find(|s| s.name == name) else {
+            continue;
+        };
+        if !syntaxes.contains(syntax) {
+            syntaxes.push(syntax.clone());
+        }
+    }
+
+    syntaxes
+}
+
+/// Adds the custom syntaxes to the shared custom syntax set,
+/// syntaxes with an already known name are skipped
+/// # Errors
+/// - if some of the definitions cannot be parsed
+pub fn add_custom_syntaxes(syntaxes: Vec<CustomSyntax>) -> anyhow::Result<()> {
+    let mut registry = registry().write().unwrap_or_else(PoisonError::into_inner);
+
+    let mut new_syntaxes = Vec::new();
+    for syntax in syntaxes {
+        if registry
+            .custom_syntaxes
+            .iter()
+            .chain(&new_syntaxes)
+            .any(|s: &CustomSyntax| s.name == syntax.name)
+        {
+            continue;
+        }
+
+        SyntaxDefinition::load_from_str(&syntax.definition, true, None)
+            .with_context(|| format!("Invalid syntax definition \"{}\"", syntax.name))?;
+        new_syntaxes.push(syntax);
+    }
+
+    if new_syntaxes.is_empty() {
+        return Ok(());
+    }
+
+    // the custom set is small, so it is simply built again with all the custom syntaxes
+    let mut builder = SyntaxSetBuilder::new();
+    for syntax in registry.custom_syntaxes.iter().chain(&new_syntaxes) {
+        builder.add(SyntaxDefinition::load_from_str(
+            &syntax.definition,
+            true,
+            None,
+        )?);
+    }
+    registry.syntax_sets.custom = Arc::new(builder.build());
+
+    debug!(
+        "Added custom syntaxes: {:?}",
+        new_syntaxes.iter().map(|s| &s.name).collect::<Vec<_>>()
+    );
+    registry.custom_syntaxes.extend(new_syntaxes);
+
+    Ok(())
+}
+
+/// Loads all `.sublime-syntax` and `.tmTheme` files from the given directory,
+/// themes are named after their file names (without the extension)
+/// # Errors
+/// - if the directory cannot be read
+/// - if some of the files is not a valid syntax definition or theme
+pub fn load_custom_dir(dir: &Path) -> anyhow::Result<()> {
+    let mut syntaxes = Vec::new();
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("Cannot read syntax directory {}", dir.display()))?
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(fs::DirEntry::path);
+
+    for entry in entries {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SYNTAX_EXTENSION) {
+            continue;
+        }
+
+        let definition = fs::read_to_string(&path)
+            .with_context(|| format!("Cannot read syntax file {}", path.display()))?;
+        let name = SyntaxDefinition::load_from_str(&definition, true, None)
+            .with_context(|| format!("Invalid syntax file {}", path.display()))?
+            .name;
+
+        info!("Loaded custom syntax \"{name}\" from {}", path.display());
+        syntaxes.push(CustomSyntax { name, definition });
+    }
+
+    add_custom_syntaxes(syntaxes)?;
+
+    let custom_themes = ThemeSet::load_from_folder(dir)
+        .with_context(|| format!("Cannot load themes from {}", dir.display()))?;
+    for name in custom_themes.themes.keys() {
+        info!("Loaded custom theme \"{name}\" from {}", dir.display());
+    }
+
+    let mut registry = registry().write().unwrap_or_else(PoisonError::into_inner);
+    let mut themes = registry.theme_set.themes.clone();
+    themes.extend(custom_themes.themes);
+    registry.theme_set = Arc::new(ThemeSet { themes });
+
+    Ok(())
+}
@@ -23,16 +23,14 @@ where
         tokio::select! {
             maybe_event = crossterm_event => {
                 match maybe_event {
-                    Some(Ok(Event::Key(key))) => {
-                        if key.kind == KeyEventKind::Press {
-                            // we are in raw mode, so we need to handle this ourselves
-                            if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
-                                term.send(Stop).await??;
-                                return Ok(())
-                            }
-
-                            term.send(KeyPress { key_code: key.code}).await??;
+                    Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                        // we are in raw mode, so we need to handle this ourselves
+                        if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
+                            term.send(Stop).await??;
+                            return Ok(())
                         }
+
+                        term.send(KeyPress { key_code: key.code}).await??;
                     }
                     Some(Ok(Event::Resize(_,_))) => {
                         term.send(Redraw).await??;
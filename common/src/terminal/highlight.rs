@@ -1,18 +1,21 @@
-use clap::ValueEnum;
+use anyhow::bail;
 use ratatui::style::{Color as RatatuiColor, Modifier, Style as RatatuiStyle};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
-use serde::Serialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::{self, Display};
+use std::str::FromStr;
 use syntect::easy::HighlightLines;
-use syntect::highlighting::ThemeSet;
 use syntect::highlighting::{Color, FontStyle, Style};
-use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
+use uuid::Uuid;
 
 use crate::questions::{find_syntax, CodeBlock};
+use crate::syntax::{syntax_sets, theme_set};
+use crate::terminal::ui_theme::palette;
 
-#[derive(Clone, Copy, ValueEnum, Serialize, Default, PartialEq)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum Theme {
     #[default]
     Default,
@@ -23,11 +26,26 @@ pub enum Theme {
     InspiredGitHub,
     SolarizedDark,
     SolarizedLight,
+    /// theme loaded from a `.tmTheme` file, named after the file
+    Custom(String),
 }
 
-impl From<Theme> for &str {
-    fn from(value: Theme) -> Self {
-        match value {
+const BUILT_IN_THEMES: [Theme; 8] = [
+    Theme::Default,
+    Theme::EightiesDark,
+    Theme::MochaDark,
+    Theme::OceanDark,
+    Theme::OceanLight,
+    Theme::InspiredGitHub,
+    Theme::SolarizedDark,
+    Theme::SolarizedLight,
+];
+
+impl Theme {
+    // name of the theme in the theme set
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
             Theme::Default | Theme::EightiesDark => "base16-eighties.dark",
             Theme::MochaDark => "base16-mocha.dark",
             Theme::OceanDark => "base16-ocean.dark",
@@ -35,54 +53,222 @@ impl From<Theme> for &str {
             Theme::InspiredGitHub => "InspiredGitHub",
             Theme::SolarizedDark => "Solarized (dark)",
             Theme::SolarizedLight => "Solarized (light)",
+            Theme::Custom(name) => name,
+        }
+    }
+
+    /// Checks that the theme is available (custom themes have to be loaded first)
+    /// # Errors
+    /// - if the theme is not loaded
+    pub fn ensure_loaded(&self) -> anyhow::Result<()> {
+        if !theme_set().themes.contains_key(self.name()) {
+            bail!(
+                "Unknown syntax theme \"{self}\", use one of the built-in themes ({}) \
+                or a name of a .tmTheme file from the syntax directory",
+                BUILT_IN_THEMES.map(|theme| theme.to_string()).join(", ")
+            );
         }
+
+        Ok(())
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Theme::Default => "default",
+            Theme::EightiesDark => "eighties-dark",
+            Theme::MochaDark => "mocha-dark",
+            Theme::OceanDark => "ocean-dark",
+            Theme::OceanLight => "ocean-light",
+            Theme::InspiredGitHub => "inspired-git-hub",
+            Theme::SolarizedDark => "solarized-dark",
+            Theme::SolarizedLight => "solarized-light",
+            Theme::Custom(name) => name,
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Theme {
+    type Err = Infallible;
+
+    // anything that is not a built-in theme is considered to be a custom theme
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(BUILT_IN_THEMES
+            .into_iter()
+            .find(|theme| theme.to_string() == s)
+            .unwrap_or_else(|| Theme::Custom(s.to_owned())))
     }
 }
 
-// NICE TO HAVE: store the result of this function
-// in the state so it doesn't get called with every redraw
+/// Highlighted lines of a code block, the paragraph for the screen is built only from
+/// the visible lines and borrows their spans, so the cached code is never copied
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HighlightedCode {
+    lines: Vec<Line<'static>>,
+    style: RatatuiStyle,
+}
+
+impl HighlightedCode {
+    #[must_use]
+    pub fn lines(&self) -> &[Line<'static>] {
+        &self.lines
+    }
+
+    /// Paragraph with `height` lines of the code starting with the line `scroll`
+    #[must_use]
+    pub fn paragraph(&self, scroll: usize, height: usize) -> Paragraph<'_> {
+        let visible: Vec<Line> = self
+            .lines
+            .iter()
+            .skip(scroll)
+            .take(height)
+            .map(|line| {
+                let spans: Vec<Span> = line
+                    .spans
+                    .iter()
+                    .map(|span| Span::styled(span.content.as_ref(), span.style))
+                    .collect();
+                Line {
+                    spans,
+                    alignment: line.alignment,
+                }
+            })
+            .collect();
+
+        Paragraph::new(visible).style(self.style)
+    }
+
+    /// Paragraph with the whole code, which takes the highlighted lines over
+    #[must_use]
+    pub fn into_paragraph(self) -> Paragraph<'static> {
+        Paragraph::new(self.lines).style(self.style)
+    }
+}
+
+/// Highlighted code of one question (the code block of the question and the code
+/// snippets of its choices), kept in the terminal state, so the code is highlighted
+/// only once and not with every redraw
+#[derive(Debug, Default, Clone)]
+pub struct CodeCache {
+    code_block: Option<HighlightedCode>,
+    choices: HashMap<Uuid, HighlightedCode>,
+}
+
+impl CodeCache {
+    // highlighted code block of the question, with line numbers
+    pub fn code_block(&mut self, block: &CodeBlock, syntax_theme: &Theme) -> &HighlightedCode {
+        self.code_block
+            .get_or_insert_with(|| highlight_code(block, syntax_theme, true))
+    }
+
+    // highlights the code snippets of the choices that are not highlighted yet
+    pub fn highlight_choices<'a>(
+        &mut self,
+        choices: impl IntoIterator<Item = (Uuid, &'a CodeBlock)>,
+        syntax_theme: &Theme,
+    ) {
+        for (uuid, block) in choices {
+            self.choices
+                .entry(uuid)
+                .or_insert_with(|| highlight_code(block, syntax_theme, false));
+        }
+    }
+
+    #[must_use]
+    pub fn choice(&self, uuid: &Uuid) -> Option<&HighlightedCode> {
+        self.choices.get(uuid)
+    }
+}
+
+#[must_use]
+pub fn code_block(block: &CodeBlock, syntax_theme: &Theme) -> Paragraph<'static> {
+    highlight_code(block, syntax_theme, false).into_paragraph()
+}
+
+/// Same as `code_block`, but with a gutter containing line numbers
+/// and with the lines from `highlight_lines` emphasized
 #[must_use]
-pub fn code_block(block: &CodeBlock, syntax_theme: Theme) -> Paragraph {
-    let ss = SyntaxSet::load_defaults_newlines();
-    let ts = ThemeSet::load_defaults();
+pub fn code_block_with_line_numbers(block: &CodeBlock, syntax_theme: &Theme) -> Paragraph<'static> {
+    highlight_code(block, syntax_theme, true).into_paragraph()
+}
+
+fn highlight_code(block: &CodeBlock, syntax_theme: &Theme, line_numbers: bool) -> HighlightedCode {
+    let syntax_sets = syntax_sets();
+    let ts = theme_set();
 
-    let use_bg_color = syntax_theme != Theme::Default;
+    let use_bg_color = *syntax_theme != Theme::Default;
 
-    let syntax = match find_syntax(&block.language, Some(&block.code)) {
-        Ok(syntax) => syntax,                          // should always happen
-        Err(_) => ss.find_syntax_plain_text().clone(), // fallback if something does terribly wrong
+    let (ss, syntax) = match find_syntax(&syntax_sets, &block.language, Some(&block.code)) {
+        Ok(found) => found, // should always happen
+        // fallback if something does terribly wrong
+        Err(_) => (
+            &*syntax_sets.default,
+            syntax_sets.default.find_syntax_plain_text(),
+        ),
     };
 
-    let theme = &ts.themes[syntax_theme.into()];
-    let mut highlighter = HighlightLines::new(&syntax, theme);
+    // custom themes are checked at startup, the fallback is just to be sure
+    let theme = ts
+        .themes
+        .get(syntax_theme.name())
+        .unwrap_or_else(|| &ts.themes[Theme::Default.name()]);
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    let gutter_width = block.lines_count().to_string().len();
 
     let mut lines: Vec<Line> = Vec::new();
 
-    for line in LinesWithEndings::from(&block.code) {
-        let Ok(ranges) = highlighter.highlight_line(line, &ss) else {
-            return Paragraph::new("Unable to highlight code block");
+    for (index, line) in LinesWithEndings::from(&block.code).enumerate() {
+        let Ok(ranges) = highlighter.highlight_line(line, ss) else {
+            return HighlightedCode {
+                lines: vec![Line::from("Unable to highlight code block")],
+                style: RatatuiStyle::default(),
+            };
         };
 
-        let spans = ranges
-            .into_iter()
-            .map(|range| range_to_span(range, use_bg_color))
-            .collect::<Vec<_>>();
-        let line = Line::from(spans);
+        let line_number = index + 1;
+        let is_highlighted = line_numbers && block.is_line_highlighted(line_number);
+
+        let mut spans = Vec::with_capacity(ranges.len() + 1);
+        if line_numbers {
+            spans.push(gutter_span(line_number, gutter_width, is_highlighted));
+        }
+        spans.extend(
+            ranges
+                .into_iter()
+                .map(|range| range_to_span(range, use_bg_color && !is_highlighted)),
+        );
+
+        let mut line = Line::from(spans);
+        if is_highlighted {
+            line.patch_style(palette().highlighted_line);
+        }
         lines.push(line);
     }
 
-    let highlighted_paragraph = Paragraph::new(lines);
-    if !use_bg_color {
-        return highlighted_paragraph;
-    }
+    let background = translate_color(theme.settings.background).filter(|_| use_bg_color);
+    let style = match background {
+        Some(color) => RatatuiStyle::default().bg(color),
+        None => RatatuiStyle::default(),
+    };
+
+    HighlightedCode { lines, style }
+}
 
-    let Some(translated_color) = translate_color(theme.settings.background) else {
-        return highlighted_paragraph;
+fn gutter_span(line_number: usize, width: usize, highlighted: bool) -> Span<'static> {
+    let marker = if highlighted { '▶' } else { '│' };
+    let style = if highlighted {
+        RatatuiStyle::default().add_modifier(Modifier::BOLD)
+    } else {
+        palette().line_number
     };
-    highlighted_paragraph.style(RatatuiStyle::default().bg(translated_color))
+
+    Span::styled(format!("{line_number:>width$} {marker} "), style)
 }
 
-fn range_to_span((style, content): (Style, &str), use_bg_color: bool) -> Span {
+fn range_to_span((style, content): (Style, &str), use_bg_color: bool) -> Span<'static> {
     let bg = if use_bg_color {
         translate_color(Some(style.background))
     } else {
@@ -90,7 +276,7 @@ fn range_to_span((style, content): (Style, &str), use_bg_color: bool) -> Span {
     };
 
     Span::styled(
-        content,
+        content.to_owned(),
         RatatuiStyle {
             fg: translate_color(Some(style.foreground)),
             bg,
@@ -1,5 +1,7 @@
 use crossterm::event::KeyCode;
 
+use crate::constants::CODE_SCROLL_LINES;
+
 pub fn move_in_list(selected: &mut usize, list_size: usize, key_code: KeyCode) -> bool {
     match key_code {
         KeyCode::Down | KeyCode::Char('s') => {
@@ -17,3 +19,20 @@ pub fn move_in_list(selected: &mut usize, list_size: usize, key_code: KeyCode) -
         _ => false,
     }
 }
+
+// scroll the code block with PageUp/PageDown, the scroll gets clamped
+// to the visible part of the code when rendering
+pub fn scroll_code(scroll: &mut u16, lines_count: usize, key_code: KeyCode) -> bool {
+    let max_scroll = u16::try_from(lines_count.saturating_sub(1)).unwrap_or(u16::MAX);
+    match key_code {
+        KeyCode::PageDown => {
+            *scroll = scroll.saturating_add(CODE_SCROLL_LINES).min(max_scroll);
+            true
+        }
+        KeyCode::PageUp => {
+            *scroll = scroll.saturating_sub(CODE_SCROLL_LINES);
+            true
+        }
+        _ => false,
+    }
+}
@@ -4,4 +4,5 @@ pub mod highlight;
 pub mod input_utils;
 pub mod messages;
 pub mod render;
+pub mod ui_theme;
 pub mod widgets;
@@ -5,14 +5,18 @@ use ratatui::{
     widgets::{block::Title, Block, BorderType, Borders, Padding, Paragraph, Wrap},
 };
 
+use crate::terminal::ui_theme::palette;
+
 #[must_use]
 pub fn get_outer_block(name: &str) -> Block<'_> {
     let title = Title::from(" Clihoot: ".to_owned() + name + " ");
     let block = Block::default()
         .title(title)
-        .title_style(style::Style::default().bold())
+        .title_style(palette().title.bold())
         .borders(Borders::ALL)
         .border_type(BorderType::Thick)
+        .border_style(palette().border)
+        .style(palette().screen)
         .padding(Padding::new(2, 2, 1, 1));
     block
 }
@@ -21,8 +25,9 @@ pub fn get_outer_block(name: &str) -> Block<'_> {
 pub fn get_inner_block(title: &str) -> Block<'_> {
     let block = Block::new()
         .borders(Borders::TOP)
+        .border_style(palette().border)
         .title(title)
-        .title_style(style::Style::default().bold())
+        .title_style(palette().title.bold())
         .title_alignment(Alignment::Center)
         .padding(Padding::new(1, 1, 1, 1));
     block
@@ -30,7 +35,9 @@ pub fn get_inner_block(title: &str) -> Block<'_> {
 
 #[must_use]
 pub fn get_bordered_block() -> Block<'static> {
-    let block = Block::default().borders(Borders::ALL);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(palette().border);
     block
 }
 
@@ -72,12 +72,12 @@ pub fn waiting(
     let items: Vec<_> = players
         .iter()
         .map(|player| {
-            let mut item = Line::raw(player.nickname.to_string());
+            let mut item = Line::raw(player.display_name());
             if player.uuid == player_uuid.unwrap_or(Uuid::nil()) {
                 item.patch_style(get_player_style());
             }
 
-            ListItem::new(item).fg(player.color)
+            ListItem::new(item).fg(player.display_color())
         })
         .collect();
 
@@ -122,7 +122,10 @@ pub fn results(
             quiz_name,
         );
 
-        let paragraph = get_centered_paragraph("Great job everyone!", Block::default());
+        let paragraph = get_centered_paragraph(
+            "Great job everyone! Press Enter to continue",
+            Block::default(),
+        );
         frame.render_widget(paragraph, layout[2]);
     }
 
@@ -130,7 +133,7 @@ pub fn results(
         .players
         .iter()
         .map(|(player, score)| {
-            let mut name_cell = Line::raw(player.nickname.to_string()).alignment(Alignment::Left);
+            let mut name_cell = Line::raw(player.display_name()).alignment(Alignment::Left);
             let mut score_cell = Line::raw(format!("{score}")).alignment(Alignment::Center);
             if player.uuid == player_uuid.unwrap_or(Uuid::nil()) {
                 name_cell.patch_style(get_player_style());
@@ -138,7 +141,7 @@ pub fn results(
             }
             let row = vec![name_cell, score_cell];
 
-            Row::new(row).style(style::Style::default().fg(player.color))
+            Row::new(row).style(style::Style::default().fg(player.display_color()))
         })
         .collect();
 
@@ -1,13 +1,16 @@
+use figlet_rs::FIGfont;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     prelude::*,
     style::{self},
     widgets::{
         block::{Position, Title},
-        Block, BorderType, Clear, Padding, Row, Table,
+        Block, BorderType, Clear, Padding, Paragraph, Row, Table,
     },
 };
 
+use crate::terminal::ui_theme::palette;
+
 use super::{get_bordered_block, get_centered_paragraph};
 
 fn popup_block<'a>(title: &'a str, bottom_title: &'a str) -> Block<'a> {
@@ -22,7 +25,7 @@ fn popup_block<'a>(title: &'a str, bottom_title: &'a str) -> Block<'a> {
         .title(bottom_title)
         .border_type(BorderType::Thick)
         .padding(Padding::new(1, 1, 1, 1))
-        .style(Style::default().bg(Color::DarkGray));
+        .style(palette().popup);
     popup_block
 }
 
@@ -62,6 +65,66 @@ pub fn confirm(frame: &mut Frame, message: &str) {
     frame.render_widget(paragraph, area);
 }
 
+pub fn notice(frame: &mut Frame, title: &str, message: &str) {
+    let popup_block = popup_block(title, " Press any key to close ");
+
+    let area = centered_rect(frame.size(), 60, 30);
+
+    let paragraph = get_centered_paragraph(message, popup_block);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// Shows the text in big letters in the top right corner, e.g. a code the students copy
+/// from the projector, in plain letters when the big ones do not fit
+pub fn banner(frame: &mut Frame, title: &str, text: &str) {
+    let area = frame.size();
+
+    let figure = FIGfont::standard()
+        .ok()
+        .and_then(|font| font.convert(text).map(|figure| figure.to_string()))
+        .filter(|figure| {
+            let width = figure.lines().map(|line| line.chars().count()).max();
+            let height = figure.lines().count();
+            // the players list must stay readable next to it
+            width.is_some_and(|width| width + 6 < usize::from(area.width / 2))
+                && height + 4 < usize::from(area.height / 2)
+        });
+    let content = figure.unwrap_or_else(|| text.to_owned());
+
+    let width = content
+        .lines()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = u16::try_from(width + 4)
+        .unwrap_or(u16::MAX)
+        .max(u16::try_from(title.chars().count() + 4).unwrap_or(u16::MAX))
+        .min(area.width.saturating_sub(2));
+    let height = u16::try_from(content.lines().count() + 2)
+        .unwrap_or(u16::MAX)
+        .min(area.height.saturating_sub(2));
+
+    let area = Rect::new(
+        area.right().saturating_sub(width + 2),
+        area.y + 2,
+        width,
+        height,
+    );
+
+    let block = get_bordered_block()
+        .title(Title::from(title).alignment(Alignment::Center))
+        .border_type(BorderType::Thick)
+        .style(palette().popup);
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .alignment(Alignment::Center);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
 // source: https://ratatui.rs/how-to/layout/center-a-rect/
 fn centered_rect(r: Rect, percent_x: u16, percent_y: u16) -> Rect {
     let popup_layout = Layout::default()
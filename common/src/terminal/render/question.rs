@@ -1,12 +1,12 @@
-use crate::terminal::highlight;
-use crate::{constants::COLORS, messages::network::QuestionEnded, questions::CodeBlock};
 use crate::{
     messages::network::NextQuestion,
     terminal::{
-        highlight::Theme,
+        highlight::{CodeCache, Theme},
+        ui_theme::palette,
         widgets::choice::{Grid, Selector, SelectorState},
     },
 };
+use crate::{messages::network::QuestionEnded, questions::CodeBlock};
 use log::{debug, trace};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -68,7 +68,13 @@ fn time(
     frame.render_widget(answered_paragraph, counts_layout[2]);
 }
 
-fn question_layout(frame: &mut Frame, title: &str, text: &str, quiz_name: &str) -> Rc<[Rect]> {
+fn question_layout(
+    frame: &mut Frame,
+    title: &str,
+    text: &str,
+    quiz_name: &str,
+    choices_have_code: bool,
+) -> Rc<[Rect]> {
     let outer_block = get_outer_block(quiz_name);
 
     let inner_block = get_inner_block(title);
@@ -78,12 +84,22 @@ fn question_layout(frame: &mut Frame, title: &str, text: &str, quiz_name: &str)
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(vec![
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Percentage(40),
-            Constraint::Percentage(40),
-        ])
+        .constraints(if choices_have_code {
+            // code snippets in the choices need more space than plain text
+            vec![
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Percentage(25),
+                Constraint::Percentage(55),
+            ]
+        } else {
+            vec![
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Percentage(40),
+                Constraint::Percentage(40),
+            ]
+        })
         .split(content_space);
 
     let paragraph =
@@ -97,9 +113,38 @@ fn question_layout(frame: &mut Frame, title: &str, text: &str, quiz_name: &str)
     layout
 }
 
-fn code(frame: &mut Frame, code_block: &CodeBlock, theme: Theme, layout: &[Rect]) {
-    let code_paragraph = highlight::code_block(code_block, theme)
-        .block(get_bordered_block().padding(Padding::new(1, 1, 1, 1)));
+fn code(
+    frame: &mut Frame,
+    code_block: &CodeBlock,
+    theme: &Theme,
+    scroll: &mut u16,
+    code_cache: &mut CodeCache,
+    layout: &[Rect],
+) {
+    let mut block = get_bordered_block().padding(Padding::new(1, 1, 1, 1));
+
+    let lines_count = u16::try_from(code_block.lines_count()).unwrap_or(u16::MAX);
+    let visible_lines = block.inner(layout[2]).height;
+
+    // do not scroll past the last line of the code
+    *scroll = (*scroll).min(lines_count.saturating_sub(visible_lines));
+    let scroll = *scroll;
+
+    if lines_count > visible_lines {
+        let last_visible = (scroll + visible_lines).min(lines_count);
+        let title = Title::from(format!(
+            " lines {}-{last_visible}/{lines_count} (PgUp/PgDn) ",
+            scroll + 1
+        ))
+        .alignment(Alignment::Right)
+        .position(Position::Bottom);
+        block = block.title(title);
+    }
+
+    let code_paragraph = code_cache
+        .code_block(code_block, theme)
+        .paragraph(usize::from(scroll), usize::from(visible_lines))
+        .block(block);
     frame.render_widget(code_paragraph, layout[2]);
 }
 
@@ -112,7 +157,9 @@ pub fn question(
     choice_selector_state: Option<&mut SelectorState>,
     time_from_start: usize,
     answered: bool,
-    theme: Theme,
+    theme: &Theme,
+    code_scroll: &mut u16,
+    code_cache: &mut CodeCache,
     quiz_name: &str,
 ) {
     let layout = question_layout(
@@ -128,6 +175,7 @@ pub fn question(
             question.question.text.as_str()
         },
         quiz_name,
+        choice_grid.has_code(),
     );
 
     time(
@@ -143,7 +191,7 @@ pub fn question(
     }
 
     if let Some(code_block) = &question.question.code_block {
-        code(frame, code_block, theme, &layout);
+        code(frame, code_block, theme, code_scroll, code_cache, &layout);
     }
 
     if time_from_start < question.show_choices_after {
@@ -165,30 +213,39 @@ pub fn question(
 
     let mut items = choice_grid.clone().items();
 
+    let colors = palette().colors;
     let mut color_index = 0;
     for (row, items) in items.iter_mut().enumerate() {
-        for (col, mut items) in items.iter_mut().enumerate() {
+        for (col, item) in items.iter_mut().enumerate() {
             trace!("row: {row}, col: {col}");
-            match &mut items {
-                Some(item) => {
-                    color_index += 1;
-
-                    item.set_style_ref(
-                        style::Style::default().fg(COLORS[color_index % COLORS.len()]),
-                    );
-                }
-                None => {}
+            if let Some(item) = item {
+                color_index += 1;
+
+                item.set_style_ref(
+                    style::Style::default().fg(colors[color_index % colors.len()].color),
+                );
             }
         }
     }
 
     *choice_grid = Grid::new(items);
 
+    code_cache.highlight_choices(
+        question
+            .question
+            .choices
+            .iter()
+            .filter_map(|choice| Some((choice.id, choice.code.as_ref()?))),
+        theme,
+    );
+
     let choice_selector = Selector::new(choice_grid.clone());
     let choice_selector = choice_selector
         .vertical_gap(1)
         .horizontal_gap(2)
-        .current_item_style(Style::default().bg(Color::White))
+        .syntax_theme(theme.clone())
+        .code_cache(code_cache)
+        .current_item_style(palette().current_item)
         .selected_item_block(
             Block::default()
                 .borders(Borders::ALL)
@@ -204,67 +261,89 @@ pub fn question(
     }
 }
 
-pub fn answers(frame: &mut Frame, question: &QuestionEnded, theme: Theme, quiz_name: &str) {
+pub fn answers(
+    frame: &mut Frame,
+    question: &QuestionEnded,
+    theme: &Theme,
+    code_scroll: &mut u16,
+    code_cache: &mut CodeCache,
+    quiz_name: &str,
+) {
     let layout = question_layout(
         frame,
         &format!(" Question {} ", question.question_index + 1),
         &question.question.text,
         quiz_name,
+        question
+            .question
+            .choices
+            .iter()
+            .any(|choice| choice.code.is_some()),
     );
 
     if let Some(code_block) = &question.question.code_block {
-        code(frame, code_block, theme, &layout);
+        code(frame, code_block, theme, code_scroll, code_cache, &layout);
     }
 
     let mut choice_grid: Grid = question.clone().question.into();
     let mut items = choice_grid.clone().items();
 
     for (row, items) in items.iter_mut().enumerate() {
-        for (col, mut items) in items.iter_mut().enumerate() {
-            match &mut items {
-                Some(item) => {
-                    item.set_style_ref(Style::default());
-
-                    let was_selected_by_user = question
-                        .player_answer
-                        .iter()
-                        .any(|choice| choice.contains(&item.get_uuid()));
-
-                    debug!("was_selected_by_user {row} {col}: {was_selected_by_user}");
-
-                    let answers_count = match question.stats.get(&item.get_uuid()) {
-                        Some(count) => count.players_answered_count,
-                        None => 0,
-                    };
-
-                    let title = Title::from(answers_count.to_string())
-                        .alignment(Alignment::Right)
-                        .position(Position::Top);
-
-                    if was_selected_by_user {
-                        item.set_block_ref(
-                            get_bordered_block()
-                                .border_type(BorderType::Double)
-                                .title(title),
-                        );
-                        item.set_style_ref(Style::default().bold());
-                    } else {
-                        item.set_block_ref(get_bordered_block().title(title));
-                    }
-                }
-                None => {}
+        for (col, item) in items.iter_mut().enumerate() {
+            let Some(item) = item else {
+                continue;
+            };
+
+            item.set_style_ref(Style::default());
+
+            let was_selected_by_user = question
+                .player_answer
+                .iter()
+                .any(|choice| choice.contains(&item.get_uuid()));
+
+            debug!("was_selected_by_user {row} {col}: {was_selected_by_user}");
+
+            let answers_count = match question.stats.get(&item.get_uuid()) {
+                Some(count) => count.players_answered_count,
+                None => 0,
+            };
+
+            let title = Title::from(answers_count.to_string())
+                .alignment(Alignment::Right)
+                .position(Position::Top);
+
+            if was_selected_by_user {
+                item.set_block_ref(
+                    get_bordered_block()
+                        .border_type(BorderType::Double)
+                        .title(title),
+                );
+                item.set_style_ref(Style::default().bold());
+            } else {
+                item.set_block_ref(get_bordered_block().title(title));
             }
         }
     }
 
     choice_grid = Grid::new(items);
 
+    code_cache.highlight_choices(
+        question
+            .question
+            .choices
+            .iter()
+            .filter_map(|choice| Some((choice.id, choice.code.as_ref()?))),
+        theme,
+    );
+
     let choice_selector = Selector::new(choice_grid);
     let choice_selector = choice_selector
         .vertical_gap(1)
         .horizontal_gap(3)
+        .syntax_theme(theme.clone())
+        .code_cache(code_cache)
         .current_item_style(Style::default())
-        .correct_item_style(Style::default().bg(Color::Green))
+        .correct_item_style(palette().correct_item)
         .block(Block::default());
 
     frame.render_widget(choice_selector, layout[3]);
@@ -79,8 +79,12 @@ pub fn welcome(frame: &mut Frame, quiz_name: &str) {
 }
 
 pub fn end_game(frame: &mut Frame, quiz_name: &str) {
+    end_game_with_hint(frame, "Press CTRL C to close", quiz_name);
+}
+
+pub fn end_game_with_hint(frame: &mut Frame, hint: &str, quiz_name: &str) {
     let lines = ["Game", "Ended", "Thank You!"];
-    ascii_art(frame, &lines, "Press CTRL C to close", quiz_name);
+    ascii_art(frame, &lines, hint, quiz_name);
 }
 
 pub fn error(frame: &mut Frame, message: &str, quiz_name: &str) {
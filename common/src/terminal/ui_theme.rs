@@ -0,0 +1,168 @@
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+use ratatui::style::{Color, Modifier, Style};
+
+/// Color theme of the user interface (everything except the highlighted code,
+/// which has its own syntax theme)
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq)]
+pub enum UiTheme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    Colorblind,
+}
+
+/// Styles and colors used when rendering the terminal screens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// base style of the whole screen
+    pub screen: Style,
+    pub border: Style,
+    pub title: Style,
+    pub popup: Style,
+    /// choice the cursor is on
+    pub current_item: Style,
+    /// correct choices after the question ended
+    pub correct_item: Style,
+    pub error: Style,
+    /// lines of the code the teacher wants to point out
+    pub highlighted_line: Style,
+    pub line_number: Style,
+    /// distinct colors, the choices are colored by them in turns
+    /// and the players choose one of them
+    pub colors: [NamedColor; 7],
+}
+
+/// Color with a name, so it can be offered to the players
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamedColor {
+    pub name: &'static str,
+    pub color: Color,
+}
+
+const fn named(name: &'static str, color: Color) -> NamedColor {
+    NamedColor { name, color }
+}
+
+// Okabe-Ito palette, distinguishable with all common types of color blindness
+const ORANGE: Color = Color::Rgb(230, 159, 0);
+const SKY_BLUE: Color = Color::Rgb(86, 180, 233);
+const BLUISH_GREEN: Color = Color::Rgb(0, 158, 115);
+const YELLOW: Color = Color::Rgb(240, 228, 66);
+const BLUE: Color = Color::Rgb(0, 114, 178);
+const VERMILLION: Color = Color::Rgb(213, 94, 0);
+const REDDISH_PURPLE: Color = Color::Rgb(204, 121, 167);
+
+impl UiTheme {
+    #[must_use]
+    pub fn palette(self) -> Palette {
+        match self {
+            UiTheme::Dark => Palette {
+                screen: Style::default(),
+                border: Style::default(),
+                title: Style::default(),
+                popup: Style::default().bg(Color::DarkGray),
+                current_item: Style::default().bg(Color::White),
+                correct_item: Style::default().bg(Color::Green),
+                error: Style::default().fg(Color::Red),
+                highlighted_line: Style::default().bg(Color::DarkGray),
+                line_number: Style::default().fg(Color::DarkGray),
+                colors: [
+                    named("Red", Color::Red),
+                    named("Blue", Color::Blue),
+                    named("Green", Color::Green),
+                    named("Yellow", Color::Yellow),
+                    named("Magenta", Color::Magenta),
+                    named("Cyan", Color::Cyan),
+                    named("Gray", Color::Gray),
+                ],
+            },
+            // for terminals with a light background, the colors of the dark theme
+            // are mostly too bright to be readable there
+            UiTheme::Light => Palette {
+                screen: Style::default(),
+                border: Style::default().fg(Color::DarkGray),
+                title: Style::default().fg(Color::Black),
+                popup: Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Rgb(215, 215, 215)),
+                current_item: Style::default().bg(Color::Rgb(200, 200, 200)),
+                correct_item: Style::default().bg(Color::Rgb(150, 220, 150)),
+                error: Style::default().fg(Color::Rgb(180, 0, 0)),
+                highlighted_line: Style::default().bg(Color::Rgb(255, 245, 170)),
+                line_number: Style::default().fg(Color::Gray),
+                colors: [
+                    named("Red", Color::Rgb(180, 0, 0)),
+                    named("Blue", Color::Rgb(0, 60, 180)),
+                    named("Green", Color::Rgb(0, 120, 0)),
+                    named("Brown", Color::Rgb(150, 90, 0)),
+                    named("Purple", Color::Rgb(140, 0, 140)),
+                    named("Teal", Color::Rgb(0, 120, 130)),
+                    named("Gray", Color::Rgb(70, 70, 70)),
+                ],
+            },
+            // white on black with bold borders, readable even on a washed out projector
+            UiTheme::HighContrast => Palette {
+                screen: Style::default().fg(Color::White).bg(Color::Black),
+                border: Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+                title: Style::default().fg(Color::Yellow),
+                popup: Style::default().fg(Color::White).bg(Color::Blue),
+                current_item: Style::default().fg(Color::Black).bg(Color::Yellow),
+                correct_item: Style::default().fg(Color::Black).bg(Color::LightGreen),
+                error: Style::default()
+                    .fg(Color::LightRed)
+                    .add_modifier(Modifier::BOLD),
+                highlighted_line: Style::default().fg(Color::Black).bg(Color::Yellow),
+                line_number: Style::default().fg(Color::Gray),
+                colors: [
+                    named("White", Color::White),
+                    named("Yellow", Color::Yellow),
+                    named("Cyan", Color::LightCyan),
+                    named("Green", Color::LightGreen),
+                    named("Magenta", Color::LightMagenta),
+                    named("Red", Color::LightRed),
+                    named("Blue", Color::LightBlue),
+                ],
+            },
+            // no red-green pairs, correct answers are blue instead of green
+            UiTheme::Colorblind => Palette {
+                screen: Style::default(),
+                border: Style::default(),
+                title: Style::default(),
+                popup: Style::default().bg(Color::DarkGray),
+                current_item: Style::default().fg(Color::Black).bg(Color::White),
+                correct_item: Style::default().fg(Color::White).bg(BLUE),
+                error: Style::default().fg(VERMILLION),
+                highlighted_line: Style::default().bg(Color::DarkGray),
+                line_number: Style::default().fg(Color::DarkGray),
+                colors: [
+                    named("Orange", ORANGE),
+                    named("Sky blue", SKY_BLUE),
+                    named("Bluish green", BLUISH_GREEN),
+                    named("Yellow", YELLOW),
+                    named("Blue", BLUE),
+                    named("Vermillion", VERMILLION),
+                    named("Reddish purple", REDDISH_PURPLE),
+                ],
+            },
+        }
+    }
+}
+
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+/// Sets the theme for the whole application, has to be called before anything is rendered,
+/// later calls are ignored
+pub fn init(theme: UiTheme) {
+    let _ = PALETTE.set(theme.palette());
+}
+
+/// Palette of the theme chosen at startup (the dark one if none was chosen)
+#[must_use]
+pub fn palette() -> &'static Palette {
+    PALETTE.get_or_init(|| UiTheme::default().palette())
+}
@@ -34,6 +34,11 @@ impl Grid {
         self.is_empty
     }
 
+    #[must_use]
+    pub fn has_code(&self) -> bool {
+        self.items.iter().flatten().flatten().any(Item::has_code)
+    }
+
     // consume self and return the items inside
     // useful when wanting to change the grid or items inside
     #[must_use]
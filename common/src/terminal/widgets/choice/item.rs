@@ -3,12 +3,13 @@ use ratatui::widgets::{Block, Borders};
 use std::cmp::PartialEq;
 use uuid::Uuid;
 
-use crate::questions::{Choice, ChoiceCensored};
+use crate::questions::{Choice, ChoiceCensored, CodeBlock};
 
 #[derive(Debug, Clone, Default, PartialEq)]
 #[allow(clippy::module_name_repetitions)]
 pub struct Item {
     pub(super) content: String,
+    pub(super) code: Option<CodeBlock>,
     pub(super) is_correct: bool,
     pub(super) uuid: Uuid,
     pub(super) style: Style,
@@ -20,6 +21,7 @@ impl Item {
     pub fn new(content: String, is_correct: bool, uuid: Uuid) -> Self {
         Self {
             content,
+            code: None,
             is_correct,
             uuid,
             style: Style::default(),
@@ -27,6 +29,12 @@ impl Item {
         }
     }
 
+    #[must_use]
+    pub fn code(mut self, code: Option<CodeBlock>) -> Self {
+        self.code = code;
+        self
+    }
+
     #[must_use]
     pub fn style(mut self, style: Style) -> Self {
         self.style = style;
@@ -51,17 +59,33 @@ impl Item {
     pub fn get_uuid(&self) -> Uuid {
         self.uuid
     }
+
+    #[must_use]
+    pub fn has_code(&self) -> bool {
+        self.code.is_some()
+    }
+
+    // height the item needs to show the whole text and code snippet, including borders
+    pub(super) fn content_height(&self) -> u16 {
+        let text_lines = self.content.lines().count();
+        let code_lines = self
+            .code
+            .as_ref()
+            .map_or(0, |code| code.code.lines().count());
+
+        u16::try_from(text_lines + code_lines + 2).unwrap_or(u16::MAX)
+    }
 }
 
 impl From<ChoiceCensored> for Item {
     fn from(value: ChoiceCensored) -> Self {
-        Self::new(value.text, false, value.id)
+        Self::new(value.text, false, value.id).code(value.code)
     }
 }
 
 impl From<Choice> for Item {
     fn from(value: Choice) -> Self {
-        Self::new(value.text, value.is_correct, value.id)
+        Self::new(value.text, value.is_correct, value.id).code(value.code)
     }
 }
 
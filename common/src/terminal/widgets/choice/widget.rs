@@ -1,10 +1,12 @@
 use ratatui::layout::{Alignment, Rect};
+use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::prelude::Buffer;
 use ratatui::style::{Style, Stylize};
 use ratatui::text::Text;
 use ratatui::widgets::{Block, Padding, Paragraph, StatefulWidget, Widget, Wrap};
 
-use crate::terminal::widgets::choice::{Grid, SelectorState};
+use crate::terminal::highlight::{self, CodeCache, Theme};
+use crate::terminal::widgets::choice::{Grid, Item, SelectorState};
 
 #[derive(Default, Clone)]
 pub struct Selector<'a> {
@@ -19,6 +21,8 @@ pub struct Selector<'a> {
     horizontal_gap: u16,
     vertical_gap: u16,
     max_width_percentage: u8,
+    syntax_theme: Theme,
+    code_cache: Option<&'a CodeCache>,
 }
 
 impl<'a> Selector<'a> {
@@ -36,6 +40,8 @@ impl<'a> Selector<'a> {
             horizontal_gap: 0,
             vertical_gap: 0,
             max_width_percentage: 100,
+            syntax_theme: Theme::default(),
+            code_cache: None,
         }
     }
 
@@ -106,6 +112,21 @@ impl<'a> Selector<'a> {
         self.max_width_percentage = max_width_percentage;
         self
     }
+
+    // theme used for highlighting the code snippets inside the choices
+    #[must_use]
+    pub fn syntax_theme(mut self, syntax_theme: Theme) -> Self {
+        self.syntax_theme = syntax_theme;
+        self
+    }
+
+    // already highlighted code snippets of the choices, the snippets missing
+    // in the cache are highlighted during the rendering
+    #[must_use]
+    pub fn code_cache(mut self, code_cache: &'a CodeCache) -> Self {
+        self.code_cache = Some(code_cache);
+        self
+    }
 }
 
 fn calculate_size(available_size: u16, item_count: u16, total_gap_size: u16, max_size: u16) -> u16 {
@@ -118,11 +139,80 @@ fn calculate_size(available_size: u16, item_count: u16, total_gap_size: u16, max
     }
 }
 
+// split the available height between the rows according to the height of their content,
+// rows with longer code snippets get more space
+fn calculate_row_heights(available_size: u16, content_heights: &[u16]) -> Vec<u16> {
+    let total: u32 = content_heights
+        .iter()
+        .map(|&height| u32::from(height))
+        .sum();
+    if total == 0 {
+        return vec![0; content_heights.len()];
+    }
+
+    if total <= u32::from(available_size) {
+        // everything fits, give the leftover space evenly to all rows
+        let leftover = (u32::from(available_size) - total)
+            / u32::try_from(content_heights.len()).unwrap_or(u32::MAX);
+        return content_heights
+            .iter()
+            .map(|&height| u16::try_from(u32::from(height) + leftover).unwrap_or(u16::MAX))
+            .collect();
+    }
+
+    content_heights
+        .iter()
+        .map(|&height| {
+            u16::try_from(u32::from(height) * u32::from(available_size) / total).unwrap_or(u16::MAX)
+        })
+        .collect()
+}
+
+fn render_code_item(
+    item: &Item,
+    block: Block,
+    style: Style,
+    syntax_theme: &Theme,
+    code_cache: Option<&CodeCache>,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let Some(code) = &item.code else {
+        return;
+    };
+
+    let inner_area = block.inner(area);
+    block.style(style).render(area, buf);
+
+    let text = Text::from(item.content.clone());
+    let text_height = u16::try_from(text.height()).unwrap_or_default();
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(text_height), Constraint::Min(0)])
+        .split(inner_area);
+
+    Paragraph::new(text)
+        .style(style)
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Center)
+        .render(layout[0], buf);
+
+    // the code keeps its own colors, so the selection style is only applied to the border and text
+    match code_cache.and_then(|cache| cache.choice(&item.uuid)) {
+        Some(highlighted) => highlighted
+            .paragraph(0, usize::from(layout[1].height))
+            .render(layout[1], buf),
+        None => highlight::code_block(code, syntax_theme).render(layout[1], buf),
+    }
+}
+
 impl<'a> StatefulWidget for Selector<'a> {
     type State = SelectorState;
 
     // when rendering the widget, make sure that the ChoiceSelectorState is used
     // with the same grid that is used for the rendering
+    #[allow(clippy::too_many_lines)]
     fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let choice_selector_area = match self.block.take() {
             Some(b) => {
@@ -151,16 +241,38 @@ impl<'a> StatefulWidget for Selector<'a> {
         if total_vertical_gap_size >= choice_selector_area.height {
             total_vertical_gap_size = 0;
         }
-        let item_height = calculate_size(
-            choice_selector_area.height,
-            u16::try_from(items.len()).unwrap_or(u16::MAX),
-            total_vertical_gap_size,
-            choice_selector_area.height,
-        );
+        let available_height = choice_selector_area.height - total_vertical_gap_size;
+        let row_heights = if self.grid.has_code() {
+            let content_heights: Vec<u16> = self
+                .grid
+                .items
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .flatten()
+                        .map(Item::content_height)
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+            calculate_row_heights(available_height, &content_heights)
+        } else {
+            let item_height = calculate_size(
+                choice_selector_area.height,
+                u16::try_from(self.grid.items.len()).unwrap_or(u16::MAX),
+                total_vertical_gap_size,
+                choice_selector_area.height,
+            );
+            vec![item_height; self.grid.items.len()]
+        };
+
+        let items = &mut self.grid.items;
 
         let (x, mut y) = (choice_selector_area.x, choice_selector_area.y);
 
         for (i, row) in items.iter_mut().enumerate() {
+            let item_height = row_heights[i];
+
             let mut total_horizontal_gap_size =
                 self.horizontal_gap * (u16::try_from(row.len()).unwrap_or(u16::MAX) - 1);
             if total_horizontal_gap_size > choice_selector_area.width {
@@ -182,7 +294,7 @@ impl<'a> StatefulWidget for Selector<'a> {
             for (j, item) in row.iter_mut().enumerate() {
                 let area = Rect::new(
                     row_x + u16::try_from(j).unwrap_or_default() * item_width,
-                    y + u16::try_from(i).unwrap_or_default() * item_height,
+                    y,
                     item_width,
                     item_height,
                 );
@@ -212,17 +324,30 @@ impl<'a> StatefulWidget for Selector<'a> {
                 }
 
                 let block = if self.current_item_block.is_some() && current {
-                    self.current_item_block.clone().take()
+                    self.current_item_block.clone()
                 } else if self.selected_item_block.is_some() && selected {
-                    self.selected_item_block.clone().take()
+                    self.selected_item_block.clone()
                 } else if self.correct_item_block.is_some() && correct {
-                    self.correct_item_block.clone().take()
+                    self.correct_item_block.clone()
                 } else {
                     None
                 };
 
                 let block = block.unwrap_or(item.block.clone());
 
+                if item.has_code() {
+                    render_code_item(
+                        item,
+                        block,
+                        style,
+                        &self.syntax_theme,
+                        self.code_cache,
+                        area,
+                        buf,
+                    );
+                    continue;
+                }
+
                 let text = Text::from(item.content.clone());
                 let text_height = u16::try_from(text.height()).unwrap_or_default() + 2;
                 // centering the text vertically
@@ -236,6 +361,7 @@ impl<'a> StatefulWidget for Selector<'a> {
                     .render(area, buf);
             }
 
+            y += item_height;
             if total_vertical_gap_size > 0 {
                 y += self.vertical_gap;
             }
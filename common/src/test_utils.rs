@@ -40,6 +40,10 @@ pub fn compare_censored_questions(
         if left_choice.text != right_choice.text {
             return false;
         }
+
+        if left_choice.code != right_choice.code {
+            return false;
+        }
     }
 
     true
@@ -71,6 +75,10 @@ fn compare_questions(left: &questions::Question, right: &questions::Question) ->
             return false;
         }
 
+        if left_choice.code != right_choice.code {
+            return false;
+        }
+
         if left_choice.is_correct != right_choice.is_correct {
             return false;
         }
@@ -91,21 +99,25 @@ pub fn no_code_question_fixture() -> questions::Question {
             questions::Choice {
                 id: uuid::Uuid::nil(),
                 text: "sleep".to_string(),
+                code: None,
                 is_correct: false,
             },
             questions::Choice {
                 id: uuid::Uuid::nil(),
                 text: "42".to_string(),
+                code: None,
                 is_correct: true,
             },
             questions::Choice {
                 id: uuid::Uuid::nil(),
                 text: "food".to_string(),
+                code: None,
                 is_correct: false,
             },
             questions::Choice {
                 id: uuid::Uuid::nil(),
                 text: "69".to_string(),
+                code: None,
                 is_correct: false,
             },
         ],
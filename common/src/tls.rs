@@ -0,0 +1,152 @@
+use std::{fs::File, io::BufReader, path::Path, sync::Arc};
+
+use anyhow::{bail, Context};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{self, ring, WebPkiSupportedAlgorithms},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use sha2::{Digest, Sha256};
+
+/// SHA-256 fingerprint of a DER encoded certificate, as uppercase hex bytes separated by colons
+#[must_use]
+pub fn fingerprint(certificate: &[u8]) -> String {
+    Sha256::digest(certificate)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Brings a fingerprint typed by the user to the form returned by [`fingerprint`],
+/// the bytes may be separated by colons or not separated at all
+/// # Errors
+/// - if the fingerprint does not consist of 32 hex bytes
+pub fn normalize_fingerprint(fingerprint: &str) -> anyhow::Result<String> {
+    let digits: String = fingerprint
+        .chars()
+        .filter(|c| *c != ':' && !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if digits.len() != 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("The fingerprint must be a SHA-256 hash, 32 hex bytes, not '{fingerprint}'");
+    }
+
+    Ok(digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+/// Reads all certificates from a PEM file
+/// # Errors
+/// - if the file cannot be read
+/// - if there is no certificate in the file
+pub fn read_certificates(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let file =
+        File::open(path).with_context(|| format!("Cannot open certificate {}", path.display()))?;
+
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid certificate {}", path.display()))?;
+
+    if certificates.is_empty() {
+        bail!("There is no certificate in {}", path.display());
+    }
+
+    Ok(certificates)
+}
+
+/// Accepts only the server certificate with the given fingerprint, whoever signed it,
+/// so a self-signed certificate can be trusted after the fingerprint is checked
+#[derive(Debug)]
+pub struct PinnedCertificate {
+    fingerprint: String,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl PinnedCertificate {
+    /// # Errors
+    /// - if the fingerprint is not a valid SHA-256 fingerprint
+    pub fn new(fingerprint: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            fingerprint: normalize_fingerprint(fingerprint)?,
+            algorithms: ring::default_provider().signature_verification_algorithms,
+        })
+    }
+}
+
+impl ServerCertVerifier for PinnedCertificate {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if fingerprint(end_entity) == self.fingerprint {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "The certificate of the server does not have the expected fingerprint".to_string(),
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// TLS configuration of a client connecting to `wss://`, `None` means the default
+/// configuration trusting the well-known certificate authorities
+/// # Errors
+/// - if the certificate authority cannot be read
+/// - if the fingerprint is not valid
+pub fn client_config(
+    ca_cert: Option<&Path>,
+    fingerprint: Option<&str>,
+) -> anyhow::Result<Option<Arc<ClientConfig>>> {
+    let config = match (ca_cert, fingerprint) {
+        (_, Some(fingerprint)) => ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(PinnedCertificate::new(fingerprint)?))
+            .with_no_client_auth(),
+        (Some(ca_cert), None) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in read_certificates(ca_cert)? {
+                roots.add(certificate)?;
+            }
+
+            ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth()
+        }
+        (None, None) => return Ok(None),
+    };
+
+    Ok(Some(Arc::new(config)))
+}
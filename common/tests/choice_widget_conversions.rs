@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use common::questions::{Choice, ChoiceCensored, Question, QuestionCensored};
+use common::questions::{Choice, ChoiceCensored, CodeBlock, Question, QuestionCensored};
 use common::terminal::widgets::choice::{Grid, Item};
 
 #[test]
@@ -10,6 +10,7 @@ fn test_choice_item_from_censored() {
     let choice = ChoiceCensored {
         id,
         text: text.clone(),
+        code: None,
     };
 
     let choice_item: Item = choice.into();
@@ -27,6 +28,7 @@ fn test_choice_item_from_uncensored() {
         id,
         is_correct: true,
         text: text.clone(),
+        code: None,
     };
 
     let choice_item: Item = choice.into();
@@ -36,6 +38,32 @@ fn test_choice_item_from_uncensored() {
     assert_eq!(choice_item, wanted);
 }
 
+#[test]
+fn test_choice_item_with_code() {
+    let id = Uuid::new_v4();
+    let text = "Test string".to_string();
+    let code = CodeBlock {
+        language: "rust".to_string(),
+        code: "fn main() {\n    println!(\"42\");\n}\n".to_string(),
+        highlight_lines: vec![],
+    };
+    let choice = ChoiceCensored {
+        id,
+        text: text.clone(),
+        code: Some(code.clone()),
+    };
+
+    let choice_item: Item = choice.into();
+
+    let wanted = Item::new(text.clone(), false, id).code(Some(code));
+
+    assert_eq!(choice_item, wanted);
+    assert!(choice_item.has_code());
+
+    let choice_grid = Grid::new(vec![vec![Some(choice_item), None]]);
+    assert!(choice_grid.has_code());
+}
+
 #[test]
 fn test_choice_grid_from_censored_2_choices() {
     let id_1 = Uuid::new_v4();
@@ -51,10 +79,12 @@ fn test_choice_grid_from_censored_2_choices() {
             ChoiceCensored {
                 id: id_1,
                 text: text.clone(),
+                code: None,
             },
             ChoiceCensored {
                 id: id_2,
                 text: text.clone(),
+                code: None,
             },
         ],
     };
@@ -85,14 +115,17 @@ fn test_choice_grid_from_censored_3_choices() {
             ChoiceCensored {
                 id: id_1,
                 text: text.clone(),
+                code: None,
             },
             ChoiceCensored {
                 id: id_2,
                 text: text.clone(),
+                code: None,
             },
             ChoiceCensored {
                 id: id_3,
                 text: text.clone(),
+                code: None,
             },
         ],
     };
@@ -127,18 +160,22 @@ fn test_choice_grid_from_censored_4_choices() {
             ChoiceCensored {
                 id: id_1,
                 text: text.clone(),
+                code: None,
             },
             ChoiceCensored {
                 id: id_2,
                 text: text.clone(),
+                code: None,
             },
             ChoiceCensored {
                 id: id_3,
                 text: text.clone(),
+                code: None,
             },
             ChoiceCensored {
                 id: id_4,
                 text: text.clone(),
+                code: None,
             },
         ],
     };
@@ -174,11 +211,13 @@ fn test_choice_grid_from_uncensored_2_choices() {
             Choice {
                 id: id_1,
                 text: text.clone(),
+                code: None,
                 is_correct: true,
             },
             Choice {
                 id: id_2,
                 text: text.clone(),
+                code: None,
                 is_correct: false,
             },
         ],
@@ -210,16 +249,19 @@ fn test_choice_grid_from_uncensored_3_choices() {
             Choice {
                 id: id_1,
                 text: text.clone(),
+                code: None,
                 is_correct: false,
             },
             Choice {
                 id: id_2,
                 text: text.clone(),
+                code: None,
                 is_correct: false,
             },
             Choice {
                 id: id_3,
                 text: text.clone(),
+                code: None,
                 is_correct: true,
             },
         ],
@@ -255,21 +297,25 @@ fn test_choice_grid_from_uncensored_4_choices() {
             Choice {
                 id: id_1,
                 text: text.clone(),
+                code: None,
                 is_correct: false,
             },
             Choice {
                 id: id_2,
                 text: text.clone(),
+                code: None,
                 is_correct: true,
             },
             Choice {
                 id: id_3,
                 text: text.clone(),
+                code: None,
                 is_correct: true,
             },
             Choice {
                 id: id_4,
                 text: text.clone(),
+                code: None,
                 is_correct: false,
             },
         ],
@@ -0,0 +1,92 @@
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+use uuid::Uuid;
+
+use common::questions::CodeBlock;
+use common::terminal::highlight::{self, CodeCache, Theme};
+
+fn rust_code_block() -> CodeBlock {
+    CodeBlock {
+        language: "rust".to_string(),
+        code: "fn main() {\n    println!(\"Hello\");\n}\n".to_string(),
+        highlight_lines: vec![],
+    }
+}
+
+#[test]
+fn test_code_cache_highlights_question_code() {
+    let block = rust_code_block();
+    let mut cache = CodeCache::default();
+
+    let wanted = highlight::code_block_with_line_numbers(&block, &Theme::default());
+
+    assert_eq!(
+        cache
+            .code_block(&block, &Theme::default())
+            .clone()
+            .into_paragraph(),
+        wanted
+    );
+    // the cached paragraph is returned even if the code changes in the meantime
+    let other_block = CodeBlock {
+        code: "fn other() {}\n".to_string(),
+        ..rust_code_block()
+    };
+    assert_eq!(
+        cache
+            .code_block(&other_block, &Theme::default())
+            .clone()
+            .into_paragraph(),
+        wanted
+    );
+}
+
+#[test]
+fn test_code_cache_highlights_choices() {
+    let block = rust_code_block();
+    let with_code = Uuid::new_v4();
+    let without_code = Uuid::new_v4();
+    let mut cache = CodeCache::default();
+
+    cache.highlight_choices([(with_code, &block)], &Theme::SolarizedDark);
+
+    assert_eq!(
+        cache
+            .choice(&with_code)
+            .map(|highlighted| highlighted.clone().into_paragraph()),
+        Some(highlight::code_block(&block, &Theme::SolarizedDark))
+    );
+    assert_eq!(cache.choice(&without_code), None);
+}
+
+#[test]
+fn test_code_paragraph_has_only_visible_lines() {
+    let block = CodeBlock {
+        code: (1..=500)
+            .map(|i| format!("let x{i} = {i};\n"))
+            .collect::<Vec<_>>()
+            .concat(),
+        ..rust_code_block()
+    };
+    let mut cache = CodeCache::default();
+    let highlighted = cache.code_block(&block, &Theme::default());
+    assert_eq!(highlighted.lines().len(), 500);
+
+    let area = Rect::new(0, 0, 30, 3);
+    let mut buffer = Buffer::empty(area);
+    highlighted.paragraph(100, 3).render(area, &mut buffer);
+
+    let row = |y| {
+        (0..area.width)
+            .map(|x| buffer.get(x, y).symbol().to_string())
+            .collect::<String>()
+    };
+    assert!(row(0).starts_with("101 │ let x101 = 101;"));
+    assert!(row(2).starts_with("103 │ let x103 = 103;"));
+
+    // scrolled to the end, only the remaining lines are drawn
+    let mut buffer = Buffer::empty(area);
+    highlighted.paragraph(499, 3).render(area, &mut buffer);
+    assert!((0..area.width)
+        .map(|x| buffer.get(x, 1).symbol())
+        .all(|symbol| symbol == " "));
+}
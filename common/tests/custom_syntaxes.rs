@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::widgets::Widget;
+
+use common::questions::{self, find_syntax, CodeBlock};
+use common::syntax::{self, CustomSyntax};
+use common::terminal::highlight::{self, Theme};
+
+// the registry is shared by the whole test binary, so every test loads the directory itself
+fn load_custom_dir() {
+    syntax::load_custom_dir(Path::new("./tests/files/syntaxes"))
+        .expect("Syntax directory should be OK");
+}
+
+#[test]
+fn test_ok_custom_syntax() {
+    load_custom_dir();
+
+    let syntax_sets = syntax::syntax_sets();
+    let (_, syntax) = find_syntax(&syntax_sets, "while", None).expect("Syntax should be loaded");
+    assert_eq!(syntax.name, "While");
+
+    let result =
+        questions::QuestionSet::from_file(Path::new("./tests/files/ok_custom_syntax.yaml"))
+            .expect("Question file should be OK");
+    assert_eq!(result.syntax_names(), vec!["While".to_string()]);
+
+    let custom_syntaxes = syntax::custom_syntaxes(["While", "Rust"]);
+    assert_eq!(custom_syntaxes.len(), 1);
+    assert_eq!(custom_syntaxes[0].name, "While");
+}
+
+#[test]
+fn test_custom_syntax_loaded_only_once() {
+    load_custom_dir();
+    let definition = syntax::custom_syntaxes(["While"]).remove(0).definition;
+
+    syntax::add_custom_syntaxes(vec![CustomSyntax {
+        name: "While".to_string(),
+        definition,
+    }])
+    .expect("Known syntax should be skipped");
+
+    assert_eq!(syntax::custom_syntaxes(["While"]).len(), 1);
+}
+
+#[test]
+fn test_invalid_custom_syntax() {
+    let result = syntax::add_custom_syntaxes(vec![CustomSyntax {
+        name: "Broken".to_string(),
+        definition: "this is not a syntax definition".to_string(),
+    }]);
+
+    assert!(result.is_err());
+    assert!(syntax::custom_syntaxes(["Broken"]).is_empty());
+}
+
+#[test]
+fn test_custom_theme() {
+    load_custom_dir();
+
+    let theme: Theme = "plain-contrast".parse().expect("Parsing theme never fails");
+    assert_eq!(theme, Theme::Custom("plain-contrast".to_string()));
+    assert!(theme.ensure_loaded().is_ok());
+
+    let unknown: Theme = "no-such-theme".parse().expect("Parsing theme never fails");
+    assert!(unknown.ensure_loaded().is_err());
+
+    let built_in: Theme = "solarized-dark".parse().expect("Parsing theme never fails");
+    assert_eq!(built_in, Theme::SolarizedDark);
+    assert_eq!(built_in.to_string(), "solarized-dark");
+}
+
+#[test]
+fn test_highlight_with_custom_syntax_and_theme() {
+    load_custom_dir();
+
+    let block = CodeBlock {
+        language: "while".to_string(),
+        code: "begin x1 = 1 end\n".to_string(),
+        highlight_lines: vec![],
+    };
+    let theme = Theme::Custom("plain-contrast".to_string());
+
+    let area = Rect::new(0, 0, 20, 1);
+    let mut buffer = Buffer::empty(area);
+    highlight::code_block(&block, &theme).render(area, &mut buffer);
+
+    // "begin" is a keyword, which is yellow in the custom theme
+    assert_eq!(buffer.get(0, 0).symbol(), "b");
+    assert_eq!(buffer.get(0, 0).fg, Color::Rgb(255, 255, 0));
+    assert_eq!(buffer.get(6, 0).fg, Color::Rgb(255, 255, 255));
+}
@@ -0,0 +1,108 @@
+use common::{
+    constants::AVATARS,
+    messages::{
+        encoding::{decode, Encoding, SharedMessage},
+        network::{PlayerData, PlayersUpdate, TryJoinRequest},
+        protocol::{Capability, ProtocolVersion},
+        ClientNetworkMessage, ServerNetworkMessage,
+    },
+};
+use ratatui::style::Color;
+use uuid::Uuid;
+
+fn players_update(count: usize) -> ServerNetworkMessage {
+    ServerNetworkMessage::PlayersUpdate(PlayersUpdate {
+        players: (0..count)
+            .map(|i| PlayerData {
+                uuid: Uuid::new_v4(),
+                nickname: format!("player {i}"),
+                color: Color::Rgb(10, 20, 30),
+                color_index: None,
+                avatar: Some(AVATARS[i % AVATARS.len()].to_string()),
+            })
+            .collect(),
+    })
+}
+
+#[test]
+fn both_encodings_round_trip() -> anyhow::Result<()> {
+    let ServerNetworkMessage::PlayersUpdate(message) = players_update(3) else {
+        unreachable!()
+    };
+
+    for encoding in [Encoding::Json, Encoding::MessagePack] {
+        let frame = encoding.encode(&ServerNetworkMessage::PlayersUpdate(message.clone()))?;
+        let ServerNetworkMessage::PlayersUpdate(decoded) = decode(&frame)? else {
+            panic!("Expected PlayersUpdate");
+        };
+        assert_eq!(decoded, message);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn message_pack_is_smaller() -> anyhow::Result<()> {
+    let message = players_update(100);
+
+    let json = Encoding::Json.encode(&message)?;
+    let binary = Encoding::MessagePack.encode(&message)?;
+
+    assert!(binary.len() < json.len());
+
+    Ok(())
+}
+
+#[test]
+fn missing_default_fields_are_filled_in_message_pack() -> anyhow::Result<()> {
+    // a request of a client before the handshake, only with the uuid
+    #[derive(serde::Serialize)]
+    enum OldMessage {
+        TryJoinRequest { uuid: Uuid },
+    }
+
+    let uuid = Uuid::new_v4();
+    let frame = Encoding::MessagePack.encode(&OldMessage::TryJoinRequest { uuid })?;
+
+    let ClientNetworkMessage::TryJoinRequest(request) = decode(&frame)? else {
+        panic!("Expected TryJoinRequest");
+    };
+    assert_eq!(request.uuid, uuid);
+    assert_eq!(request.protocol, ProtocolVersion::LEGACY);
+
+    Ok(())
+}
+
+#[test]
+fn message_pack_is_used_only_when_both_sides_can() {
+    let all = Capability::supported();
+    let json_only = vec![Capability::PersonalReport];
+
+    assert_eq!(Encoding::negotiate(&all, &all), Encoding::MessagePack);
+    assert_eq!(Encoding::negotiate(&all, &json_only), Encoding::Json);
+    assert_eq!(Encoding::negotiate(&json_only, &all), Encoding::Json);
+    assert_eq!(Encoding::negotiate(&all, &[]), Encoding::Json);
+}
+
+#[test]
+fn request_announces_message_pack() {
+    let request = TryJoinRequest::new(Uuid::new_v4());
+    assert!(request.capabilities.contains(&Capability::MessagePack));
+}
+
+#[test]
+fn shared_message_is_encoded_the_same() -> anyhow::Result<()> {
+    let message = players_update(10);
+    let shared = SharedMessage::new(message.clone());
+
+    for encoding in [Encoding::Json, Encoding::MessagePack] {
+        let expected = encoding.encode(&message)?;
+
+        // the first clone encodes the message, the others get the same frame
+        for clone in [shared.clone(), shared.clone()] {
+            assert_eq!(clone.frame(encoding)?, expected);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use common::{
+    messages::network::{ChoiceReport, PersonalReport, QuestionReport},
+    questions::CodeBlock,
+};
+
+fn choice(text: &str) -> ChoiceReport {
+    ChoiceReport {
+        text: text.to_string(),
+        code: None,
+    }
+}
+
+fn rust_code(code: &str) -> CodeBlock {
+    CodeBlock {
+        language: "rust".to_string(),
+        code: code.to_string(),
+        highlight_lines: Vec::new(),
+    }
+}
+
+fn sample_report() -> PersonalReport {
+    PersonalReport {
+        quiz_name: "Rust basics".to_string(),
+        nickname: "Ferris".to_string(),
+        rank: 2,
+        players_count: 5,
+        total_points: 800,
+        questions: vec![
+            QuestionReport {
+                question_text: "Which keyword declares a variable?".to_string(),
+                code_block: None,
+                selected_choices: Some(vec![choice("let")]),
+                correct_choices: vec![choice("let")],
+                is_correct: true,
+                points: 800,
+            },
+            QuestionReport {
+                question_text: "Which types are unsigned?".to_string(),
+                code_block: None,
+                selected_choices: Some(vec![choice("u8")]),
+                correct_choices: vec![choice("u8"), choice("usize")],
+                is_correct: false,
+                points: 0,
+            },
+            QuestionReport {
+                question_text: "What does `?` do?".to_string(),
+                code_block: None,
+                selected_choices: None,
+                correct_choices: vec![choice("Propagates errors")],
+                is_correct: false,
+                points: 0,
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_accuracy() {
+    let report = sample_report();
+
+    assert_eq!(report.correct_answers(), 1);
+    assert!((report.accuracy() - 100.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_accuracy_of_empty_report() {
+    let report = PersonalReport {
+        questions: Vec::new(),
+        ..sample_report()
+    };
+
+    assert_eq!(report.correct_answers(), 0);
+    assert!(report.accuracy().abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_markdown() {
+    let markdown = sample_report().to_markdown();
+
+    assert!(markdown.starts_with("# Rust basics\n"));
+    assert!(markdown.contains("- Rank: 2 of 5\n"));
+    assert!(markdown.contains("- Accuracy: 33 % (1 of 3 questions)\n"));
+    assert!(markdown.contains("## Question 1 (correct, 800 points)"));
+    assert!(markdown.contains("- Correct answer: u8, usize\n"));
+    assert!(markdown.contains("## Question 3 (wrong, 0 points)"));
+    assert!(markdown.contains("- Your answer: (no answer)\n"));
+}
+
+#[test]
+fn test_markdown_with_code() {
+    let mut report = sample_report();
+    report.questions[0].code_block = Some(rust_code("let x = 5;\n\nprintln!(\"{x}\");\n"));
+    report.questions[1].selected_choices = Some(vec![ChoiceReport {
+        text: "This one".to_string(),
+        code: Some(rust_code("let s = \"```\";")),
+    }]);
+
+    let markdown = report.to_markdown();
+
+    assert!(markdown.contains(
+        "## Question 1 (correct, 800 points)\n\n\
+         Which keyword declares a variable?\n\n\
+         ```rust\n\
+         let x = 5;\n\
+         \n\
+         println!(\"{x}\");\n\
+         ```\n\n\
+         - Your answer: let\n"
+    ));
+    // the fence is longer than the backticks in the code
+    assert!(markdown.contains(
+        "- Your answer:\n  \
+         - This one\n    \
+         ````rust\n    \
+         let s = \"```\";\n    \
+         ````\n\
+         - Correct answer: u8, usize\n"
+    ));
+}
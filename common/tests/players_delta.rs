@@ -0,0 +1,48 @@
+use common::messages::network::{PlayerData, PlayersDelta};
+use ratatui::style::Color;
+use uuid::Uuid;
+
+fn player(nickname: &str) -> PlayerData {
+    PlayerData {
+        uuid: Uuid::new_v4(),
+        nickname: nickname.to_string(),
+        color: Color::Rgb(10, 20, 30),
+        color_index: None,
+        avatar: None,
+    }
+}
+
+#[test]
+fn delta_is_applied_to_players() {
+    let alice = player("alice");
+    let bob = player("bob");
+    let carol = player("carol");
+    let mut players = vec![alice.clone(), bob.clone()];
+
+    PlayersDelta {
+        joined: vec![carol.clone()],
+        left: vec![alice.uuid],
+    }
+    .apply(&mut players);
+
+    assert_eq!(players, vec![bob, carol]);
+}
+
+#[test]
+fn known_and_unknown_players_are_handled() {
+    let alice = player("alice");
+    let mut players = vec![alice.clone()];
+
+    // alice joined again with another nickname, somebody unknown left
+    let renamed = PlayerData {
+        nickname: "alice2".to_string(),
+        ..alice
+    };
+    PlayersDelta {
+        joined: vec![renamed.clone()],
+        left: vec![Uuid::new_v4()],
+    }
+    .apply(&mut players);
+
+    assert_eq!(players, vec![renamed]);
+}
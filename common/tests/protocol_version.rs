@@ -0,0 +1,66 @@
+use common::messages::{
+    network::TryJoinRequest,
+    protocol::{Capability, ProtocolVersion},
+};
+use uuid::Uuid;
+
+const SERVER: ProtocolVersion = ProtocolVersion { major: 2, minor: 3 };
+
+#[test]
+fn same_and_previous_minor_version_are_accepted() {
+    assert!(SERVER.accepts(SERVER).is_ok());
+    assert!(SERVER
+        .accepts(ProtocolVersion { major: 2, minor: 2 })
+        .is_ok());
+}
+
+#[test]
+fn older_client_is_asked_to_update() {
+    let reason = SERVER
+        .accepts(ProtocolVersion { major: 2, minor: 1 })
+        .unwrap_err();
+    assert!(reason.contains("update your client to protocol 2.3"));
+
+    assert!(SERVER
+        .accepts(ProtocolVersion { major: 1, minor: 9 })
+        .is_err());
+}
+
+#[test]
+fn newer_client_is_refused() {
+    let reason = SERVER
+        .accepts(ProtocolVersion { major: 2, minor: 4 })
+        .unwrap_err();
+    assert!(reason.contains("newer"));
+
+    assert!(SERVER
+        .accepts(ProtocolVersion { major: 3, minor: 0 })
+        .is_err());
+}
+
+#[test]
+fn request_of_client_before_handshake_is_legacy() -> anyhow::Result<()> {
+    let uuid = Uuid::new_v4();
+    let request: TryJoinRequest = serde_json::from_str(&format!(r#"{{"uuid":"{uuid}"}}"#))?;
+
+    assert_eq!(request.protocol, ProtocolVersion::LEGACY);
+    assert!(request.capabilities.is_empty());
+    assert!(request.pin.is_none());
+    assert!(request.join_code.is_none());
+    assert!(ProtocolVersion::CURRENT.accepts(request.protocol).is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn unknown_capability_is_tolerated() -> anyhow::Result<()> {
+    let capabilities: Vec<Capability> =
+        serde_json::from_str(r#"["personal_report", "teleportation"]"#)?;
+
+    assert_eq!(
+        capabilities,
+        vec![Capability::PersonalReport, Capability::Unknown]
+    );
+
+    Ok(())
+}
@@ -42,6 +42,7 @@ fn test_ok_code() {
         code_block: Some(questions::CodeBlock {
             language: "rs".to_string(),
             code: "fn main() {\n    println!(\"42\");\n}\n".to_string(),
+            highlight_lines: vec![],
         }),
         time_seconds: 42,
         is_multichoice: true,
@@ -49,22 +50,26 @@ fn test_ok_code() {
             questions::Choice {
                 id: Uuid::nil(),
                 text: "Nothing useful".to_string(),
+                code: None,
                 is_correct: false,
             },
             questions::Choice {
                 id: Uuid::nil(),
                 text: "It prints 42".to_string(),
+                code: None,
                 is_correct: true,
             },
             questions::Choice {
                 id: Uuid::nil(),
                 text: "It fails to compile and the compiler will scream at us".to_string(),
+                code: None,
                 is_correct: false,
             },
             questions::Choice {
                 id: Uuid::nil(),
                 text: "It answers to the ultimate question of life, the Universe, and Everything"
                     .to_string(),
+                code: None,
                 is_correct: true,
             },
         ],
@@ -73,6 +78,86 @@ fn test_ok_code() {
     assert_questionset_eq!(result, wanted);
 }
 
+#[test]
+fn test_ok_choice_code() {
+    let result = questions::QuestionSet::from_file(Path::new("./tests/files/ok_choice_code.yaml"))
+        .expect("Question file should be OK");
+
+    let wanted = questions::QuestionSet::new(vec![questions::Question {
+        text: "Which of these snippets compiles?".to_string(),
+        code_block: None,
+        time_seconds: 42,
+        is_multichoice: false,
+        choices: vec![
+            questions::Choice {
+                id: Uuid::nil(),
+                text: "A".to_string(),
+                code: Some(questions::CodeBlock {
+                    language: "rust".to_string(),
+                    code: "let a = 1;\na = 5;\n".to_string(),
+                    highlight_lines: vec![],
+                }),
+                is_correct: false,
+            },
+            questions::Choice {
+                id: Uuid::nil(),
+                text: "B".to_string(),
+                code: Some(questions::CodeBlock {
+                    language: "rust".to_string(),
+                    code: "let mut a = 1;\na = 5;\n".to_string(),
+                    highlight_lines: vec![],
+                }),
+                is_correct: true,
+            },
+        ],
+    }]);
+
+    assert_questionset_eq!(result, wanted);
+}
+
+#[test]
+fn test_ok_highlight_lines() {
+    let result =
+        questions::QuestionSet::from_file(Path::new("./tests/files/ok_highlight_lines.yaml"))
+            .expect("Question file should be OK");
+
+    let code_block = result.questions[0]
+        .code_block
+        .as_ref()
+        .expect("Question should have code");
+
+    assert_eq!(
+        code_block.highlight_lines,
+        vec![
+            questions::LineRange { start: 2, end: 2 },
+            questions::LineRange { start: 4, end: 5 },
+        ]
+    );
+    assert!(!code_block.is_line_highlighted(1));
+    assert!(code_block.is_line_highlighted(2));
+    assert!(!code_block.is_line_highlighted(3));
+    assert!(code_block.is_line_highlighted(4));
+    assert!(code_block.is_line_highlighted(5));
+    assert_eq!(code_block.lines_count(), 6);
+}
+
+#[test]
+fn test_invalid_highlight_lines() {
+    let result =
+        questions::QuestionSet::from_file(Path::new("./tests/files/err_highlight_lines.yaml"));
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_highlight_lines_beyond_code() {
+    let result = questions::QuestionSet::from_file(Path::new(
+        "./tests/files/err_highlight_lines_beyond_code.yaml",
+    ));
+
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_ok_multiple() {
     let result = questions::QuestionSet::from_file(Path::new("./tests/files/ok_multiple.yaml"))
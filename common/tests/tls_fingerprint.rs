@@ -0,0 +1,33 @@
+use common::tls::{fingerprint, normalize_fingerprint};
+
+#[test]
+fn fingerprint_is_colon_separated_sha256() {
+    let fingerprint = fingerprint(b"certificate");
+
+    assert_eq!(fingerprint.len(), 32 * 3 - 1);
+    assert!(fingerprint
+        .split(':')
+        .all(|byte| byte.len() == 2 && byte.chars().all(|c| c.is_ascii_hexdigit())));
+    assert_eq!(fingerprint, fingerprint.to_uppercase());
+}
+
+#[test]
+fn typed_fingerprint_is_normalized() -> anyhow::Result<()> {
+    let expected = fingerprint(b"certificate");
+
+    assert_eq!(normalize_fingerprint(&expected)?, expected);
+    assert_eq!(
+        normalize_fingerprint(&expected.replace(':', "").to_lowercase())?,
+        expected
+    );
+    assert_eq!(normalize_fingerprint(&format!("  {expected}\n"))?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_fingerprint_is_refused() {
+    assert!(normalize_fingerprint("AB:CD").is_err());
+    assert!(normalize_fingerprint(&"X".repeat(64)).is_err());
+    assert!(normalize_fingerprint("").is_err());
+}
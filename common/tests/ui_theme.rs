@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use clap::ValueEnum;
+use ratatui::style::Color;
+
+use common::{
+    messages::network::PlayerData,
+    terminal::ui_theme::{palette, UiTheme},
+};
+use uuid::Uuid;
+
+#[test]
+fn test_colors_are_distinct() {
+    for theme in UiTheme::value_variants() {
+        let palette = theme.palette();
+        let colors: HashSet<_> = palette.colors.iter().map(|c| c.color).collect();
+        let names: HashSet<_> = palette.colors.iter().map(|c| c.name).collect();
+
+        assert_eq!(colors.len(), palette.colors.len(), "{theme:?}");
+        assert_eq!(names.len(), palette.colors.len(), "{theme:?}");
+    }
+}
+
+#[test]
+fn test_colorblind_theme_avoids_red_and_green() {
+    let palette = UiTheme::Colorblind.palette();
+    let red_and_green = [Color::Red, Color::LightRed, Color::Green, Color::LightGreen];
+
+    assert!(!palette
+        .colors
+        .iter()
+        .any(|named| red_and_green.contains(&named.color)));
+    assert!(!red_and_green
+        .iter()
+        .any(|color| palette.correct_item.bg == Some(*color)));
+}
+
+#[test]
+fn test_player_color_follows_viewers_palette() {
+    // chosen as "Green" by a student with the light theme
+    let player = PlayerData {
+        uuid: Uuid::new_v4(),
+        nickname: "alice".to_string(),
+        color: UiTheme::Light.palette().colors[2].color,
+        color_index: Some(2),
+        avatar: None,
+    };
+    assert_eq!(player.display_color(), palette().colors[2].color);
+
+    // the older clients send only the color
+    let player = PlayerData {
+        color_index: None,
+        ..player
+    };
+    assert_eq!(
+        player.display_color(),
+        UiTheme::Light.palette().colors[2].color
+    );
+}
@@ -1,16 +1,68 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
-use clap::Parser;
+use clap::{value_parser, Parser, Subcommand};
 use clap_num::number_range;
 
 use log::info;
 
-use common::terminal::highlight::Theme;
+use common::{
+    heartbeat::{DEFAULT_HEARTBEAT_INTERVAL_SECS, DEFAULT_HEARTBEAT_TIMEOUT_SECS},
+    terminal::{highlight::Theme, ui_theme::UiTheme},
+};
+
+use crate::{
+    lobby::{generate_join_code, Roster, DEFAULT_SNAPSHOT_FILE},
+    tls::TlsSettings,
+    websocket::{
+        ConnectionLimits, DEFAULT_HANDSHAKE_TIMEOUT_SECS, DEFAULT_MAX_FRAME_SIZE,
+        DEFAULT_MAX_MESSAGES_PER_SEC, DEFAULT_OUTBOX_CAPACITY,
+    },
+};
+
+const DEFAULT_QUESTIONS_FILE: &str = "default_questions.yaml";
 
 fn valid_port(s: &str) -> Result<u16, String> {
     number_range(s, 1025u16, u16::MAX)
 }
 
+fn valid_outbox_capacity(s: &str) -> Result<usize, String> {
+    number_range(s, 1, usize::MAX)
+}
+
+fn valid_max_frame_size(s: &str) -> Result<usize, String> {
+    // the join request with a long nickname must still fit
+    number_range(s, 1024, usize::MAX)
+}
+
+fn valid_max_connections_per_ip(s: &str) -> Result<usize, String> {
+    number_range(s, 1, usize::MAX)
+}
+
+fn valid_pin(s: &str) -> Result<String, String> {
+    if (4..=12).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Ok(s.to_owned())
+    } else {
+        Err(format!("PIN must be 4 to 12 letters or digits, not {s}"))
+    }
+}
+
+fn valid_join_code(s: &str) -> Result<String, String> {
+    if (3..=12).contains(&s.len()) && s.chars().all(|c| c.is_ascii_alphanumeric()) {
+        Ok(s.to_owned())
+    } else {
+        Err(format!(
+            "Join code must be 3 to 12 letters or digits, not {s}"
+        ))
+    }
+}
+
+fn valid_speed(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(speed) if speed > 0.0 && speed.is_finite() => Ok(speed),
+        _ => Err(format!("Speed must be a positive number, not {s}")),
+    }
+}
+
 fn valid_questions_file(file: &str) -> Result<PathBuf, String> {
     // recursively try to find the file from the current directory up to the root
     let mut current_dir = std::env::current_dir().expect("Failed to get current directory");
@@ -35,16 +87,21 @@ fn valid_questions_file(file: &str) -> Result<PathBuf, String> {
     ))
 }
 
-#[derive(Parser)]
+#[derive(Parser, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 #[clap(version = "1.0", author = "Robert Gemrot")]
 pub struct Args {
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+
     /// Port which the server will bind to. Must be greater than 1024. Defaults to 8080.
     #[clap(short, long, default_value="8080", value_parser=valid_port)]
     pub port: u16,
 
-    /// Where to load questions from
-    #[clap(short, long, value_parser=valid_questions_file, default_value = "default_questions.yaml")]
-    pub questions_file: PathBuf,
+    /// Where to load questions from [default: `default_questions.yaml`]
+    // the default is not set here, clap would look for it even when running a subcommand
+    #[clap(short, long, value_parser=valid_questions_file)]
+    pub questions_file: Option<PathBuf>,
 
     /// Where to write log messages to
     #[clap(short, long, default_value = "clihoot_server_logs.log")]
@@ -58,7 +115,220 @@ pub struct Args {
     #[clap(short = 'a', long, default_value = "false")]
     pub randomize_answers: bool,
 
-    /// Theme for syntax highlighting of code in questions
-    #[clap(short('t'), long, default_value_t, value_enum)]
+    /// Theme for syntax highlighting of code in questions. One of: default, eighties-dark,
+    /// mocha-dark, ocean-dark, ocean-light, inspired-git-hub, solarized-dark, solarized-light,
+    /// or a name of a .tmTheme file (without the extension) from the syntax directory
+    #[clap(short('t'), long, default_value_t)]
     pub syntax_theme: Theme,
+
+    /// Color theme of the user interface
+    #[clap(short, long, default_value_t, value_enum)]
+    pub ui_theme: UiTheme,
+
+    /// Directory with custom .sublime-syntax and .tmTheme files, the syntaxes used
+    /// in the quiz are sent to the students
+    #[clap(long)]
+    pub syntax_dir: Option<PathBuf>,
+
+    /// Directory where the results are exported to when the game ends
+    /// (or when the teacher asks for it)
+    #[clap(long, default_value = ".")]
+    pub results_dir: PathBuf,
+
+    /// `SQLite` database where every finished game is stored
+    #[clap(long, global = true)]
+    pub db: Option<PathBuf>,
+
+    /// Where the state of the game is periodically saved, so it can be resumed with --resume
+    #[clap(long, default_value = DEFAULT_SNAPSHOT_FILE)]
+    pub snapshot_file: PathBuf,
+
+    /// Snapshot of an interrupted game to continue, the questions are taken from the snapshot
+    #[clap(long, conflicts_with = "questions_file")]
+    pub resume: Option<PathBuf>,
+
+    /// JSONL file where all messages of the game are recorded, so the game can be replayed
+    #[clap(long)]
+    pub journal: Option<PathBuf>,
+
+    /// PEM certificate of the server, the students then connect with wss://
+    #[clap(long, requires = "tls_key", conflicts_with = "tls_self_signed")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key of the certificate given by --tls-cert
+    #[clap(long, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Encrypt the connections with a certificate generated for this game,
+    /// its fingerprint is shown on the teacher's screen
+    #[clap(long)]
+    pub tls_self_signed: bool,
+
+    /// How often (in seconds) the clients are pinged
+    #[clap(
+        long,
+        default_value_t = DEFAULT_HEARTBEAT_INTERVAL_SECS,
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    pub heartbeat_interval: u64,
+
+    /// After how many seconds without any message from a client the player is marked as away,
+    /// the questions then do not wait for their answer
+    #[clap(
+        long,
+        default_value_t = DEFAULT_HEARTBEAT_TIMEOUT_SECS,
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    pub heartbeat_timeout: u64,
+
+    /// Send all messages as JSON text, even to the clients which can use the binary
+    /// `MessagePack` encoding (useful for debugging)
+    #[clap(long)]
+    pub json_only: bool,
+
+    /// How many messages may wait for a client which does not read them,
+    /// the client is disconnected when there are more
+    #[clap(long, default_value_t = DEFAULT_OUTBOX_CAPACITY, value_parser = valid_outbox_capacity)]
+    pub outbox_capacity: usize,
+
+    /// The biggest message a client may send, in bytes, the client is disconnected
+    /// when it sends a bigger one
+    #[clap(long, default_value_t = DEFAULT_MAX_FRAME_SIZE, value_parser = valid_max_frame_size)]
+    pub max_frame_size: usize,
+
+    /// How many messages a client may send per second (twice as many in a short burst),
+    /// the client is disconnected when it sends more
+    #[clap(
+        long,
+        default_value_t = DEFAULT_MAX_MESSAGES_PER_SEC,
+        value_parser = value_parser!(u32).range(1..)
+    )]
+    pub max_messages_per_sec: u32,
+
+    /// How many clients may connect from one IP address, unlimited by default,
+    /// as the whole class often connects through one NAT
+    #[clap(long, value_parser = valid_max_connections_per_ip)]
+    pub max_connections_per_ip: Option<usize>,
+
+    /// How many seconds a client has to finish the TLS and websocket handshakes,
+    /// the connection is closed when it takes longer
+    #[clap(
+        long,
+        default_value_t = DEFAULT_HANDSHAKE_TIMEOUT_SECS,
+        value_parser = value_parser!(u64).range(1..)
+    )]
+    pub handshake_timeout: u64,
+
+    /// PIN of the game, the students need it only when the server hosts more games
+    /// [default: random]
+    #[clap(long, value_parser = valid_pin)]
+    pub pin: Option<String>,
+
+    /// Port on which the server waits for the teachers of other games (see the attach command),
+    /// only the teachers on this computer can attach; other games cannot be started without it
+    #[clap(long, value_parser = valid_port, global = true)]
+    pub attach_port: Option<u16>,
+
+    /// Protect the game with a code the students have to enter to join, shown on the
+    /// teacher's screen; a random one is generated (for every game) if no code is given
+    #[clap(long, value_name = "CODE", value_parser = valid_join_code)]
+    pub join_code: Option<Option<String>>,
+
+    /// The joining players wait until the teacher lets them in (or not) on the teacher's screen
+    #[clap(long)]
+    pub approve_joins: bool,
+
+    /// CSV file with the `id` and `name` columns of the students in the class,
+    /// the players have to join with one of the IDs (each ID only once),
+    /// the nicknames stay public and the IDs go to the exported results
+    #[clap(long, value_name = "FILE")]
+    pub roster: Option<PathBuf>,
+}
+
+impl Args {
+    #[must_use]
+    pub fn connection_limits(&self) -> ConnectionLimits {
+        ConnectionLimits {
+            max_frame_size: self.max_frame_size,
+            max_messages_per_sec: self.max_messages_per_sec,
+            max_connections_per_ip: self.max_connections_per_ip,
+            handshake_timeout: Duration::from_secs(self.handshake_timeout),
+        }
+    }
+
+    /// The join code of a game, a new random one every time if it should be generated,
+    /// `None` if the games are not protected
+    #[must_use]
+    pub fn join_code(&self) -> Option<String> {
+        self.join_code
+            .as_ref()
+            .map(|code| code.clone().unwrap_or_else(generate_join_code))
+    }
+
+    /// The questions file given by the user, or the default one
+    /// # Errors
+    /// - if the default questions file does not exist
+    pub fn questions_file(&self) -> anyhow::Result<PathBuf> {
+        match &self.questions_file {
+            Some(file) => Ok(file.clone()),
+            None => valid_questions_file(DEFAULT_QUESTIONS_FILE).map_err(anyhow::Error::msg),
+        }
+    }
+
+    /// The class roster, `None` if the players join without a student ID
+    /// # Errors
+    /// - if the roster cannot be loaded
+    pub fn roster(&self) -> anyhow::Result<Option<Roster>> {
+        self.roster.as_deref().map(Roster::load).transpose()
+    }
+
+    /// The TLS settings of the server, `None` if the connections are not encrypted
+    /// # Errors
+    /// - if the certificate or the key cannot be loaded
+    /// - if the self-signed certificate cannot be generated
+    pub fn tls(&self) -> anyhow::Result<Option<TlsSettings>> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Ok(Some(TlsSettings::from_pem(cert, key)?)),
+            _ if self.tls_self_signed => Ok(Some(TlsSettings::self_signed()?)),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Subcommand, Clone)]
+pub enum Command {
+    /// Print the games stored in the database (clihoot.sqlite if --db is not given) and exit
+    History {
+        /// Id of the game to print in detail, all games are listed if not given
+        session: Option<i64>,
+    },
+    /// Replay a game recorded with --journal in the teacher's terminal
+    Replay {
+        /// The recorded journal
+        journal: PathBuf,
+
+        /// How fast to replay the game, 2.0 is twice as fast as the recording
+        #[clap(short, long, default_value = "1.0", value_parser = valid_speed)]
+        speed: f64,
+    },
+    /// Be the teacher of another game on the server running on this computer, in this terminal
+    Attach {
+        /// PIN of the game; with the questions file, the new game gets it (a random one
+        /// if not given), without it, the game whose teacher lost the connection is taken over
+        #[clap(long, value_parser = valid_pin, required_unless_present = "questions_file")]
+        pin: Option<String>,
+
+        /// Questions of a new game, the server randomizes them the way it randomizes its own
+        #[clap(short, long, value_parser = valid_questions_file)]
+        questions_file: Option<PathBuf>,
+
+        /// Secret key of the game, written to the log when the teacher attached first,
+        /// needed to take the game back
+        #[clap(long, conflicts_with = "questions_file")]
+        key: Option<String>,
+
+        /// Where to write log messages to
+        #[clap(short, long, default_value = "clihoot_teacher_logs.log")]
+        log_file: PathBuf,
+    },
 }
@@ -0,0 +1,127 @@
+use std::{
+    io::{BufRead, Write},
+    net::TcpStream,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use actix::{dev::ToEnvelope, Actor, Addr, Handler, Message};
+use anyhow::Context;
+use log::error;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    messages::attach::{ToLobby, ToTeacher},
+    Lobby, Teacher,
+};
+
+/// The teacher of a lobby, in the same process as the lobby or attached from another one
+#[derive(Debug, Clone)]
+pub enum TeacherHandle {
+    Local(Addr<Teacher>),
+    /// messages for the teacher, written to its connection
+    Remote(UnboundedSender<ToTeacher>),
+}
+
+impl TeacherHandle {
+    pub fn do_send<M>(&self, msg: M)
+    where
+        M: Message + Into<ToTeacher> + Send + 'static,
+        M::Result: Send,
+        Teacher: Handler<M>,
+        <Teacher as Actor>::Context: ToEnvelope<Teacher, M>,
+    {
+        match self {
+            Self::Local(teacher) => teacher.do_send(msg),
+            Self::Remote(connection) => {
+                // the teacher lost the connection, it can attach again
+                let _ = connection.send(msg.into());
+            }
+        }
+    }
+
+    /// Whether the teacher is still there, the game may get another one when it is not
+    #[must_use]
+    pub fn connected(&self) -> bool {
+        match self {
+            Self::Local(teacher) => teacher.connected(),
+            Self::Remote(connection) => !connection.is_closed(),
+        }
+    }
+}
+
+impl PartialEq for TeacherHandle {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Local(teacher), Self::Local(other)) => teacher == other,
+            (Self::Remote(connection), Self::Remote(other)) => connection.same_channel(other),
+            _ => false,
+        }
+    }
+}
+
+impl From<Addr<Teacher>> for TeacherHandle {
+    fn from(teacher: Addr<Teacher>) -> Self {
+        Self::Local(teacher)
+    }
+}
+
+/// The lobby of the teacher, in the same process as the teacher or in the server
+/// the teacher attached to
+#[derive(Debug, Clone)]
+pub enum LobbyHandle {
+    Local(Addr<Lobby>),
+    /// connection to the server, the messages are written right away,
+    /// so the last one is sent even when the teacher stops after it
+    Remote(Arc<Mutex<TcpStream>>),
+}
+
+impl LobbyHandle {
+    pub fn do_send<M>(&self, msg: M)
+    where
+        M: Message + Into<ToLobby> + Send + 'static,
+        M::Result: Send,
+        Lobby: Handler<M>,
+        <Lobby as Actor>::Context: ToEnvelope<Lobby, M>,
+    {
+        match self {
+            Self::Local(lobby) => lobby.do_send(msg),
+            Self::Remote(connection) => {
+                let mut connection = connection.lock().unwrap_or_else(PoisonError::into_inner);
+                if let Err(err) = write_line(&mut *connection, &msg.into()) {
+                    error!("Failed to send a message to the server: {err:#}");
+                }
+            }
+        }
+    }
+}
+
+impl From<Addr<Lobby>> for LobbyHandle {
+    fn from(lobby: Addr<Lobby>) -> Self {
+        Self::Local(lobby)
+    }
+}
+
+/// Writes the message as a single line of JSON, the way the teachers and the server talk
+/// # Errors
+/// - if the message cannot be serialized or written
+pub fn write_line<T: Serialize>(writer: &mut impl Write, msg: &T) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(msg)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+
+    Ok(())
+}
+
+/// Reads a message written by [`write_line`], `None` when the connection was closed
+/// # Errors
+/// - if the line cannot be read or parsed
+pub fn read_line<T: DeserializeOwned>(reader: &mut impl BufRead) -> anyhow::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    let msg = serde_json::from_str(&line).context("Invalid message")?;
+    Ok(Some(msg))
+}
@@ -0,0 +1,445 @@
+use std::path::Path;
+
+use anyhow::Context;
+use common::questions::QuestionSet;
+use rusqlite::{params, Connection, OptionalExtension};
+use uuid::Uuid;
+
+use crate::lobby::{GameResults, SummaryRow};
+
+pub const DEFAULT_DB_FILE: &str = "clihoot.sqlite";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    quiz_name TEXT NOT NULL,
+    played_at TEXT NOT NULL,
+    questions_count INTEGER NOT NULL,
+    players_count INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS questions (
+    session_id INTEGER NOT NULL REFERENCES sessions(id),
+    question_index INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    code TEXT,
+    -- choices with their correctness as JSON, the quiz file may change later
+    choices TEXT NOT NULL,
+    PRIMARY KEY (session_id, question_index)
+);
+
+CREATE TABLE IF NOT EXISTS players (
+    session_id INTEGER NOT NULL REFERENCES sessions(id),
+    uuid TEXT NOT NULL,
+    nickname TEXT NOT NULL,
+    -- ID from the class roster, NULL when the game had none
+    student_id TEXT,
+    rank INTEGER NOT NULL,
+    total_points INTEGER NOT NULL,
+    correct_answers INTEGER NOT NULL,
+    answered_questions INTEGER NOT NULL,
+    left_game INTEGER NOT NULL,
+    PRIMARY KEY (session_id, uuid)
+);
+
+CREATE TABLE IF NOT EXISTS answers (
+    session_id INTEGER NOT NULL REFERENCES sessions(id),
+    question_index INTEGER NOT NULL,
+    player_uuid TEXT NOT NULL,
+    -- texts of the selected choices as JSON
+    selected_choices TEXT NOT NULL,
+    is_correct INTEGER NOT NULL,
+    answer_order INTEGER,
+    answered_at TEXT,
+    points INTEGER NOT NULL,
+    PRIMARY KEY (session_id, question_index, player_uuid)
+);
+";
+
+/// The databases created before the roster was supported have no `student_id` column
+fn add_student_id_column(conn: &Connection) -> rusqlite::Result<()> {
+    let has_column = conn
+        .prepare("SELECT 1 FROM pragma_table_info('players') WHERE name = 'student_id'")?
+        .exists([])?;
+    if !has_column {
+        conn.execute("ALTER TABLE players ADD COLUMN student_id TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// A stored game
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub id: i64,
+    pub quiz_name: String,
+    pub played_at: String,
+    pub questions_count: usize,
+    pub players_count: usize,
+}
+
+/// How a single question went in a single game
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestionSummary {
+    /// number of the question, starting from 1
+    pub question: usize,
+    pub text: String,
+    pub answered: usize,
+    pub correct: usize,
+    pub players: usize,
+    pub average_points: f64,
+}
+
+/// How a question went over all the stored games, questions are identified by their text
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestionTrend {
+    pub text: String,
+    pub sessions: usize,
+    pub answers: usize,
+    pub correct: usize,
+}
+
+/// Database of the finished games
+pub struct History {
+    conn: Connection,
+}
+
+impl History {
+    /// Opens the database (creating it if it does not exist)
+    /// # Errors
+    /// - if the database cannot be opened or is not a clihoot database
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Cannot open database {}", path.display()))?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("Cannot create tables in {}", path.display()))?;
+        add_student_id_column(&conn)
+            .with_context(|| format!("Cannot upgrade the tables in {}", path.display()))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Stores the results of the game together with the asked questions,
+    /// returns the id of the new session
+    /// # Errors
+    /// - if the game cannot be written to the database
+    pub fn save_game(
+        &mut self,
+        questions: &QuestionSet,
+        results: &GameResults,
+    ) -> anyhow::Result<i64> {
+        let tx = self.conn.transaction()?;
+
+        tx.execute(
+            "INSERT INTO sessions (quiz_name, played_at, questions_count, players_count)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                results.quiz_name,
+                results.exported_at.to_rfc3339(),
+                results.questions_asked,
+                results.summary.len(),
+            ],
+        )?;
+        let session_id = tx.last_insert_rowid();
+
+        for (index, question) in questions.iter().take(results.questions_asked).enumerate() {
+            let choices = serde_json::to_string(&question.choices)?;
+            tx.execute(
+                "INSERT INTO questions (session_id, question_index, text, code, choices)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    session_id,
+                    index + 1,
+                    question.text,
+                    question.code_block.as_ref().map(|block| &block.code),
+                    choices,
+                ],
+            )?;
+        }
+
+        for player in &results.summary {
+            tx.execute(
+                "INSERT INTO players (session_id, uuid, nickname, student_id, rank, total_points,
+                    correct_answers, answered_questions, left_game)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    session_id,
+                    player.player_uuid.to_string(),
+                    player.nickname,
+                    player.student_id,
+                    player.rank,
+                    player.total_points,
+                    player.correct_answers,
+                    player.answered_questions,
+                    player.left_game,
+                ],
+            )?;
+        }
+
+        for answer in &results.answers {
+            tx.execute(
+                "INSERT INTO answers (session_id, question_index, player_uuid, selected_choices,
+                    is_correct, answer_order, answered_at, points)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    answer.question,
+                    answer.player_uuid.to_string(),
+                    serde_json::to_string(&answer.selected_choices)?,
+                    answer.is_correct,
+                    answer.answer_order,
+                    answer.answered_at.map(|time| time.to_rfc3339()),
+                    answer.points,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+
+        Ok(session_id)
+    }
+
+    /// All stored games, the newest first
+    /// # Errors
+    /// - if the database cannot be read
+    pub fn sessions(&self) -> anyhow::Result<Vec<SessionSummary>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, quiz_name, played_at, questions_count, players_count
+             FROM sessions ORDER BY id DESC",
+        )?;
+
+        let sessions = statement
+            .query_map([], |row| {
+                Ok(SessionSummary {
+                    id: row.get(0)?,
+                    quiz_name: row.get(1)?,
+                    played_at: row.get(2)?,
+                    questions_count: row.get(3)?,
+                    players_count: row.get(4)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// # Errors
+    /// - if the database cannot be read
+    pub fn session(&self, id: i64) -> anyhow::Result<Option<SessionSummary>> {
+        let session = self
+            .conn
+            .query_row(
+                "SELECT id, quiz_name, played_at, questions_count, players_count
+                 FROM sessions WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(SessionSummary {
+                        id: row.get(0)?,
+                        quiz_name: row.get(1)?,
+                        played_at: row.get(2)?,
+                        questions_count: row.get(3)?,
+                        players_count: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(session)
+    }
+
+    /// Players of the game ordered by their rank
+    /// # Errors
+    /// - if the database cannot be read
+    pub fn players(&self, session_id: i64) -> anyhow::Result<Vec<SummaryRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT rank, uuid, nickname, total_points, correct_answers, answered_questions,
+                left_game, student_id
+             FROM players WHERE session_id = ?1 ORDER BY rank, nickname",
+        )?;
+
+        let players = statement
+            .query_map([session_id], |row| {
+                let uuid: String = row.get(1)?;
+                Ok(SummaryRow {
+                    rank: row.get(0)?,
+                    player_uuid: Uuid::parse_str(&uuid).unwrap_or_default(),
+                    nickname: row.get(2)?,
+                    student_id: row.get(7)?,
+                    // the history does not keep the class roster, only the IDs
+                    student_name: None,
+                    total_points: row.get(3)?,
+                    correct_answers: row.get(4)?,
+                    answered_questions: row.get(5)?,
+                    left_game: row.get(6)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(players)
+    }
+
+    /// # Errors
+    /// - if the database cannot be read
+    pub fn question_summaries(&self, session_id: i64) -> anyhow::Result<Vec<QuestionSummary>> {
+        let mut statement = self.conn.prepare(
+            "SELECT q.question_index, q.text,
+                COUNT(a.answer_order), COALESCE(SUM(a.is_correct), 0), COUNT(a.player_uuid),
+                COALESCE(AVG(a.points), 0.0)
+             FROM questions q
+             LEFT JOIN answers a
+                ON a.session_id = q.session_id AND a.question_index = q.question_index
+             WHERE q.session_id = ?1
+             GROUP BY q.question_index
+             ORDER BY q.question_index",
+        )?;
+
+        let questions = statement
+            .query_map([session_id], |row| {
+                Ok(QuestionSummary {
+                    question: row.get(0)?,
+                    text: row.get(1)?,
+                    answered: row.get(2)?,
+                    correct: row.get(3)?,
+                    players: row.get(4)?,
+                    average_points: row.get(5)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(questions)
+    }
+
+    /// Questions over all the games, the ones with the lowest ratio of correct answers first
+    /// # Errors
+    /// - if the database cannot be read
+    pub fn hardest_questions(&self, limit: usize) -> anyhow::Result<Vec<QuestionTrend>> {
+        let mut statement = self.conn.prepare(
+            "SELECT q.text, COUNT(DISTINCT q.session_id), COUNT(a.player_uuid),
+                COALESCE(SUM(a.is_correct), 0)
+             FROM questions q
+             JOIN answers a
+                ON a.session_id = q.session_id AND a.question_index = q.question_index
+             GROUP BY q.text
+             ORDER BY CAST(SUM(a.is_correct) AS REAL) / COUNT(a.player_uuid), q.text
+             LIMIT ?1",
+        )?;
+
+        let questions = statement
+            .query_map([limit], |row| {
+                Ok(QuestionTrend {
+                    text: row.get(0)?,
+                    sessions: row.get(1)?,
+                    answers: row.get(2)?,
+                    correct: row.get(3)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(questions)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * part as f64 / total as f64
+    }
+}
+
+// question texts can be long, the tables are easier to read with them shortened
+fn shorten(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let shortened: String = text.chars().take(max_chars - 3).collect();
+    format!("{shortened}...")
+}
+
+/// Prints the list of the stored games and the hardest questions,
+/// or the details of a single game if `session` is given
+/// # Errors
+/// - if the database cannot be read
+/// - if there is no game with the given id
+pub fn print_history(db: &Path, session: Option<i64>) -> anyhow::Result<()> {
+    let history = History::open(db)?;
+
+    let Some(id) = session else {
+        let sessions = history.sessions()?;
+        if sessions.is_empty() {
+            println!("No games are stored in {}", db.display());
+            return Ok(());
+        }
+
+        println!(
+            "{:>4}  {:<25}  {:<30}  {:>9}  {:>7}",
+            "ID", "PLAYED AT", "QUIZ", "QUESTIONS", "PLAYERS"
+        );
+        for s in sessions {
+            println!(
+                "{:>4}  {:<25}  {:<30}  {:>9}  {:>7}",
+                s.id,
+                s.played_at,
+                shorten(&s.quiz_name, 30),
+                s.questions_count,
+                s.players_count
+            );
+        }
+
+        println!("\nQuestions with the fewest correct answers:");
+        println!("{:>9}  {:>8}  QUESTION", "CORRECT", "SESSIONS");
+        for q in history.hardest_questions(10)? {
+            println!(
+                "{:>8.1}%  {:>8}  {}",
+                percent(q.correct, q.answers),
+                q.sessions,
+                shorten(&q.text, 60)
+            );
+        }
+
+        return Ok(());
+    };
+
+    let Some(session) = history.session(id)? else {
+        anyhow::bail!("There is no game with id {id} in {}", db.display());
+    };
+
+    println!("{} (played at {})\n", session.quiz_name, session.played_at);
+
+    println!(
+        "{:>4}  {:<30}  {:>6}  {:>7}  {:>8}",
+        "RANK", "PLAYER", "POINTS", "CORRECT", "ANSWERED"
+    );
+    for p in history.players(id)? {
+        let left = if p.left_game { " (left)" } else { "" };
+        println!(
+            "{:>4}  {:<30}  {:>6}  {:>7}  {:>8}",
+            p.rank,
+            shorten(&format!("{}{left}", p.nickname), 30),
+            p.total_points,
+            p.correct_answers,
+            p.answered_questions
+        );
+    }
+
+    println!();
+    println!(
+        "{:>2}  {:>9}  {:>8}  {:>10}  QUESTION",
+        "#", "CORRECT", "ANSWERED", "AVG POINTS"
+    );
+    for q in history.question_summaries(id)? {
+        println!(
+            "{:>2}  {:>8.1}%  {:>3} / {:<2}  {:>10.1}  {}",
+            q.question,
+            percent(q.correct, q.players),
+            q.answered,
+            q.players,
+            q.average_points,
+            shorten(&q.text, 60)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, LineWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use common::{
+    messages::{ClientNetworkMessage, ServerNetworkMessage},
+    questions::QuestionSet,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Something that happened in the game, the lobby messages are the ones
+/// that change the state of the game without a message from a player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// the first entry of every journal, with the questions in the order they are asked
+    GameStarted {
+        questions: QuestionSet,
+    },
+    FromClient {
+        player: Option<Uuid>,
+        message: ClientNetworkMessage,
+    },
+    ToClient {
+        player: Option<Uuid>,
+        message: ServerNetworkMessage,
+    },
+    StartQuestion,
+    EndQuestion {
+        index: usize,
+    },
+    SwitchToLeaderboard,
+    KickPlayer {
+        player_uuid: Uuid,
+        reason: Option<String>,
+    },
+    SetLock {
+        locked: bool,
+    },
+    DecideJoins {
+        players: Vec<Uuid>,
+        approved: bool,
+    },
+    Disconnect {
+        player_id: Uuid,
+    },
+}
+
+/// A single line of the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+impl JournalEntry {
+    /// Reads all entries of a JSONL journal
+    /// # Errors
+    /// - if the file cannot be read
+    /// - if some of the lines is not a valid entry
+    pub fn read_all(path: &Path) -> anyhow::Result<Vec<Self>> {
+        let file =
+            File::open(path).with_context(|| format!("Cannot open journal {}", path.display()))?;
+
+        let mut entries = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid entry on line {}", number + 1))?;
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+}
+
+/// JSONL journal of the game, shared by the lobby and the websockets
+#[derive(Debug, Clone)]
+pub struct Journal {
+    writer: Arc<Mutex<LineWriter<File>>>,
+}
+
+impl Journal {
+    /// Creates the journal file and records the questions of the game
+    /// # Errors
+    /// - if the file cannot be created
+    pub fn create(path: &Path, questions: &QuestionSet) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Cannot create journal {}", path.display()))?;
+
+        let journal = Self {
+            writer: Arc::new(Mutex::new(LineWriter::new(file))),
+        };
+        journal.write(&JournalEvent::GameStarted {
+            questions: questions.clone(),
+        })?;
+
+        Ok(journal)
+    }
+
+    /// Records the event, the game goes on even if the journal cannot be written
+    pub fn record(&self, event: &JournalEvent) {
+        if let Err(err) = self.write(event) {
+            error!("Failed to write to the journal: {err:#}");
+        }
+    }
+
+    fn write(&self, event: &JournalEvent) -> anyhow::Result<()> {
+        // same as `JournalEntry`, but the event does not have to be cloned
+        #[derive(Serialize)]
+        struct EntryRef<'a> {
+            at: DateTime<Utc>,
+            #[serde(flatten)]
+            event: &'a JournalEvent,
+        }
+
+        let line = serde_json::to_string(&EntryRef {
+            at: Utc::now(),
+            event,
+        })?;
+
+        let Ok(mut writer) = self.writer.lock() else {
+            bail!("The journal writer is poisoned");
+        };
+        writeln!(writer, "{line}")?;
+
+        Ok(())
+    }
+}
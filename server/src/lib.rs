@@ -1,7 +1,13 @@
 mod args;
+pub mod attach;
+pub mod history;
+pub mod journal;
 pub mod lobby;
 pub mod messages;
+pub mod replay;
+pub mod rooms;
 pub mod teacher;
+pub mod tls;
 pub mod websocket;
 
 pub use args::*;
@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{export::SummaryRow, state::Lobby};
+
+/// Share of the best and the worst players compared by the discrimination index
+pub const EXTREME_GROUP_RATIO: f64 = 0.27;
+
+/// How often a single choice was picked
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChoiceAnalysis {
+    pub text: String,
+    pub is_correct: bool,
+    pub picked_total: usize,
+    /// picked by the players in the upper 27 % of the leaderboard
+    pub picked_by_upper: usize,
+    /// picked by the players in the lower 27 % of the leaderboard
+    pub picked_by_lower: usize,
+}
+
+/// Item analysis of a single question
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuestionAnalysis {
+    /// number of the question, starting from 1
+    pub question: usize,
+    pub question_text: String,
+    pub players: usize,
+    pub answered: usize,
+    /// proportion of the players who answered correctly (not answering counts as wrong),
+    /// `None` if there were no players
+    pub difficulty: Option<f64>,
+    /// proportion correct in the upper group minus the proportion correct in the lower group,
+    /// `None` if there are not enough players to form the groups
+    pub discrimination: Option<f64>,
+    /// mean time in seconds from showing the choices to answering
+    pub mean_response_time: Option<f64>,
+    pub choices: Vec<ChoiceAnalysis>,
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn ratio(part: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64)
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn extreme_group_size(players: usize) -> usize {
+    // each group needs at least one player and the groups must not overlap
+    ((players as f64 * EXTREME_GROUP_RATIO).ceil() as usize).clamp(1, players / 2)
+}
+
+impl Lobby {
+    /// Computes the item analysis of the questions asked so far,
+    /// the `summary` (ordered by rank) decides who is in the upper and lower group
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn item_analysis(&self, summary: &[SummaryRow]) -> Vec<QuestionAnalysis> {
+        let players = summary.len();
+        let (upper, lower): (HashSet<Uuid>, HashSet<Uuid>) = if players >= 2 {
+            let size = extreme_group_size(players);
+            (
+                summary.iter().take(size).map(|p| p.player_uuid).collect(),
+                summary
+                    .iter()
+                    .rev()
+                    .take(size)
+                    .map(|p| p.player_uuid)
+                    .collect(),
+            )
+        } else {
+            (HashSet::new(), HashSet::new())
+        };
+
+        self.questions
+            .iter()
+            .take(self.questions_asked())
+            .enumerate()
+            .map(|(index, question)| {
+                let correct_choices: HashSet<Uuid> = question
+                    .choices
+                    .iter()
+                    .filter(|choice| choice.is_correct)
+                    .map(|choice| choice.id)
+                    .collect();
+
+                // only the answers of the players in the summary count,
+                // so the numbers match the rest of the export
+                let records: Vec<_> = self
+                    .results
+                    .get(&index)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(uuid, _)| summary.iter().any(|p| p.player_uuid == **uuid))
+                    .collect();
+
+                let correct_players: HashSet<Uuid> = records
+                    .iter()
+                    .filter(|(_, record)| record.selected_answers == correct_choices)
+                    .map(|(uuid, _)| **uuid)
+                    .collect();
+                let correct_in =
+                    |group: &HashSet<Uuid>| group.intersection(&correct_players).count();
+
+                let discrimination = if upper.is_empty() {
+                    None
+                } else {
+                    ratio(correct_in(&upper), upper.len())
+                        .zip(ratio(correct_in(&lower), lower.len()))
+                        .map(|(upper, lower)| upper - lower)
+                };
+
+                // the choices might have been shown a bit earlier on the clients,
+                // so the negative times are rounded to zero
+                let response_times: Vec<f64> = self
+                    .choices_shown_at
+                    .get(&index)
+                    .map(|shown_at| {
+                        records
+                            .iter()
+                            .map(|(_, record)| {
+                                (record.timestamp - *shown_at).num_milliseconds().max(0) as f64
+                                    / 1000.0
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let mean_response_time = (!response_times.is_empty())
+                    .then(|| response_times.iter().sum::<f64>() / response_times.len() as f64);
+
+                let choices = question
+                    .choices
+                    .iter()
+                    .map(|choice| {
+                        let picked_by = |group: Option<&HashSet<Uuid>>| {
+                            records
+                                .iter()
+                                .filter(|(uuid, record)| {
+                                    record.selected_answers.contains(&choice.id)
+                                        && group.is_none_or(|group| group.contains(uuid))
+                                })
+                                .count()
+                        };
+
+                        ChoiceAnalysis {
+                            text: choice.text.clone(),
+                            is_correct: choice.is_correct,
+                            picked_total: picked_by(None),
+                            picked_by_upper: picked_by(Some(&upper)),
+                            picked_by_lower: picked_by(Some(&lower)),
+                        }
+                    })
+                    .collect();
+
+                QuestionAnalysis {
+                    question: index + 1,
+                    question_text: question.text.clone(),
+                    players,
+                    answered: records.len(),
+                    difficulty: ratio(correct_players.len(), players),
+                    discrimination,
+                    mean_response_time,
+                    choices,
+                }
+            })
+            .collect()
+    }
+}
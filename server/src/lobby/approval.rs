@@ -0,0 +1,92 @@
+use actix::Context;
+use common::messages::{
+    network::{CanJoin, JoinResponse, PlayerData, WaitingForApproval},
+    ServerNetworkMessage,
+};
+use log::info;
+use uuid::Uuid;
+
+use crate::messages::teacher::ShowApprovalQueue;
+
+use super::state::{JoinedPlayer, Lobby};
+
+impl Lobby {
+    /// Players waiting for the teacher's approval, in the order they came
+    #[must_use]
+    pub fn get_awaiting_approval(&self) -> Vec<PlayerData> {
+        let mut players: Vec<_> = self.awaiting_approval.values().collect();
+
+        players.sort_by_key(|x| x.joined_at);
+
+        players.into_iter().map(JoinedPlayer::data).collect()
+    }
+
+    /// Puts the player in the queue, the teacher decides whether it joins
+    pub fn await_approval(&mut self, player: JoinedPlayer) {
+        info!("{} waits for the teacher's approval", player.uuid);
+
+        player.do_send(ServerNetworkMessage::WaitingForApproval(
+            WaitingForApproval { uuid: player.uuid },
+        ));
+        self.awaiting_approval.insert(player.uuid, player);
+        self.send_approval_queue_to_teacher();
+    }
+
+    /// Lets the player in, returns whether the player was waiting for it
+    pub fn approve_player(&mut self, id: Uuid, ctx: &mut Context<Self>) -> bool {
+        let Some(mut player) = self.awaiting_approval.remove(&id) else {
+            return false;
+        };
+        info!("{id} was let in by the teacher");
+
+        player.joined_at = chrono::Utc::now();
+        if let Some(old_uuid) = player.resumes {
+            self.restore_resumed_player(old_uuid, id);
+        }
+        if let Some(student_id) = &player.student_id {
+            self.student_ids.insert(id, student_id.clone());
+        }
+        if let Some(resume_token) = &player.resume_token {
+            self.resume_tokens.insert(id, resume_token.clone());
+        }
+        self.joined_players.insert(id, player);
+
+        // the player learns about joining before it gets any update of the players
+        if let Some(player) = self.joined_players.get(&id) {
+            player.do_send(ServerNetworkMessage::JoinResponse(JoinResponse {
+                uuid: id,
+                can_join: CanJoin::Yes,
+                quiz_name: self.questions.quiz_name.clone(),
+                players: self.get_players(),
+            }));
+        }
+
+        let _ = self.player_joined(id, ctx);
+        true
+    }
+
+    /// Does not let the player in, returns whether the player was waiting for approval
+    pub fn refuse_player(&mut self, id: Uuid, reason: &str) -> bool {
+        let Some(player) = self.awaiting_approval.remove(&id) else {
+            return false;
+        };
+        info!("{id} was not let in: {reason}");
+
+        player.do_send(ServerNetworkMessage::JoinResponse(JoinResponse {
+            uuid: id,
+            can_join: CanJoin::No(reason.to_owned()),
+            quiz_name: self.questions.quiz_name.clone(),
+            players: self.get_players(),
+        }));
+        true
+    }
+
+    /// Lets the teacher know who is waiting for the approval
+    pub fn send_approval_queue_to_teacher(&self) {
+        if let Some(teacher) = &self.teacher {
+            teacher.do_send(ShowApprovalQueue {
+                players: self.get_awaiting_approval(),
+            });
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use common::messages::network::PlayerData;
+use itertools::Itertools;
+use log::info;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{
+    analysis::QuestionAnalysis,
+    state::{Lobby, Phase},
+};
+
+/// Answer of a single player to a single question
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AnswerRow {
+    /// number of the question, starting from 1
+    pub question: usize,
+    pub question_text: String,
+    pub player_uuid: Uuid,
+    pub nickname: String,
+    /// ID and name of the student from the class roster, `None` without a roster
+    pub student_id: Option<String>,
+    pub student_name: Option<String>,
+    /// texts of the selected choices, empty if the player did not answer
+    pub selected_choices: Vec<String>,
+    /// whether the player selected exactly the correct choices
+    pub is_correct: bool,
+    /// 1 for the fastest player, `None` if the player did not answer
+    pub answer_order: Option<usize>,
+    pub answered_at: Option<DateTime<Utc>>,
+    pub points: usize,
+}
+
+/// Totals of a single player over the whole game
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SummaryRow {
+    /// players with the same number of points share the rank
+    pub rank: usize,
+    pub player_uuid: Uuid,
+    pub nickname: String,
+    /// ID and name of the student from the class roster, `None` without a roster
+    pub student_id: Option<String>,
+    pub student_name: Option<String>,
+    pub total_points: usize,
+    pub correct_answers: usize,
+    pub answered_questions: usize,
+    /// whether the player disconnected or was kicked before the export
+    pub left_game: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GameResults {
+    pub quiz_name: String,
+    pub exported_at: DateTime<Utc>,
+    /// number of questions that were asked so far
+    pub questions_asked: usize,
+    pub answers: Vec<AnswerRow>,
+    pub summary: Vec<SummaryRow>,
+    pub analysis: Vec<QuestionAnalysis>,
+}
+
+/// Cell of a CSV file with text a player could have written, a spreadsheet would run
+/// a cell starting with one of these characters as a formula
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Text for a CSV cell which the spreadsheets show as text, never as a formula
+fn spreadsheet_text(text: &str) -> String {
+    if text.starts_with(FORMULA_PREFIXES) {
+        format!("'{text}")
+    } else {
+        text.to_owned()
+    }
+}
+
+impl Lobby {
+    /// Number of questions that have been shown to the players
+    pub(super) fn questions_asked(&self) -> usize {
+        match self.phase {
+            Phase::WaitingForPlayers => 0,
+            Phase::ActiveQuestion(index)
+            | Phase::AfterQuestion(index)
+            | Phase::ShowingLeaderboard(index) => index + 1,
+            Phase::GameEnded => self.questions.len(),
+        }
+    }
+
+    /// Student ID and name of the player from the class roster
+    fn student(&self, player_uuid: &Uuid) -> (Option<String>, Option<String>) {
+        let student_id = self.student_ids.get(player_uuid).cloned();
+        let student_name = student_id
+            .as_deref()
+            .and_then(|id| self.roster.as_ref()?.find(id))
+            .map(|student| student.name.clone());
+
+        (student_id, student_name)
+    }
+
+    /// Collects the results of all players (including the ones who already left)
+    /// for all questions asked so far
+    #[must_use]
+    pub fn game_results(&self) -> GameResults {
+        let questions_asked = self.questions_asked();
+
+        let players: Vec<(PlayerData, bool)> = self
+            .get_players()
+            .into_iter()
+            .map(|player| (player, false))
+            .chain(
+                // players who left without answering anything are not interesting
+                self.left_players
+                    .values()
+                    .filter(|player| {
+                        !self.joined_players.contains_key(&player.uuid)
+                            && self
+                                .results
+                                .values()
+                                .any(|records| records.contains_key(&player.uuid))
+                    })
+                    .sorted_by(|a, b| a.nickname.cmp(&b.nickname))
+                    .map(|player| (player.clone(), true)),
+            )
+            .collect();
+
+        let mut answers = Vec::with_capacity(questions_asked * players.len());
+        for (index, question) in self.questions.iter().take(questions_asked).enumerate() {
+            let correct_choices: HashSet<Uuid> = question
+                .choices
+                .iter()
+                .filter(|choice| choice.is_correct)
+                .map(|choice| choice.id)
+                .collect();
+            let records = self.results.get(&index);
+
+            for (player, _) in &players {
+                let record = records.and_then(|records| records.get(&player.uuid));
+                let (student_id, student_name) = self.student(&player.uuid);
+
+                answers.push(AnswerRow {
+                    question: index + 1,
+                    question_text: question.text.clone(),
+                    player_uuid: player.uuid,
+                    nickname: player.nickname.clone(),
+                    student_id,
+                    student_name,
+                    // keep the order of the choices from the quiz file
+                    selected_choices: record.map_or_else(Vec::new, |record| {
+                        question
+                            .choices
+                            .iter()
+                            .filter(|choice| record.selected_answers.contains(&choice.id))
+                            .map(|choice| choice.text.clone())
+                            .collect()
+                    }),
+                    is_correct: record.is_some_and(|r| r.selected_answers == correct_choices),
+                    answer_order: record.map(|r| r.answer_order),
+                    answered_at: record.map(|r| r.timestamp),
+                    points: record.map_or(0, |r| r.points_awarded),
+                });
+            }
+        }
+
+        let mut totals: HashMap<Uuid, (usize, usize, usize)> = HashMap::new();
+        for row in &answers {
+            let (points, correct, answered) = totals.entry(row.player_uuid).or_default();
+            *points += row.points;
+            *correct += usize::from(row.is_correct);
+            *answered += usize::from(row.answer_order.is_some());
+        }
+
+        let mut summary: Vec<SummaryRow> = players
+            .into_iter()
+            .map(|(player, left_game)| {
+                let (total_points, correct_answers, answered_questions) =
+                    totals.get(&player.uuid).copied().unwrap_or_default();
+                let (student_id, student_name) = self.student(&player.uuid);
+                SummaryRow {
+                    rank: 0,
+                    player_uuid: player.uuid,
+                    nickname: player.nickname,
+                    student_id,
+                    student_name,
+                    total_points,
+                    correct_answers,
+                    answered_questions,
+                    left_game,
+                }
+            })
+            // stable sort, players with the same points stay in the order they joined
+            .sorted_by_key(|row| std::cmp::Reverse(row.total_points))
+            .collect();
+
+        for i in 0..summary.len() {
+            summary[i].rank = if i > 0 && summary[i - 1].total_points == summary[i].total_points {
+                summary[i - 1].rank
+            } else {
+                i + 1
+            };
+        }
+
+        let analysis = self.item_analysis(&summary);
+
+        GameResults {
+            quiz_name: self.questions.quiz_name.clone(),
+            exported_at: Utc::now(),
+            questions_asked,
+            answers,
+            summary,
+            analysis,
+        }
+    }
+}
+
+impl GameResults {
+    /// Writes the results to the directory as `<quiz>_<time>_answers.csv`,
+    /// `<quiz>_<time>_summary.csv`, `<quiz>_<time>_analysis.csv` and `<quiz>_<time>.json`,
+    /// returns the paths of the files
+    /// # Errors
+    /// - if the directory cannot be created
+    /// - if some of the files cannot be written
+    pub fn write_to_dir(&self, dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Cannot create results directory {}", dir.display()))?;
+
+        let quiz_name: String = self
+            .quiz_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let prefix = format!(
+            "{quiz_name}_{}",
+            self.exported_at.format("%Y-%m-%d_%H-%M-%S")
+        );
+
+        let answers_path = dir.join(format!("{prefix}_answers.csv"));
+        self.write_answers_csv(&answers_path)?;
+
+        let summary_path = dir.join(format!("{prefix}_summary.csv"));
+        self.write_summary_csv(&summary_path)?;
+
+        let analysis_path = dir.join(format!("{prefix}_analysis.csv"));
+        self.write_analysis_csv(&analysis_path)?;
+
+        let json_path = dir.join(format!("{prefix}.json"));
+        let file = File::create(&json_path)
+            .with_context(|| format!("Cannot create {}", json_path.display()))?;
+        serde_json::to_writer_pretty(file, self)?;
+
+        info!("Results exported to {}", dir.display());
+
+        Ok(vec![answers_path, summary_path, analysis_path, json_path])
+    }
+
+    fn write_answers_csv(&self, path: &Path) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(path)
+            .with_context(|| format!("Cannot create {}", path.display()))?;
+
+        writer.write_record([
+            "question",
+            "question_text",
+            "player_uuid",
+            "nickname",
+            "student_id",
+            "student_name",
+            "selected_choices",
+            "is_correct",
+            "answer_order",
+            "answered_at",
+            "points",
+        ])?;
+
+        for row in &self.answers {
+            writer.write_record([
+                row.question.to_string(),
+                spreadsheet_text(&row.question_text),
+                row.player_uuid.to_string(),
+                spreadsheet_text(&row.nickname),
+                spreadsheet_text(row.student_id.as_deref().unwrap_or_default()),
+                spreadsheet_text(row.student_name.as_deref().unwrap_or_default()),
+                spreadsheet_text(&row.selected_choices.join("; ")),
+                row.is_correct.to_string(),
+                row.answer_order.map_or_else(String::new, |o| o.to_string()),
+                row.answered_at.map_or_else(String::new, |t| t.to_rfc3339()),
+                row.points.to_string(),
+            ])?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_summary_csv(&self, path: &Path) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(path)
+            .with_context(|| format!("Cannot create {}", path.display()))?;
+
+        writer.write_record([
+            "rank",
+            "player_uuid",
+            "nickname",
+            "student_id",
+            "student_name",
+            "total_points",
+            "correct_answers",
+            "answered_questions",
+            "left_game",
+        ])?;
+
+        for row in &self.summary {
+            writer.write_record([
+                row.rank.to_string(),
+                row.player_uuid.to_string(),
+                spreadsheet_text(&row.nickname),
+                spreadsheet_text(row.student_id.as_deref().unwrap_or_default()),
+                spreadsheet_text(row.student_name.as_deref().unwrap_or_default()),
+                row.total_points.to_string(),
+                row.correct_answers.to_string(),
+                row.answered_questions.to_string(),
+                row.left_game.to_string(),
+            ])?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// one row per choice, the statistics of the question are repeated in every row
+    fn write_analysis_csv(&self, path: &Path) -> anyhow::Result<()> {
+        let mut writer = csv::Writer::from_path(path)
+            .with_context(|| format!("Cannot create {}", path.display()))?;
+
+        writer.write_record([
+            "question",
+            "question_text",
+            "players",
+            "answered",
+            "difficulty",
+            "discrimination",
+            "mean_response_time",
+            "choice",
+            "is_correct",
+            "picked_total",
+            "picked_by_upper",
+            "picked_by_lower",
+        ])?;
+
+        let optional = |value: Option<f64>| value.map_or_else(String::new, |v| format!("{v:.3}"));
+
+        for question in &self.analysis {
+            for choice in &question.choices {
+                writer.write_record([
+                    question.question.to_string(),
+                    spreadsheet_text(&question.question_text),
+                    question.players.to_string(),
+                    question.answered.to_string(),
+                    optional(question.difficulty),
+                    optional(question.discrimination),
+                    optional(question.mean_response_time),
+                    spreadsheet_text(&choice.text),
+                    choice.is_correct.to_string(),
+                    choice.picked_total.to_string(),
+                    choice.picked_by_upper.to_string(),
+                    choice.picked_by_lower.to_string(),
+                ])?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player_row(nickname: &str) -> SummaryRow {
+        SummaryRow {
+            rank: 1,
+            player_uuid: Uuid::new_v4(),
+            nickname: nickname.to_string(),
+            student_id: None,
+            student_name: None,
+            total_points: 1000,
+            correct_answers: 1,
+            answered_questions: 1,
+            left_game: false,
+        }
+    }
+
+    #[test]
+    fn formulas_are_written_as_text() {
+        assert_eq!(
+            spreadsheet_text("=HYPERLINK(\"http://evil\")"),
+            "'=HYPERLINK(\"http://evil\")"
+        );
+        assert_eq!(spreadsheet_text("@SUM(A1:A9)"), "'@SUM(A1:A9)");
+        assert_eq!(spreadsheet_text("+1"), "'+1");
+        assert_eq!(spreadsheet_text("-1"), "'-1");
+        assert_eq!(spreadsheet_text("\tx"), "'\tx");
+        assert_eq!(spreadsheet_text("\rx"), "'\rx");
+        assert_eq!(spreadsheet_text("alice=bob"), "alice=bob");
+        assert_eq!(spreadsheet_text(""), "");
+    }
+
+    #[test]
+    fn formula_nickname_is_escaped_in_summary() -> anyhow::Result<()> {
+        let nickname = "=HYPERLINK(\"http://evil\",\"click\")";
+        let results = GameResults {
+            quiz_name: "quiz".to_string(),
+            exported_at: Utc::now(),
+            questions_asked: 0,
+            answers: Vec::new(),
+            summary: vec![player_row(nickname)],
+            analysis: Vec::new(),
+        };
+
+        let path = std::env::temp_dir().join(format!("clihoot-summary-{}.csv", Uuid::new_v4()));
+        results.write_summary_csv(&path)?;
+
+        let mut reader = csv::Reader::from_path(&path)?;
+        let row = reader.records().next().expect("Missing the player")?;
+        assert_eq!(&row[2], format!("'{nickname}"));
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+}
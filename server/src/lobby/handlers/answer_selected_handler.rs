@@ -53,6 +53,25 @@ impl Handler<AnswerSelected> for Lobby {
             bail!("Player {id} selected more than one answer, but the question is not multichoice");
         }
 
+        // the answers must be choices of the question, anything else would only get
+        // into the results and break the statistics
+        let choices = &self.questions[msg.question_index].choices;
+        if msg.answers.len() > choices.len() {
+            bail!(
+                "Player {id} selected {} answers, but the question has only {} choices",
+                msg.answers.len(),
+                choices.len()
+            );
+        }
+
+        if let Some(unknown) = msg
+            .answers
+            .iter()
+            .find(|answer| !choices.iter().any(|choice| choice.id == **answer))
+        {
+            bail!("Player {id} selected {unknown}, which is not a choice of the question");
+        }
+
         let answer_order = self.results.entry(msg.question_index).or_default().len();
         debug!("Player {id} answered as {answer_order}th player");
 
@@ -78,7 +97,7 @@ impl Handler<AnswerSelected> for Lobby {
         );
 
         // if the last player answered, notify self of the end of the question
-        if self.results.entry(msg.question_index).or_default().len() == self.joined_players.len() {
+        if self.everybody_answered(msg.question_index) {
             ctx.notify(EndQuestion {
                 index: msg.question_index,
             });
@@ -0,0 +1,29 @@
+use actix::{Context, Handler};
+use log::warn;
+
+use crate::{
+    messages::{teacher::ShowWarning, websocket::ConnectionWarning},
+    Lobby,
+};
+
+impl Handler<ConnectionWarning> for Lobby {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConnectionWarning, _: &mut Context<Self>) -> Self::Result {
+        let client = msg
+            .player_id
+            .and_then(|id| self.joined_players.get(&id))
+            .map_or_else(
+                || msg.who.to_string(),
+                |player| format!("{} ({})", player.nickname, msg.who),
+            );
+
+        warn!("Client {client}: {}", msg.reason);
+
+        if let Some(teacher) = &self.teacher {
+            teacher.do_send(ShowWarning {
+                message: format!("{client}: {}", msg.reason),
+            });
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use actix::{Context, Handler};
+use common::constants::JOIN_NOT_APPROVED_MSG;
+
+use crate::{journal::JournalEvent, messages::lobby::DecideJoins, Lobby};
+
+use log::{debug, warn};
+
+impl Handler<DecideJoins> for Lobby {
+    type Result = ();
+
+    fn handle(&mut self, msg: DecideJoins, ctx: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Received DecideJoins message in Lobby; approved: {}",
+            msg.approved
+        );
+        self.record(&JournalEvent::DecideJoins {
+            players: msg.players.clone(),
+            approved: msg.approved,
+        });
+
+        for id in msg.players {
+            let decided = if msg.approved {
+                self.approve_player(id, ctx)
+            } else {
+                self.refuse_player(id, JOIN_NOT_APPROVED_MSG)
+            };
+
+            if !decided {
+                warn!("{id} was not waiting for approval");
+            }
+        }
+
+        self.send_approval_queue_to_teacher();
+    }
+}
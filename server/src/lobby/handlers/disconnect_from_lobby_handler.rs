@@ -1,17 +1,26 @@
 use actix::{Context, Handler};
 
-use crate::{messages::websocket::DisconnectFromLobby, Lobby};
+use crate::{journal::JournalEvent, messages::websocket::DisconnectFromLobby, Lobby};
 use log::info;
 
 /// Handler for Disconnect message.
 impl Handler<DisconnectFromLobby> for Lobby {
     type Result = ();
 
-    fn handle(&mut self, msg: DisconnectFromLobby, _: &mut Context<Self>) {
-        if self.joined_players.remove(&msg.player_id).is_some() {
+    fn handle(&mut self, msg: DisconnectFromLobby, ctx: &mut Context<Self>) {
+        self.record(&JournalEvent::Disconnect {
+            player_id: msg.player_id,
+        });
+
+        if self.remove_player(&msg.player_id) {
             info!("{} disconnected", msg.player_id);
 
-            let _ = self.send_players_update(Some(&msg.player_id));
+            let _ = self.player_left(msg.player_id, ctx);
+        }
+
+        if self.awaiting_approval.remove(&msg.player_id).is_some() {
+            info!("{} left before the teacher let them in", msg.player_id);
+            self.send_approval_queue_to_teacher();
         }
     }
 }
@@ -3,6 +3,7 @@ use anyhow::bail;
 use log::debug;
 
 use crate::{
+    journal::JournalEvent,
     lobby::{Lobby, Phase},
     messages::lobby::EndQuestion,
 };
@@ -12,6 +13,7 @@ impl Handler<EndQuestion> for Lobby {
 
     fn handle(&mut self, msg: EndQuestion, _: &mut Context<Self>) -> Self::Result {
         debug!("Received EndQuestion message in Lobby; ending question");
+        self.record(&JournalEvent::EndQuestion { index: msg.index });
 
         // check that it's the correct phase
         let Phase::ActiveQuestion(question) = self.phase else {
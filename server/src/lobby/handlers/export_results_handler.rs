@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+use actix::{Context, Handler};
+use log::debug;
+
+use crate::{lobby::Lobby, messages::lobby::ExportResults};
+
+impl Handler<ExportResults> for Lobby {
+    type Result = anyhow::Result<Vec<PathBuf>>;
+
+    fn handle(&mut self, _msg: ExportResults, _: &mut Context<Self>) -> Self::Result {
+        debug!("Received ExportResults message in Lobby; exporting results");
+
+        self.export_results()
+    }
+}
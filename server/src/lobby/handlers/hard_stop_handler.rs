@@ -1,5 +1,4 @@
-use actix::{Context, Handler};
-use actix_rt::System;
+use actix::{ActorContext, Context, Handler};
 use log::debug;
 
 use crate::{messages::lobby::HardStop, Lobby};
@@ -7,8 +6,9 @@ use crate::{messages::lobby::HardStop, Lobby};
 impl Handler<HardStop> for Lobby {
     type Result = ();
 
-    fn handle(&mut self, _msg: HardStop, _: &mut Context<Self>) {
-        debug!("Received HardStop message in Lobby; stopping server");
-        System::current().stop();
+    fn handle(&mut self, _msg: HardStop, ctx: &mut Context<Self>) {
+        debug!("Received HardStop message in Lobby; stopping the game");
+        // the other games of the server go on, the server stops with the last one
+        ctx.stop();
     }
 }
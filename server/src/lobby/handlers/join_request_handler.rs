@@ -1,19 +1,23 @@
 use actix::Handler;
 use common::{
-    constants::{LOBBY_LOCKED_MSG, NICKNAME_ALREADY_TAKEN_MSG, PLAYER_NOT_IN_WAITING_LIST_MSG},
+    constants::{
+        APPROVAL_CLOSED_MSG, AVATARS, COLOR_AND_AVATAR_TAKEN_MSG, INVALID_AVATAR_MSG,
+        LOBBY_LOCKED_MSG, NICKNAME_ALREADY_TAKEN_MSG, PLAYER_NOT_IN_WAITING_LIST_MSG,
+    },
     messages::network::{CanJoin, JoinResponse},
 };
 use log::debug;
 
 use crate::{
-    lobby::{JoinedPlayer, Lobby},
+    lobby::{JoinedPlayer, Lobby, Phase},
     messages::client::JoinRequest,
 };
 
 impl Handler<JoinRequest> for Lobby {
-    type Result = JoinResponse;
+    type Result = Option<JoinResponse>;
 
-    fn handle(&mut self, msg: JoinRequest, _ctx: &mut Self::Context) -> Self::Result {
+    #[allow(clippy::too_many_lines)]
+    fn handle(&mut self, msg: JoinRequest, ctx: &mut Self::Context) -> Self::Result {
         debug!(
             "Received JoinRequest message: {:?} from {:?}",
             msg, msg.addr
@@ -27,50 +31,125 @@ impl Handler<JoinRequest> for Lobby {
         };
 
         if self.locked {
-            return JoinResponse {
+            return Some(JoinResponse {
                 can_join: CanJoin::No(LOBBY_LOCKED_MSG.to_owned()),
                 ..result
-            };
+            });
         }
 
         let id = msg.player_data.uuid;
         if !self.waiting_players.contains(&id) {
-            return JoinResponse {
+            return Some(JoinResponse {
                 can_join: CanJoin::No(PLAYER_NOT_IN_WAITING_LIST_MSG.to_owned()),
                 ..result
-            };
+            });
         }
 
         if self
             .joined_players
             .values()
+            .chain(self.awaiting_approval.values())
             .any(|x| x.nickname == msg.player_data.nickname)
         {
-            return JoinResponse {
+            return Some(JoinResponse {
                 can_join: CanJoin::No(NICKNAME_ALREADY_TAKEN_MSG.to_owned()),
                 ..result
-            };
+            });
+        }
+
+        let avatar = msg.player_data.avatar.as_deref();
+        if avatar.is_some_and(|avatar| !AVATARS.contains(&avatar)) {
+            return Some(JoinResponse {
+                can_join: CanJoin::No(INVALID_AVATAR_MSG.to_owned()),
+                ..result
+            });
+        }
+
+        if self
+            .get_players()
+            .iter()
+            .chain(self.get_awaiting_approval().iter())
+            .any(|x| x.looks_like(msg.player_data.display_color(), avatar))
+        {
+            return Some(JoinResponse {
+                can_join: CanJoin::No(COLOR_AND_AVATAR_TAKEN_MSG.to_owned()),
+                ..result
+            });
+        }
+
+        let student_id = match self.check_student_id(msg.student_id.as_deref()) {
+            Ok(student_id) => student_id,
+            Err(reason) => {
+                return Some(JoinResponse {
+                    can_join: CanJoin::No(reason),
+                    ..result
+                });
+            }
+        };
+
+        let resumes = match self.find_resumed_player(
+            &msg.player_data.nickname,
+            student_id.as_deref(),
+            msg.resume_token.as_deref(),
+        ) {
+            Ok(resumes) => resumes,
+            Err(reason) => {
+                return Some(JoinResponse {
+                    can_join: CanJoin::No(reason),
+                    ..result
+                });
+            }
+        };
+
+        // the players who were in the game before (or in the resumed one) were already let in,
+        // they come back without asking the teacher again
+        let needs_approval =
+            self.approve_joins && resumes.is_none() && !self.left_players.contains_key(&id);
+
+        if needs_approval && self.phase != Phase::WaitingForPlayers {
+            return Some(JoinResponse {
+                can_join: CanJoin::No(APPROVAL_CLOSED_MSG.to_owned()),
+                ..result
+            });
         }
 
         self.waiting_players.retain(|&x| x != id);
-        self.joined_players.insert(
-            id,
-            JoinedPlayer {
-                addr: msg.addr,
-                color: msg.player_data.color,
-                nickname: msg.player_data.nickname,
-                uuid: msg.player_data.uuid,
-                joined_at: chrono::Utc::now(),
-            },
-        );
+        let player = JoinedPlayer {
+            addr: msg.addr,
+            color: msg.player_data.color,
+            color_index: msg.player_data.color_index,
+            avatar: msg.player_data.avatar,
+            nickname: msg.player_data.nickname,
+            uuid: msg.player_data.uuid,
+            joined_at: chrono::Utc::now(),
+            capabilities: msg.capabilities,
+            student_id,
+            resume_token: msg.resume_token,
+            resumes,
+        };
 
-        // do NOT send update to the player that just joined
-        let _ = self.send_players_update(Some(&id));
+        if needs_approval {
+            self.await_approval(player);
+            return None;
+        }
 
-        JoinResponse {
+        if let Some(old_uuid) = player.resumes {
+            self.restore_resumed_player(old_uuid, id);
+        }
+        if let Some(student_id) = &player.student_id {
+            self.student_ids.insert(id, student_id.clone());
+        }
+        if let Some(resume_token) = &player.resume_token {
+            self.resume_tokens.insert(id, resume_token.clone());
+        }
+        self.joined_players.insert(id, player);
+
+        let _ = self.player_joined(id, ctx);
+
+        Some(JoinResponse {
             can_join: CanJoin::Yes,
             players: self.get_players(),
             ..result
-        }
+        })
     }
 }
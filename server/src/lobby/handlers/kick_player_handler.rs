@@ -1,17 +1,18 @@
 use actix::{Context, Handler};
 
-use crate::{
-    messages::{lobby::KickPlayer, websocket::GracefulStop},
-    Lobby,
-};
+use crate::{journal::JournalEvent, messages::lobby::KickPlayer, Lobby};
 
 use log::{debug, info, warn};
 
 impl Handler<KickPlayer> for Lobby {
     type Result = anyhow::Result<()>;
 
-    fn handle(&mut self, msg: KickPlayer, _: &mut Context<Self>) -> Self::Result {
+    fn handle(&mut self, msg: KickPlayer, ctx: &mut Context<Self>) -> Self::Result {
         debug!("Received KickPlayer message in Lobby; kicking player");
+        self.record(&JournalEvent::KickPlayer {
+            player_uuid: msg.player_uuid,
+            reason: msg.reason.clone(),
+        });
 
         let socket = if let Some(socket) = self.joined_players.get(&msg.player_uuid) {
             socket.addr.clone()
@@ -20,15 +21,15 @@ impl Handler<KickPlayer> for Lobby {
             return Ok(());
         };
 
-        if self.joined_players.remove(&msg.player_uuid).is_none() {
+        if !self.remove_player(&msg.player_uuid) {
             return Ok(());
         }
 
-        socket.do_send(GracefulStop { reason: msg.reason });
+        socket.stop(msg.reason);
 
         info!("{} was kicked by teacher", msg.player_uuid);
 
-        self.send_players_update(Some(&msg.player_uuid))?;
+        self.player_left(msg.player_uuid, ctx)?;
 
         Ok(())
     }
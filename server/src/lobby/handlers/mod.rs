@@ -1,11 +1,16 @@
 pub mod answer_selected_handler;
+mod connection_warning_handler;
+mod decide_joins_handler;
 mod disconnect_from_lobby_handler;
 mod end_question_handler;
+mod export_results_handler;
 mod hard_stop_handler;
 mod join_request_handler;
 mod kick_player_handler;
 mod register_teacher_handler;
 mod set_lock_message_handler;
+mod set_player_away_handler;
 mod start_question_handler;
+mod start_replay_handler;
 pub mod switch_to_leaderboard_handler;
 mod try_join_request_handler;
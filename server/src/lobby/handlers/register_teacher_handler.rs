@@ -1,17 +1,67 @@
 use actix::{Context, Handler};
 
-use crate::{messages::lobby::RegisterTeacher, Lobby};
+use crate::{
+    messages::{
+        attach::Attached,
+        lobby::RegisterTeacher,
+        teacher::{ShowFingerprint, ShowJoinCode, ShowPin},
+    },
+    Lobby,
+};
 
-use log::debug;
+use log::{debug, error, warn};
 
 impl Handler<RegisterTeacher> for Lobby {
-    type Result = ();
+    type Result = Result<Attached, String>;
 
     fn handle(&mut self, msg: RegisterTeacher, _: &mut Context<Self>) -> Self::Result {
+        // the teacher who lost the connection can attach again with the key of the game,
+        // nobody else can take the game
+        if let Some(teacher) = &self.teacher {
+            if teacher.connected() {
+                return Err(format!("The game {} already has a teacher", self.pin));
+            }
+            if msg.key.as_deref() != Some(self.teacher_key.as_str()) {
+                warn!(
+                    "Refused a teacher with a wrong key for the game {}",
+                    self.pin
+                );
+                return Err(format!("Wrong teacher key for the game {}", self.pin));
+            }
+        }
+
         debug!("Received RegisterTeacherMessage in Lobby; unlocking lobby");
+        if let Some(tls) = &self.tls {
+            msg.teacher.do_send(ShowFingerprint {
+                fingerprint: tls.fingerprint.clone(),
+            });
+        }
+        msg.teacher.do_send(ShowPin {
+            pin: self.pin.clone(),
+        });
+        if let Some(join_code) = &self.join_code {
+            msg.teacher.do_send(ShowJoinCode {
+                join_code: join_code.clone(),
+            });
+        }
         self.teacher = Some(msg.teacher);
 
+        // the players who joined before the teacher attached again
+        if let Err(err) = self
+            .send_players_to_teacher()
+            .and_then(|()| self.catch_up_teacher())
+        {
+            error!("Failed to catch up the teacher: {err:#}");
+        }
+        self.send_approval_queue_to_teacher();
+
         // only now actually start the server (i.e. allow players to join)
         self.locked = false;
+
+        Ok(Attached {
+            quiz_name: self.questions.quiz_name.clone(),
+            pin: self.pin.clone(),
+            key: self.teacher_key.clone(),
+        })
     }
 }
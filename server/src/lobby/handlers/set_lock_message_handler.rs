@@ -1,6 +1,6 @@
 use actix::{Context, Handler};
 
-use crate::{messages::lobby::SetLockMessage, Lobby};
+use crate::{journal::JournalEvent, messages::lobby::SetLockMessage, Lobby};
 
 use log::debug;
 
@@ -12,6 +12,7 @@ impl Handler<SetLockMessage> for Lobby {
             "Received SetLockMessage in Lobby; setting `locked` to `{}`",
             msg.locked
         );
+        self.record(&JournalEvent::SetLock { locked: msg.locked });
         self.locked = msg.locked;
     }
 }
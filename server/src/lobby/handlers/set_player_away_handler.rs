@@ -0,0 +1,40 @@
+use actix::{AsyncContext, Context, Handler};
+use log::info;
+
+use crate::{
+    lobby::state::Phase,
+    messages::{lobby::EndQuestion, websocket::SetPlayerAway},
+    Lobby,
+};
+
+impl Handler<SetPlayerAway> for Lobby {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetPlayerAway, ctx: &mut Context<Self>) -> Self::Result {
+        if !self.joined_players.contains_key(&msg.player_id) {
+            return;
+        }
+
+        if !msg.away {
+            if self.away_players.remove(&msg.player_id) {
+                info!("Player {} is back", msg.player_id);
+            }
+            return;
+        }
+
+        if !self.away_players.insert(msg.player_id) {
+            return;
+        }
+        info!(
+            "Player {} stopped responding, marked as away",
+            msg.player_id
+        );
+
+        // the others may be waiting only for the away player
+        if let Phase::ActiveQuestion(index) = self.phase {
+            if self.everybody_answered(index) {
+                ctx.notify(EndQuestion { index });
+            }
+        }
+    }
+}
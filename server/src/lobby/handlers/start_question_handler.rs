@@ -1,8 +1,11 @@
 use std::time::Duration;
 
 use actix::{Addr, AsyncContext, Context, Handler};
+use chrono::Utc;
+use common::constants::APPROVAL_CLOSED_MSG;
 
 use crate::{
+    journal::JournalEvent,
     lobby::{Lobby, Phase},
     messages::lobby::{EndQuestion, StartQuestion},
 };
@@ -21,6 +24,7 @@ impl Handler<StartQuestion> for Lobby {
 
     fn handle(&mut self, _msg: StartQuestion, ctx: &mut Context<Self>) -> Self::Result {
         debug!("Received StartQuestion message in Lobby; starting question");
+        self.record(&JournalEvent::StartQuestion);
 
         // * find the next question
         // * set the phase to `ActiveQuestion`
@@ -30,14 +34,33 @@ impl Handler<StartQuestion> for Lobby {
         let next_question = self.next_question()?;
         self.phase = Phase::ActiveQuestion(next_question);
 
+        // the players are let in only while the teacher waits for them
+        if !self.awaiting_approval.is_empty() {
+            let awaiting: Vec<_> = self.awaiting_approval.keys().copied().collect();
+            for id in awaiting {
+                self.refuse_player(id, APPROVAL_CLOSED_MSG);
+            }
+            self.send_approval_queue_to_teacher();
+        }
+
         let end_time = self.send_question(next_question)?;
 
-        // spawn a task which will notify self after the timer is done
-        tokio::spawn(notify_end_question_after(
-            Duration::from_secs(end_time.try_into()?),
+        // the players can answer only after they had time to read the question
+        let reading_time = self.questions[next_question].get_reading_time_estimate();
+        self.choices_shown_at.insert(
             next_question,
-            ctx.address(),
-        ));
+            Utc::now() + chrono::Duration::seconds(reading_time.try_into()?),
+        );
+
+        // spawn a task which will notify self after the timer is done,
+        // a replayed question is ended by the journal
+        if !self.replaying {
+            tokio::spawn(notify_end_question_after(
+                Duration::from_secs(end_time.try_into()?),
+                next_question,
+                ctx.address(),
+            ));
+        }
 
         Ok(())
     }
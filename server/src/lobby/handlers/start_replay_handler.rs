@@ -0,0 +1,26 @@
+use actix::{AsyncContext, Context, Handler};
+use log::info;
+
+use crate::{
+    messages::{lobby::StartReplay, teacher::ReplayStarted},
+    replay, Lobby,
+};
+
+impl Handler<StartReplay> for Lobby {
+    type Result = ();
+
+    fn handle(&mut self, msg: StartReplay, ctx: &mut Context<Self>) -> Self::Result {
+        info!(
+            "Replaying {} journal entries at speed {}",
+            msg.entries.len(),
+            msg.speed
+        );
+        self.replaying = true;
+
+        if let Some(ref teacher) = self.teacher {
+            teacher.do_send(ReplayStarted { speed: msg.speed });
+        }
+
+        actix::spawn(replay::feed(ctx.address(), msg.entries, msg.speed));
+    }
+}
@@ -1,10 +1,11 @@
 use actix::{Context, Handler};
 use anyhow::bail;
-use log::debug;
+use log::{debug, error, info};
 
 use crate::{
+    journal::JournalEvent,
     lobby::{Lobby, Phase},
-    messages::lobby::SwitchToLeaderboard,
+    messages::{lobby::SwitchToLeaderboard, teacher::ShowAnalysis},
 };
 
 impl Handler<SwitchToLeaderboard> for Lobby {
@@ -12,6 +13,7 @@ impl Handler<SwitchToLeaderboard> for Lobby {
 
     fn handle(&mut self, _msg: SwitchToLeaderboard, _: &mut Context<Self>) -> Self::Result {
         debug!("Received SwitchToLeaderboard message in Lobby; switching to leaderboard");
+        self.record(&JournalEvent::SwitchToLeaderboard);
         // have to be in phase `AfterQuestion`
         let Phase::AfterQuestion(index) = self.phase else {
             bail!("Cannot switch to leaderboard, not in AfterQuestion phase");
@@ -27,6 +29,32 @@ impl Handler<SwitchToLeaderboard> for Lobby {
             Phase::ShowingLeaderboard(index)
         };
 
+        if is_final {
+            let results = self.game_results();
+
+            self.send_personal_reports(&results);
+
+            if let Some(ref teacher) = self.teacher {
+                teacher.do_send(ShowAnalysis {
+                    questions: results.analysis,
+                });
+            }
+        }
+
+        // the export is not needed for the game to continue, so it must not fail the switch
+        if is_final && self.results_dir.is_some() {
+            if let Err(err) = self.export_results() {
+                error!("Failed to export the results: {err:#}");
+            }
+        }
+
+        if is_final && self.db.is_some() {
+            match self.save_to_history() {
+                Ok(id) => info!("Game was stored in the history as session {id}"),
+                Err(err) => error!("Failed to store the game in the history: {err:#}"),
+            }
+        }
+
         Ok(())
     }
 }
@@ -1,23 +1,106 @@
+use std::time::Instant;
+
 use actix::{Context, Handler};
 use common::{
-    constants::LOBBY_LOCKED_MSG,
-    messages::network::{CanJoin, TryJoinRequest, TryJoinResponse},
+    constants::{
+        JOIN_CODE_REQUIRED_MSG, LOBBY_LOCKED_MSG, TOO_MANY_WRONG_JOIN_CODES_MSG,
+        WRONG_JOIN_CODE_MSG,
+    },
+    messages::{
+        network::{CanJoin, TryJoinResponse},
+        protocol::ProtocolVersion,
+    },
+};
+use log::{debug, info, warn};
+
+use crate::{
+    lobby::join_code_matches,
+    messages::{client::TryJoinRequest, teacher::ShowWarning},
+    Lobby,
 };
-use log::debug;
 
-use crate::Lobby;
+impl Lobby {
+    /// Checks the join code sent by the client, returns the reason for the client
+    /// if it cannot join
+    fn check_join_code(&mut self, msg: &TryJoinRequest) -> Result<(), String> {
+        let Some(join_code) = &self.join_code else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        if let Some(wait) = msg
+            .ip
+            .and_then(|ip| self.join_attempts.blocked_for(ip, now))
+        {
+            return Err(format!(
+                "{TOO_MANY_WRONG_JOIN_CODES_MSG}, try again in {} s",
+                wait.as_secs() + 1
+            ));
+        }
+
+        let Some(given) = &msg.request.join_code else {
+            return Err(JOIN_CODE_REQUIRED_MSG.to_owned());
+        };
+
+        if join_code_matches(join_code, given) {
+            if let Some(ip) = msg.ip {
+                self.join_attempts.succeeded(ip);
+            }
+            return Ok(());
+        }
+
+        info!("Client {} sent a wrong join code", msg.request.uuid);
+        let blocked = msg
+            .ip
+            .and_then(|ip| Some((ip, self.join_attempts.failed(ip, now)?)));
+
+        if let Some((ip, wait)) = blocked {
+            let message = format!(
+                "{ip}: {TOO_MANY_WRONG_JOIN_CODES_MSG}, blocked for {} s",
+                wait.as_secs()
+            );
+            warn!("{message}");
+            if let Some(teacher) = &self.teacher {
+                teacher.do_send(ShowWarning { message });
+            }
+        }
+
+        Err(WRONG_JOIN_CODE_MSG.to_owned())
+    }
+}
 
 impl Handler<TryJoinRequest> for Lobby {
     type Result = TryJoinResponse;
 
     fn handle(&mut self, msg: TryJoinRequest, _: &mut Context<Self>) -> Self::Result {
         debug!("Received TryJoinRequest message in Lobby; trying to join");
+        let uuid = msg.request.uuid;
         let response = TryJoinResponse {
-            uuid: msg.uuid,
+            uuid,
             can_join: CanJoin::No(String::new()),
             quiz_name: self.questions.quiz_name.clone(),
+            custom_syntaxes: self.custom_syntaxes.clone(),
+            protocol: ProtocolVersion::CURRENT,
+            capabilities: self.capabilities.clone(),
+            student_id_required: self.roster.is_some(),
         };
 
+        if let Err(reason) = ProtocolVersion::CURRENT.accepts(msg.request.protocol) {
+            info!("Client {uuid} cannot join: {reason}");
+            return TryJoinResponse {
+                can_join: CanJoin::No(reason),
+                ..response
+            };
+        }
+
+        // checked before the lock, so the students from other groups do not learn anything
+        if let Err(reason) = self.check_join_code(&msg) {
+            return TryJoinResponse {
+                can_join: CanJoin::No(reason),
+                ..response
+            };
+        }
+
         if self.locked {
             return TryJoinResponse {
                 can_join: CanJoin::No(LOBBY_LOCKED_MSG.to_owned()),
@@ -25,7 +108,7 @@ impl Handler<TryJoinRequest> for Lobby {
             };
         }
 
-        self.waiting_players.insert(msg.uuid);
+        self.waiting_players.insert(uuid);
 
         TryJoinResponse {
             can_join: CanJoin::Yes,
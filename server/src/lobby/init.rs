@@ -1,52 +1,159 @@
 use actix::{Actor, Addr};
 
+use anyhow::Context;
 use common::questions::QuestionSet;
-use log::info;
-use tokio::net::TcpListener;
+use log::{error, info, warn};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{tcp::OwnedWriteHalf, TcpListener, TcpStream},
+    sync::mpsc::{self, UnboundedReceiver},
+};
 
-use std::{net::SocketAddr, sync::mpsc::Sender};
+use std::{
+    net::SocketAddr,
+    sync::{mpsc::Sender, Arc},
+};
 
 use super::Lobby;
-use crate::websocket::Websocket;
+use crate::{
+    attach::TeacherHandle,
+    messages::{
+        attach::{AttachRequest, AttachResponse, ToLobby, ToTeacher},
+        lobby::RegisterTeacher,
+        websocket::ConnectionWarning,
+    },
+    rooms::{generate_pin, Rooms},
+    tls::TlsSettings,
+    websocket::{
+        Connection, ConnectionCounter, ConnectionGuard, ConnectionSettings, OutboxMetrics,
+        Websocket,
+    },
+};
 
-/// Starts the server and send the address of the lobby through the given channel.
+/// Starts the server with the given lobby and send the address of the lobby through the given channel.
 /// # Errors
 /// - If the tokio runtime cannot be created
 /// - If the server cannot be started
-pub fn run_server(
-    tx: Sender<Addr<Lobby>>,
-    questions: QuestionSet,
+pub fn run_server(tx: &Sender<Addr<Lobby>>, lobby: Lobby, addr: SocketAddr) -> anyhow::Result<()> {
+    let system = actix::System::new();
+
+    let (lobbies, outbox_metrics) = system.block_on(init(vec![lobby], addr, None))?;
+    let _ = tx.send(lobbies[0].clone());
+
+    system.run()?;
+    info!("Outbound queues: {outbox_metrics}");
+
+    Ok(())
+}
+
+/// How the teachers in other processes attach to the server, each with a game of its own
+#[derive(Clone)]
+pub struct AttachSettings {
+    /// where the server waits for the teachers, they are trusted, so it should be a local address
+    pub addr: SocketAddr,
+    pub new_room: NewRoom,
+}
+
+/// Sets up the lobby of a new game with its questions and PIN, the way the server
+/// set up its first game
+pub type NewRoom = Arc<dyn Fn(QuestionSet, String) -> Lobby + Send + Sync>;
+
+/// Starts the server hosting a game in each of the given lobbies, the students choose
+/// the game by its PIN, the addresses of the lobbies are sent in the same order.
+/// The connections are set up with the settings of the first lobby, the statistics
+/// of their outbound queues are for the whole server. With the attach settings, the teachers
+/// in other processes can start more games while the server runs.
+/// The server stops when the last game stops.
+/// # Errors
+/// - If the tokio runtime cannot be created
+/// - If the server cannot be started
+/// - If two lobbies have the same PIN
+pub fn run_rooms(
+    tx: &Sender<Vec<Addr<Lobby>>>,
+    lobbies: Vec<Lobby>,
     addr: SocketAddr,
+    attach: Option<AttachSettings>,
 ) -> anyhow::Result<()> {
     let system = actix::System::new();
 
-    system.block_on(init(tx, questions, addr))?;
+    let (lobbies, outbox_metrics) = system.block_on(init(lobbies, addr, attach))?;
+    let _ = tx.send(lobbies);
 
     system.run()?;
+    info!("Outbound queues: {outbox_metrics}");
 
     Ok(())
 }
 
 #[allow(clippy::unused_async)]
+/// Starts the lobbies and the task accepting the connections, returns the addresses
+/// of the lobbies and the statistics of the outbound queues shared by all of them
 async fn init(
-    tx: Sender<Addr<Lobby>>,
-    questions: QuestionSet,
+    lobbies: Vec<Lobby>,
     addr: SocketAddr,
-) -> anyhow::Result<()> {
-    // spawn an actor for managing the lobby
-    let lobby_actor = Lobby::new(questions).start();
+    attach: Option<AttachSettings>,
+) -> anyhow::Result<(Vec<Addr<Lobby>>, OutboxMetrics)> {
+    let Some(first) = lobbies.first() else {
+        anyhow::bail!("The server needs at least one lobby");
+    };
+
+    // a client is not in any room until it joins, so the statistics are for the whole server
+    let outbox_metrics = OutboxMetrics::default();
+    let tls = first.tls.clone();
+    let connections = first.connections.clone();
+    let settings = ConnectionSettings {
+        journal: first.journal.clone(),
+        heartbeat: first.heartbeat,
+        limits: first.connection_limits,
+        outbox_capacity: first.outbox_capacity,
+        outbox_metrics: outbox_metrics.clone(),
+    };
+
+    // spawn an actor for managing each lobby
+    let rooms = Rooms::default();
+    let mut addresses = Vec::with_capacity(lobbies.len());
+    for mut lobby in lobbies {
+        lobby.outbox_metrics = outbox_metrics.clone();
+        lobby.rooms = rooms.clone();
+        let pin = lobby.pin.clone();
+        let lobby = lobby.start();
+        rooms.insert(pin, lobby.clone())?;
+        addresses.push(lobby);
+    }
+
+    if let Some(attach) = attach {
+        // the main game does not need the other teachers, so it goes on without them
+        match TcpListener::bind(attach.addr).await {
+            Ok(listener) => {
+                let room = Room {
+                    rooms: rooms.clone(),
+                    outbox_metrics: outbox_metrics.clone(),
+                    connections: connections.clone(),
+                    new_lobby: attach.new_room,
+                };
+                let _teacher_acceptor = tokio::task::spawn_local(accept_teachers(listener, room));
+            }
+            Err(err) => error!(
+                "The teachers of other games cannot attach on {}: {err}",
+                attach.addr
+            ),
+        }
+    }
 
     // spawn task for accepting connections
     let _connection_acceptor =
-        tokio::task::spawn_local(accept_connections(addr, lobby_actor.clone()));
+        tokio::task::spawn_local(accept_connections(addr, rooms, tls, settings, connections));
 
-    // send the address of the lobby to the main thread
-    let _ = tx.send(lobby_actor.clone());
-
-    Ok(())
+    Ok((addresses, outbox_metrics))
 }
 
-async fn accept_connections(addr: SocketAddr, lobby: Addr<Lobby>) -> anyhow::Result<()> {
+async fn accept_connections(
+    addr: SocketAddr,
+    rooms: Rooms,
+    tls: Option<TlsSettings>,
+    settings: ConnectionSettings,
+    connections: ConnectionCounter,
+) -> anyhow::Result<()> {
     // create a TCP socket listener
 
     let listener = TcpListener::bind(addr).await?;
@@ -59,8 +166,191 @@ async fn accept_connections(addr: SocketAddr, lobby: Addr<Lobby>) -> anyhow::Res
 
         info!("Accepted connection from: {who:?}");
 
-        // spawn a actor for managing the connection
-        let ws = Websocket::new(lobby.clone(), socket, who).await?;
-        let _ = ws.start();
+        // checked before the handshakes, so refused connections cost as little as possible
+        let max = settings.limits.max_connections_per_ip;
+        let Some(connection) = connections.acquire(who.ip(), max) else {
+            let reason = format!(
+                "Refused a connection, there are already {} from {}",
+                connections.count(who.ip()),
+                who.ip()
+            );
+            // nobody knows which game the client wanted to join
+            for lobby in rooms.lobbies() {
+                lobby.do_send(ConnectionWarning {
+                    who,
+                    player_id: None,
+                    reason: reason.clone(),
+                });
+            }
+            continue;
+        };
+
+        // the handshakes run in their own task, so a client which stalls in them
+        // does not keep the others from joining
+        let rooms = rooms.clone();
+        let tls = tls.clone();
+        let settings = settings.clone();
+        let _handshake = tokio::task::spawn_local(async move {
+            let timeout = settings.limits.handshake_timeout;
+            let handshake = handshake(rooms, socket, who, tls, settings, connection);
+            match tokio::time::timeout(timeout, handshake).await {
+                Ok(Ok(ws)) => {
+                    let _ = ws.start();
+                }
+                Ok(Err(err)) => warn!("Handshake with {who:?} failed: {err:#}"),
+                Err(_) => warn!("Handshake with {who:?} did not finish in {timeout:?}"),
+            }
+        });
     }
 }
+
+/// Does the TLS handshake (if the server uses TLS) and the websocket handshake with the client
+async fn handshake(
+    rooms: Rooms,
+    socket: TcpStream,
+    who: SocketAddr,
+    tls: Option<TlsSettings>,
+    settings: ConnectionSettings,
+    connection: ConnectionGuard,
+) -> anyhow::Result<Websocket> {
+    let socket: Connection = match &tls {
+        // fails e.g. when the client connected with ws:// instead of wss://
+        Some(tls) => Box::new(
+            tls.acceptor
+                .accept(socket)
+                .await
+                .context("TLS handshake failed")?,
+        ),
+        None => Box::new(socket),
+    };
+
+    Websocket::new(rooms, socket, who, settings, connection).await
+}
+
+/// What the lobby of a game started by an attached teacher shares with the other games
+#[derive(Clone)]
+struct Room {
+    rooms: Rooms,
+    outbox_metrics: OutboxMetrics,
+    connections: ConnectionCounter,
+    new_lobby: NewRoom,
+}
+
+async fn accept_teachers(listener: TcpListener, room: Room) -> anyhow::Result<()> {
+    loop {
+        let (socket, who) = listener.accept().await?;
+        info!("Teacher connected from: {who:?}");
+
+        let room = room.clone();
+        let _teacher = tokio::task::spawn_local(async move {
+            if let Err(err) = attach_teacher(socket, room).await {
+                warn!("Teacher from {who:?} failed: {err:#}");
+            }
+            info!("Teacher from {who:?} left");
+        });
+    }
+}
+
+/// Registers the teacher with the lobby of its game, a new one or the one with the PIN,
+/// and passes the messages between them until the teacher leaves
+async fn attach_teacher(socket: TcpStream, room: Room) -> anyhow::Result<()> {
+    let (reader, mut writer) = socket.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let Some(line) = lines.next_line().await? else {
+        return Ok(());
+    };
+    let request: AttachRequest = serde_json::from_str(&line).context("Invalid attach request")?;
+
+    let key = request.key.clone();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let registered = match find_room(request, &room) {
+        Ok(lobby) => lobby
+            .send(RegisterTeacher {
+                teacher: TeacherHandle::Remote(tx),
+                key,
+            })
+            .await?
+            .map(|attached| (lobby, attached)),
+        Err(reason) => Err(reason),
+    };
+
+    // the lobby's messages wait in the channel until the teacher knows it was let in
+    let response: AttachResponse = registered
+        .as_ref()
+        .map(|(_, attached)| attached.clone())
+        .map_err(Clone::clone);
+    write_json(&mut writer, &response).await?;
+    let Ok((lobby, _)) = registered else {
+        return Ok(());
+    };
+
+    let writer_task = tokio::task::spawn_local(write_to_teacher(rx, writer));
+
+    while let Some(line) = lines.next_line().await? {
+        let msg: ToLobby = serde_json::from_str(&line).context("Invalid message")?;
+        msg.deliver(&lobby);
+    }
+
+    // the game goes on, the teacher can attach again with its PIN and key
+    writer_task.abort();
+
+    Ok(())
+}
+
+/// Lobby of the game the teacher asks for, a new game is started when the teacher
+/// brings its questions
+fn find_room(request: AttachRequest, room: &Room) -> Result<Addr<Lobby>, String> {
+    let Some(questions) = request.questions else {
+        return match request.pin {
+            Some(pin) => room.rooms.find(Some(&pin)).map_err(str::to_owned),
+            None => Err("Either the PIN of a game or the questions of a new one are needed".into()),
+        };
+    };
+
+    let pin = match request.pin {
+        Some(pin) if room.rooms.contains(&pin) => {
+            return Err(format!("There already is a game with the PIN {pin}"));
+        }
+        Some(pin) => pin,
+        None => loop {
+            let pin = generate_pin();
+            if !room.rooms.contains(&pin) {
+                break pin;
+            }
+        },
+    };
+
+    let mut lobby = (room.new_lobby)(questions, pin.clone());
+    lobby.outbox_metrics = room.outbox_metrics.clone();
+    lobby.connections = room.connections.clone();
+    lobby.rooms = room.rooms.clone();
+
+    let lobby = lobby.start();
+    room.rooms
+        .insert(pin, lobby.clone())
+        .map_err(|err| err.to_string())?;
+
+    Ok(lobby)
+}
+
+async fn write_to_teacher(mut rx: UnboundedReceiver<ToTeacher>, mut writer: OwnedWriteHalf) {
+    while let Some(msg) = rx.recv().await {
+        if let Err(err) = write_json(&mut writer, &msg).await {
+            warn!("Failed to send a message to the teacher: {err:#}");
+            break;
+        }
+    }
+}
+
+/// Writes the message as a single line of JSON, see [`crate::attach::write_line`]
+async fn write_json<T: serde::Serialize>(
+    writer: &mut OwnedWriteHalf,
+    msg: &T,
+) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(msg)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+
+    Ok(())
+}
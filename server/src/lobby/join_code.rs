@@ -0,0 +1,88 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+
+/// Number of the characters of a generated join code
+pub const JOIN_CODE_LENGTH: usize = 5;
+
+// letters and digits which cannot be mistaken for each other on the projector
+const JOIN_CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// How many wrong join codes one address may send before it has to wait,
+/// a few typos of the students behind one NAT should not lock the others out
+pub const FREE_WRONG_JOIN_CODES: u32 = 5;
+
+/// The longest time an address has to wait before it may try another join code
+pub const MAX_JOIN_CODE_BACKOFF: Duration = Duration::from_mins(1);
+
+// the wrong attempts of an address are forgotten after this long without any
+const FORGET_WRONG_JOIN_CODES_AFTER: Duration = Duration::from_mins(10);
+
+/// Random join code, short enough to be copied from the projector
+#[must_use]
+pub fn generate_join_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..JOIN_CODE_LENGTH)
+        .map(|_| char::from(JOIN_CODE_CHARS[rng.gen_range(0..JOIN_CODE_CHARS.len())]))
+        .collect()
+}
+
+/// Whether the code sent by the student is the join code, the letter case and the spaces
+/// around it do not matter
+#[must_use]
+pub fn join_code_matches(join_code: &str, given: &str) -> bool {
+    join_code.eq_ignore_ascii_case(given.trim())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct WrongJoinCodes {
+    count: u32,
+    last: Instant,
+    blocked_until: Option<Instant>,
+}
+
+/// Wrong join codes sent from each address, an address which sends too many of them
+/// has to wait longer and longer before it may try again
+#[derive(Debug, Clone, Default)]
+pub struct JoinAttempts(HashMap<IpAddr, WrongJoinCodes>);
+
+impl JoinAttempts {
+    /// How long the address still has to wait before it may try again, `None` if it may try now
+    #[must_use]
+    pub fn blocked_for(&self, ip: IpAddr, now: Instant) -> Option<Duration> {
+        let blocked_until = self.0.get(&ip)?.blocked_until?;
+        let remaining = blocked_until.saturating_duration_since(now);
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Counts a wrong join code sent from the address,
+    /// returns how long the address is blocked for if it has to wait now
+    pub fn failed(&mut self, ip: IpAddr, now: Instant) -> Option<Duration> {
+        self.0.retain(|_, wrong| {
+            now.saturating_duration_since(wrong.last) < FORGET_WRONG_JOIN_CODES_AFTER
+        });
+
+        let wrong = self.0.entry(ip).or_insert(WrongJoinCodes {
+            count: 0,
+            last: now,
+            blocked_until: None,
+        });
+        wrong.count += 1;
+        wrong.last = now;
+
+        let over = wrong.count.checked_sub(FREE_WRONG_JOIN_CODES)?;
+        // 1 s, 2 s, 4 s, ... up to the maximum
+        let backoff = Duration::from_secs(1u64 << over.min(16)).min(MAX_JOIN_CODE_BACKOFF);
+        wrong.blocked_until = Some(now + backoff);
+        Some(backoff)
+    }
+
+    /// Forgets the wrong join codes of the address, it sent the right one
+    pub fn succeeded(&mut self, ip: IpAddr) {
+        self.0.remove(&ip);
+    }
+}
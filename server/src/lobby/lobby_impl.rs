@@ -1,23 +1,51 @@
-use actix::prelude::{Actor, Context};
+use actix::prelude::{Actor, AsyncContext, Context};
+use actix_rt::System;
 use anyhow::{bail, Ok};
 use common::{
+    heartbeat::Heartbeat,
     messages::{
+        encoding::SharedMessage,
         network::{
-            ChoiceStats, NextQuestion, PlayerData, PlayersUpdate, QuestionEnded, QuestionUpdate,
-            ShowLeaderboard,
+            ChoiceReport, ChoiceStats, NextQuestion, PersonalReport, PlayerData, QuestionEnded,
+            QuestionReport, QuestionUpdate, ShowLeaderboard,
         },
+        protocol::Capability,
         ServerNetworkMessage,
     },
-    questions::{QuestionCensored, QuestionSet},
+    questions::{Choice, Question, QuestionCensored, QuestionSet},
+    syntax,
 };
 
-use log::debug;
+use log::{debug, error};
+
+use crate::{
+    history::History,
+    journal::{Journal, JournalEvent},
+    messages::{
+        teacher::{ResultsExported, ShowAnalysis},
+        websocket::Broadcast,
+    },
+    rooms::{generate_pin, generate_teacher_key, Rooms},
+    tls::TlsSettings,
+    websocket::{ConnectionCounter, ConnectionLimits, OutboxMetrics, DEFAULT_OUTBOX_CAPACITY},
+};
 use rand::prelude::*;
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
 use uuid::Uuid;
 
-use super::state::{Lobby, Phase};
+use super::{
+    export::GameResults,
+    join_code::JoinAttempts,
+    players_update::{PendingPlayersUpdate, PLAYERS_RESYNC_PERIOD, PLAYERS_UPDATE_DELAY},
+    roster::Roster,
+    snapshot::SNAPSHOT_PERIOD,
+    state::{JoinedPlayer, Lobby, Phase},
+};
 
 impl Lobby {
     #[must_use]
@@ -34,15 +62,209 @@ impl Lobby {
             }
         }
 
+        let syntax_names = questions.syntax_names();
+        let custom_syntaxes = syntax::custom_syntaxes(syntax_names.iter().map(String::as_str));
+
         Lobby {
+            custom_syntaxes,
             teacher: None,
             phase: Phase::default(),
             locked: true,
             joined_players: HashMap::new(),
             questions,
             waiting_players: HashSet::new(),
+            approve_joins: false,
+            awaiting_approval: HashMap::new(),
             results: HashMap::new(),
+            choices_shown_at: HashMap::new(),
+            left_players: HashMap::new(),
+            results_dir: None,
+            db: None,
+            snapshot_file: None,
+            resumed_players: HashMap::new(),
+            journal: None,
+            replaying: false,
+            tls: None,
+            heartbeat: Heartbeat::default(),
+            away_players: HashSet::new(),
+            capabilities: Capability::supported(),
+            players_update_delay: PLAYERS_UPDATE_DELAY,
+            players_resync_period: PLAYERS_RESYNC_PERIOD,
+            pending_players_update: PendingPlayersUpdate::default(),
+            outbox_capacity: DEFAULT_OUTBOX_CAPACITY,
+            outbox_metrics: OutboxMetrics::default(),
+            connection_limits: ConnectionLimits::default(),
+            connections: ConnectionCounter::default(),
+            pin: generate_pin(),
+            rooms: Rooms::default(),
+            join_code: None,
+            teacher_key: generate_teacher_key(),
+            join_attempts: JoinAttempts::default(),
+            roster: None,
+            student_ids: HashMap::new(),
+            resume_tokens: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_results_dir(mut self, results_dir: Option<PathBuf>) -> Self {
+        self.results_dir = results_dir;
+        self
+    }
+
+    #[must_use]
+    pub fn with_db(mut self, db: Option<PathBuf>) -> Self {
+        self.db = db;
+        self
+    }
+
+    #[must_use]
+    pub fn with_journal(mut self, journal: Option<Journal>) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    /// Records the event to the journal, if there is one
+    pub fn record(&self, event: &JournalEvent) {
+        if let Some(journal) = &self.journal {
+            journal.record(event);
+        }
+    }
+
+    #[must_use]
+    pub fn with_tls(mut self, tls: Option<TlsSettings>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    #[must_use]
+    pub fn with_heartbeat(mut self, heartbeat: Heartbeat) -> Self {
+        self.heartbeat = heartbeat;
+        self
+    }
+
+    /// With `json_only`, the binary encoding is not offered to the clients
+    #[must_use]
+    pub fn with_json_only(mut self, json_only: bool) -> Self {
+        if json_only {
+            self.capabilities
+                .retain(|capability| *capability != Capability::MessagePack);
+        }
+        self
+    }
+
+    /// `delay` is how long the changes of the players are collected before they are sent,
+    /// every `resync_period` everybody gets the whole list of players
+    #[must_use]
+    pub fn with_players_update_timing(mut self, delay: Duration, resync_period: Duration) -> Self {
+        self.players_update_delay = delay;
+        self.players_resync_period = resync_period;
+        self
+    }
+
+    /// How many messages may wait for a client before it is disconnected as too slow
+    #[must_use]
+    pub fn with_outbox_capacity(mut self, capacity: usize) -> Self {
+        self.outbox_capacity = capacity;
+        self
+    }
+
+    /// The frame size, message rate and connections per IP address allowed to each client
+    #[must_use]
+    pub fn with_connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.connection_limits = limits;
+        self
+    }
+
+    /// The PIN of the game, a random one is kept if `None`
+    #[must_use]
+    pub fn with_pin(mut self, pin: Option<String>) -> Self {
+        if let Some(pin) = pin {
+            self.pin = pin;
+        }
+        self
+    }
+
+    /// The code the students have to enter to join, anybody can join if `None`
+    #[must_use]
+    pub fn with_join_code(mut self, join_code: Option<String>) -> Self {
+        self.join_code = join_code;
+        self
+    }
+
+    /// With `approve_joins`, the players join only when the teacher lets them in
+    #[must_use]
+    pub fn with_approve_joins(mut self, approve_joins: bool) -> Self {
+        self.approve_joins = approve_joins;
+        self
+    }
+
+    /// With a roster, the players have to join with the student ID of one of its students
+    #[must_use]
+    pub fn with_roster(mut self, roster: Option<Roster>) -> Self {
+        self.roster = roster;
+        self
+    }
+
+    #[must_use]
+    pub fn with_snapshot_file(mut self, snapshot_file: Option<PathBuf>) -> Self {
+        self.snapshot_file = snapshot_file;
+        self
+    }
+
+    /// Removes the player from the joined players and remembers them for the results export,
+    /// returns whether the player was joined
+    pub fn remove_player(&mut self, player_id: &Uuid) -> bool {
+        let Some(player) = self.joined_players.remove(player_id) else {
+            return false;
+        };
+        self.away_players.remove(player_id);
+
+        self.left_players.insert(
+            player.uuid,
+            PlayerData {
+                color: player.color,
+                color_index: player.color_index,
+                nickname: player.nickname,
+                avatar: player.avatar,
+                uuid: player.uuid,
+            },
+        );
+        true
+    }
+
+    /// Exports the results to the results directory and lets the teacher know how it went
+    /// # Errors
+    /// - if no results directory was set
+    /// - if the results cannot be written
+    pub fn export_results(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let result = match &self.results_dir {
+            Some(dir) => self.game_results().write_to_dir(dir),
+            None => Err(anyhow::anyhow!("No results directory was set")),
+        };
+
+        if let Some(ref teacher) = self.teacher {
+            teacher.do_send(ResultsExported {
+                outcome: result
+                    .as_ref()
+                    .map(Clone::clone)
+                    .map_err(|err| format!("{err:#}")),
+            });
         }
+
+        result
+    }
+
+    /// Stores the game in the history database
+    /// # Errors
+    /// - if no database was set
+    /// - if the database cannot be opened or written to
+    pub fn save_to_history(&self) -> anyhow::Result<i64> {
+        let Some(db) = &self.db else {
+            bail!("No history database was set");
+        };
+
+        History::open(db)?.save_game(&self.questions, &self.game_results())
     }
 
     #[must_use]
@@ -51,14 +273,7 @@ impl Lobby {
 
         players.sort_by_key(|x| x.joined_at);
 
-        players
-            .into_iter()
-            .map(|val| PlayerData {
-                color: val.color,
-                nickname: val.nickname.clone(),
-                uuid: val.uuid,
-            })
-            .collect()
+        players.into_iter().map(JoinedPlayer::data).collect()
     }
 
     /// Returns the index of the next question, or an error if there is no next question
@@ -154,7 +369,7 @@ impl Lobby {
         };
 
         teacher.do_send(QuestionEnded {
-            stats: stats.clone(),
+            stats,
             player_answer: None,
             question_index: index,
             question: self.questions[index].clone(),
@@ -163,10 +378,28 @@ impl Lobby {
     }
 
     pub fn send_leaderboard(&self, index: usize) -> anyhow::Result<bool> {
+        let is_final = index == self.questions.len() - 1;
+        let message = self.leaderboard(index);
+
+        // send it to all students
+        self.send_to_all(&ServerNetworkMessage::ShowLeaderboard(message.clone()));
+
+        // and also to the teacher
+        let Some(ref teacher) = self.teacher else {
+            anyhow::bail!("Cannot send to teacher, Teacher is null");
+        };
+
+        teacher.do_send(message);
+
+        Ok(is_final)
+    }
+
+    /// Leaderboard after the question with the index
+    fn leaderboard(&self, index: usize) -> ShowLeaderboard {
         use itertools::Itertools;
         let is_final = index == self.questions.len() - 1;
 
-        let message = ShowLeaderboard {
+        ShowLeaderboard {
             was_final_round: is_final,
             players: self
                 .get_players()
@@ -188,10 +421,31 @@ impl Lobby {
                 // sort by score descending
                 .sorted_by_key(|(_, score)| std::cmp::Reverse(*score))
                 .collect(),
+        }
+    }
+
+    /// Whether every joined player who is not away answered the question,
+    /// the question is not ended early when everybody is away
+    #[must_use]
+    pub fn everybody_answered(&self, index: usize) -> bool {
+        let Some(results) = self.results.get(&index) else {
+            return false;
         };
 
+        let mut present = self
+            .joined_players
+            .keys()
+            .filter(|uuid| !self.away_players.contains(uuid))
+            .peekable();
+
+        present.peek().is_some() && present.all(|uuid| results.contains_key(uuid))
+    }
+
+    pub fn send_question_update(&self, index: usize) -> anyhow::Result<()> {
+        let message = self.question_update(index);
+
         // send it to all students
-        self.send_to_all(&ServerNetworkMessage::ShowLeaderboard(message.clone()));
+        self.send_to_all(&ServerNetworkMessage::QuestionUpdate(message.clone()));
 
         // and also to the teacher
         let Some(ref teacher) = self.teacher else {
@@ -199,24 +453,30 @@ impl Lobby {
         };
 
         teacher.do_send(message);
-
-        Ok(is_final)
+        Ok(())
     }
 
-    pub fn send_question_update(&self, index: usize) -> anyhow::Result<()> {
+    fn question_update(&self, index: usize) -> QuestionUpdate {
         let answered_count = self
             .results
             .get(&index)
             .map_or(0, std::collections::HashMap::len);
 
-        // construct a message object
-        let message = QuestionUpdate {
+        QuestionUpdate {
             players_answered_count: answered_count,
             question_index: index,
-        };
+        }
+    }
+
+    pub fn send_question(&self, index: usize) -> anyhow::Result<usize> {
+        let question = &self.questions[index];
+        let answer_time = question.time_seconds;
+        let reading_time = question.get_reading_time_estimate();
+
+        let message = self.censored_question(index);
 
         // send it to all students
-        self.send_to_all(&ServerNetworkMessage::QuestionUpdate(message.clone()));
+        self.send_to_all(&ServerNetworkMessage::NextQuestion(message.clone()));
 
         // and also to the teacher
         let Some(ref teacher) = self.teacher else {
@@ -224,83 +484,156 @@ impl Lobby {
         };
 
         teacher.do_send(message);
-        Ok(())
+
+        Ok(reading_time + answer_time)
     }
 
-    pub fn send_question(&self, index: usize) -> anyhow::Result<usize> {
+    /// The question with the index, without telling which choices are correct
+    fn censored_question(&self, index: usize) -> NextQuestion {
         let question = self.questions[index].clone();
 
-        let answer_time = question.time_seconds;
-        let reading_time = question.get_reading_time_estimate();
-
-        // construct a message object
-        let message = NextQuestion {
+        NextQuestion {
             question_index: index,
             questions_count: self.questions.len(),
             show_choices_after: question.get_reading_time_estimate(),
             question: QuestionCensored::from(question),
-        };
-
-        // send it to all students
-        self.send_to_all(&ServerNetworkMessage::NextQuestion(message.clone()));
+        }
+    }
 
-        // and also to the teacher
+    /// Brings a teacher who attached in the middle of the game to its current phase,
+    /// the time of a running question starts again on the teacher's screen
+    /// # Errors
+    /// - if there is no teacher
+    pub fn catch_up_teacher(&self) -> anyhow::Result<()> {
         let Some(ref teacher) = self.teacher else {
             anyhow::bail!("Cannot send to teacher, Teacher is null");
         };
 
-        teacher.do_send(message);
+        match self.phase {
+            Phase::WaitingForPlayers => {}
+            Phase::ActiveQuestion(index) => {
+                teacher.do_send(self.censored_question(index));
+                teacher.do_send(self.question_update(index));
+            }
+            Phase::AfterQuestion(index) => {
+                teacher.do_send(self.censored_question(index));
+                teacher.do_send(QuestionEnded {
+                    stats: self.get_question_stats(index)?,
+                    player_answer: None,
+                    question_index: index,
+                    question: self.questions[index].clone(),
+                });
+            }
+            Phase::ShowingLeaderboard(index) => teacher.do_send(self.leaderboard(index)),
+            Phase::GameEnded => {
+                teacher.do_send(self.leaderboard(self.questions.len() - 1));
+                teacher.do_send(ShowAnalysis {
+                    questions: self.game_results().analysis,
+                });
+            }
+        }
 
-        Ok(reading_time + answer_time)
+        Ok(())
+    }
+
+    /// Sends every joined player the summary of their own game
+    pub fn send_personal_reports(&self, results: &GameResults) {
+        for (player_id, socket_recipient) in &self.joined_players {
+            let Some(summary) = results
+                .summary
+                .iter()
+                .find(|row| row.player_uuid == *player_id)
+            else {
+                continue;
+            };
+
+            let questions = results
+                .answers
+                .iter()
+                .filter(|row| row.player_uuid == *player_id)
+                .map(|row| {
+                    let question = &self.questions[row.question - 1];
+                    let records = self.results.get(&(row.question - 1));
+                    let selected = records
+                        .and_then(|records| records.get(player_id))
+                        .map(|record| &record.selected_answers);
+
+                    QuestionReport {
+                        question_text: row.question_text.clone(),
+                        code_block: question.code_block.clone(),
+                        // keep the order of the choices from the quiz file
+                        selected_choices: selected.map(|selected| {
+                            choice_reports(question, |choice| selected.contains(&choice.id))
+                        }),
+                        correct_choices: choice_reports(question, |choice| choice.is_correct),
+                        is_correct: row.is_correct,
+                        points: row.points,
+                    }
+                })
+                .collect();
+
+            debug!("Sending PersonalReport to player {player_id}");
+            socket_recipient.do_send(ServerNetworkMessage::PersonalReport(PersonalReport {
+                quiz_name: results.quiz_name.clone(),
+                nickname: summary.nickname.clone(),
+                rank: summary.rank,
+                players_count: results.summary.len(),
+                total_points: summary.total_points,
+                questions,
+            }));
+        }
     }
 
-    /// Sends the `message` to all joined players
+    /// Sends the `message` to all joined players, it is serialized only once
     pub fn send_to_all(&self, message: &ServerNetworkMessage) {
+        let broadcast = Broadcast(SharedMessage::new(message.clone()));
         for socket_recipient in self.joined_players.values() {
-            socket_recipient.do_send(message.clone());
+            socket_recipient.broadcast(broadcast.clone());
         }
     }
 
-    /// Sends the `message` to all joined players except the one with `id_from`
+    /// Sends the `message` to all joined players except the one with `id_from`,
+    /// it is serialized only once
     pub fn send_to_others(&self, message: &ServerNetworkMessage, id_from: &Uuid) {
+        let broadcast = Broadcast(SharedMessage::new(message.clone()));
         for (id, socket_recipient) in &self.joined_players {
             if id != id_from {
-                socket_recipient.do_send(message.clone());
+                socket_recipient.broadcast(broadcast.clone());
             }
         }
     }
+}
 
-    /// Sends the `PlayersUpdate` to all currently joined players. Should be invoked
-    /// whenever the list of players changes.
-    /// If `except` is not None, the message will not be sent to the player with this id.
-    pub fn send_players_update(&self, except: Option<&Uuid>) -> anyhow::Result<()> {
-        let message = PlayersUpdate {
-            players: self.get_players(),
-        };
-
-        // (maybe) NICE TO HAVE: delay sending of the update by 100 ms, and
-        //   if another `send_players_update` is called, discard the previous one and send the new one
-        if let Some(uuid) = except {
-            self.send_to_others(&ServerNetworkMessage::PlayersUpdate(message.clone()), uuid);
-        } else {
-            self.send_to_all(&ServerNetworkMessage::PlayersUpdate(message.clone()));
-        }
-
-        // also send to teacher
-        let Some(ref teacher) = self.teacher else {
-            anyhow::bail!("Cannot send to teacher, Teacher is null");
-        };
-
-        teacher.do_send(message);
-        Ok(())
-    }
+/// The choices of the question that match the `filter`, for the `PersonalReport`
+fn choice_reports(question: &Question, filter: impl Fn(&Choice) -> bool) -> Vec<ChoiceReport> {
+    question
+        .choices
+        .iter()
+        .filter(|choice| filter(choice))
+        .map(|choice| ChoiceReport {
+            text: choice.text.clone(),
+            code: choice.code.clone(),
+        })
+        .collect()
 }
 
 impl Actor for Lobby {
     type Context = Context<Self>;
 
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         debug!("Lobby started");
+
+        ctx.run_interval(self.players_resync_period, |lobby, _| {
+            lobby.resync_players();
+        });
+
+        if self.snapshot_file.is_some() {
+            ctx.run_interval(SNAPSHOT_PERIOD, |lobby, _| {
+                if let Err(err) = lobby.save_snapshot() {
+                    error!("Failed to save the snapshot: {err:#}");
+                }
+            });
+        }
     }
 
     fn stopping(&mut self, _ctx: &mut Self::Context) -> actix::prelude::Running {
@@ -310,5 +643,18 @@ impl Actor for Lobby {
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
         debug!("Lobby stopped");
+
+        for player in self
+            .joined_players
+            .values()
+            .chain(self.awaiting_approval.values())
+        {
+            player.stop(None);
+        }
+
+        self.rooms.remove(&self.pin);
+        if self.rooms.is_empty() {
+            System::current().stop();
+        }
     }
 }
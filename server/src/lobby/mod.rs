@@ -1,10 +1,23 @@
+mod analysis;
+mod approval;
+mod export;
 mod handlers;
 mod init;
+mod join_code;
 mod lobby_impl;
+mod players_update;
 mod point_calculator;
+mod roster;
+mod snapshot;
 mod state;
 
+pub use analysis::*;
+pub use export::*;
 pub use handlers::*;
 pub use init::*;
+pub use join_code::*;
+pub use players_update::*;
 pub use point_calculator::*;
+pub use roster::*;
+pub use snapshot::*;
 pub use state::*;
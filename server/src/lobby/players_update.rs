@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use actix::{AsyncContext, Context};
+use common::messages::{
+    encoding::SharedMessage,
+    network::{PlayersDelta, PlayersUpdate},
+    protocol::Capability,
+    ServerNetworkMessage,
+};
+use log::debug;
+use uuid::Uuid;
+
+use crate::messages::websocket::Broadcast;
+
+use super::state::{JoinedPlayer, Lobby};
+
+/// How long the changes of the players are collected before they are sent,
+/// so a burst of students joining at once is sent as one message
+pub const PLAYERS_UPDATE_DELAY: Duration = Duration::from_millis(100);
+
+/// How often everybody gets the whole list of players, in case they missed a change
+pub const PLAYERS_RESYNC_PERIOD: Duration = Duration::from_secs(30);
+
+/// Changes of the players which were not sent to the players yet
+#[derive(Debug, Clone, Default)]
+pub struct PendingPlayersUpdate {
+    joined: Vec<Uuid>,
+    left: Vec<Uuid>,
+    scheduled: bool,
+}
+
+impl Lobby {
+    /// The others learn about the player with the next batch of changes,
+    /// the teacher right away
+    /// # Errors
+    /// - if there is no teacher
+    pub fn player_joined(&mut self, id: Uuid, ctx: &mut Context<Self>) -> anyhow::Result<()> {
+        let pending = &mut self.pending_players_update;
+        pending.left.retain(|left| *left != id);
+        if !pending.joined.contains(&id) {
+            pending.joined.push(id);
+        }
+
+        self.schedule_players_update(ctx);
+        self.send_players_to_teacher()
+    }
+
+    /// The others learn about the player leaving with the next batch of changes,
+    /// the teacher right away
+    /// # Errors
+    /// - if there is no teacher
+    pub fn player_left(&mut self, id: Uuid, ctx: &mut Context<Self>) -> anyhow::Result<()> {
+        let pending = &mut self.pending_players_update;
+        pending.joined.retain(|joined| *joined != id);
+        if !pending.left.contains(&id) {
+            pending.left.push(id);
+        }
+
+        self.schedule_players_update(ctx);
+        self.send_players_to_teacher()
+    }
+
+    fn schedule_players_update(&mut self, ctx: &mut Context<Self>) {
+        if self.pending_players_update.scheduled {
+            return;
+        }
+
+        self.pending_players_update.scheduled = true;
+        ctx.run_later(self.players_update_delay, |lobby, _| {
+            lobby.flush_players_update();
+        });
+    }
+
+    /// Sends the collected changes of the players, as a `PlayersDelta` to the players
+    /// who can apply it and as the whole `PlayersUpdate` to the others
+    pub fn flush_players_update(&mut self) {
+        let pending = std::mem::take(&mut self.pending_players_update);
+
+        let delta = PlayersDelta {
+            // a player who left in the meantime is already in `left`
+            joined: pending
+                .joined
+                .iter()
+                .filter_map(|id| self.joined_players.get(id))
+                .map(JoinedPlayer::data)
+                .collect(),
+            left: pending.left,
+        };
+
+        if delta.is_empty() {
+            return;
+        }
+
+        debug!(
+            "Sending {} joined and {} left players",
+            delta.joined.len(),
+            delta.left.len()
+        );
+
+        let shared_delta = Broadcast(SharedMessage::new(ServerNetworkMessage::PlayersDelta(
+            delta.clone(),
+        )));
+        let update = Broadcast(SharedMessage::new(ServerNetworkMessage::PlayersUpdate(
+            PlayersUpdate {
+                players: self.get_players(),
+            },
+        )));
+
+        for player in self.joined_players.values() {
+            let delta_aware = player.capabilities.contains(&Capability::PlayersDelta);
+
+            if !delta.joined.iter().any(|joined| joined.uuid == player.uuid) {
+                if delta_aware {
+                    player.broadcast(shared_delta.clone());
+                } else {
+                    player.broadcast(update.clone());
+                }
+                continue;
+            }
+
+            // the player joined in this batch and knows about themselves from the `JoinResponse`
+            let own_delta = PlayersDelta {
+                joined: delta
+                    .joined
+                    .iter()
+                    .filter(|joined| joined.uuid != player.uuid)
+                    .cloned()
+                    .collect(),
+                left: delta.left.clone(),
+            };
+
+            if own_delta.is_empty() {
+                continue;
+            }
+
+            if delta_aware {
+                player.do_send(ServerNetworkMessage::PlayersDelta(own_delta));
+            } else {
+                player.broadcast(update.clone());
+            }
+        }
+    }
+
+    /// Sends the whole list of players to everybody
+    pub fn resync_players(&self) {
+        if self.joined_players.is_empty() {
+            return;
+        }
+
+        self.send_to_all(&ServerNetworkMessage::PlayersUpdate(PlayersUpdate {
+            players: self.get_players(),
+        }));
+    }
+
+    pub fn send_players_to_teacher(&self) -> anyhow::Result<()> {
+        let Some(ref teacher) = self.teacher else {
+            anyhow::bail!("Cannot send to teacher, Teacher is null");
+        };
+
+        teacher.do_send(PlayersUpdate {
+            players: self.get_players(),
+        });
+        self.send_missing_students_to_teacher();
+        Ok(())
+    }
+}
@@ -67,24 +67,28 @@ mod tests {
         let choice_1 = Choice {
             id: Uuid::new_v4(),
             text: "42".to_string(),
+            code: None,
             is_correct: true,
         };
 
         let choice_2 = Choice {
             id: Uuid::new_v4(),
             text: "43".to_string(),
+            code: None,
             is_correct: true,
         };
 
         let choice_3 = Choice {
             id: Uuid::new_v4(),
             text: "44".to_string(),
+            code: None,
             is_correct: false,
         };
 
         let choice_4 = Choice {
             id: Uuid::new_v4(),
             text: "45".to_string(),
+            code: None,
             is_correct: false,
         };
 
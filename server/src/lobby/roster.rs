@@ -0,0 +1,139 @@
+use std::{collections::HashSet, path::Path};
+
+use anyhow::{bail, Context};
+use common::constants::{STUDENT_ID_REQUIRED_MSG, STUDENT_ID_TAKEN_MSG, UNKNOWN_STUDENT_ID_MSG};
+use serde::{Deserialize, Serialize};
+
+use crate::messages::teacher::ShowMissingStudents;
+
+use super::state::Lobby;
+
+/// A student from the class roster
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterStudent {
+    pub id: String,
+    pub name: String,
+}
+
+/// Students of the class, the joining players have to enter the ID of one of them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Roster {
+    students: Vec<RosterStudent>,
+}
+
+impl Roster {
+    /// Loads the roster from a CSV file with the `id` and `name` columns
+    /// # Errors
+    /// - if the file cannot be read
+    /// - if some of the rows is not a valid student
+    /// - if some of the students has no ID, or the same ID as another student
+    /// - if there are no students
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .with_context(|| format!("Cannot open roster {}", path.display()))?;
+
+        let students = reader
+            .deserialize()
+            .collect::<Result<Vec<RosterStudent>, _>>()
+            .with_context(|| format!("Invalid roster {}", path.display()))?;
+
+        Self::from_students(students).with_context(|| format!("Invalid roster {}", path.display()))
+    }
+
+    /// # Errors
+    /// - if some of the students has no ID, or the same ID as another student
+    /// - if there are no students
+    pub fn from_students(students: Vec<RosterStudent>) -> anyhow::Result<Self> {
+        if students.is_empty() {
+            bail!("There are no students in the roster");
+        }
+
+        let mut ids = HashSet::new();
+        for student in &students {
+            if student.id.trim().is_empty() {
+                bail!("Student {} has no ID", student.name);
+            }
+            if !ids.insert(student.id.trim().to_ascii_lowercase()) {
+                bail!("Student ID {} is in the roster more than once", student.id);
+            }
+        }
+
+        Ok(Self { students })
+    }
+
+    /// The student with the ID, the letter case and the spaces around it do not matter
+    #[must_use]
+    pub fn find(&self, id: &str) -> Option<&RosterStudent> {
+        let id = id.trim();
+        self.students
+            .iter()
+            .find(|student| student.id.trim().eq_ignore_ascii_case(id))
+    }
+
+    #[must_use]
+    pub fn students(&self) -> &[RosterStudent] {
+        &self.students
+    }
+}
+
+impl Lobby {
+    /// Checks the student ID the player joins with, returns the reason for the player
+    /// if it cannot join, or the ID from the roster
+    pub(super) fn check_student_id(&self, given: Option<&str>) -> Result<Option<String>, String> {
+        let Some(roster) = &self.roster else {
+            return Ok(None);
+        };
+
+        let Some(given) = given.filter(|id| !id.trim().is_empty()) else {
+            return Err(STUDENT_ID_REQUIRED_MSG.to_owned());
+        };
+
+        let Some(student) = roster.find(given) else {
+            return Err(UNKNOWN_STUDENT_ID_MSG.to_owned());
+        };
+
+        if self
+            .joined_players
+            .values()
+            .chain(self.awaiting_approval.values())
+            .any(|player| player.student_id.as_ref() == Some(&student.id))
+        {
+            return Err(STUDENT_ID_TAKEN_MSG.to_owned());
+        }
+
+        Ok(Some(student.id.clone()))
+    }
+
+    /// Students from the roster who are not in the game, in the order of the roster
+    #[must_use]
+    pub fn missing_students(&self) -> Vec<RosterStudent> {
+        let Some(roster) = &self.roster else {
+            return Vec::new();
+        };
+
+        let present: HashSet<&String> = self
+            .joined_players
+            .values()
+            .filter_map(|player| player.student_id.as_ref())
+            .collect();
+
+        roster
+            .students()
+            .iter()
+            .filter(|student| !present.contains(&student.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Lets the teacher know who from the roster is missing, if there is a roster
+    pub fn send_missing_students_to_teacher(&self) {
+        if let (Some(teacher), Some(roster)) = (&self.teacher, &self.roster) {
+            teacher.do_send(ShowMissingStudents {
+                missing: self.missing_students(),
+                roster_size: roster.students().len(),
+            });
+        }
+    }
+}
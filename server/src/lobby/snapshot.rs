@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use common::{
+    constants::NICKNAME_ALREADY_TAKEN_MSG, messages::network::PlayerData, questions::QuestionSet,
+};
+use log::info;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::state::{Lobby, Phase, QuestionRecords};
+
+pub const DEFAULT_SNAPSHOT_FILE: &str = "clihoot_snapshot.json";
+
+/// How often the lobby saves its state to the snapshot file
+pub const SNAPSHOT_PERIOD: Duration = Duration::from_secs(5);
+
+/// Part of the lobby state needed to continue the game after the server restarts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LobbySnapshot {
+    pub saved_at: DateTime<Utc>,
+    pub phase: Phase,
+    /// the questions in the order (and with the choices in the order) they are asked,
+    /// so the randomization is not done again
+    pub questions: QuestionSet,
+    /// players who were in the game when the snapshot was taken
+    pub players: Vec<PlayerData>,
+    pub left_players: Vec<PlayerData>,
+    pub results: QuestionRecords,
+    pub choices_shown_at: HashMap<usize, DateTime<Utc>>,
+    /// student IDs from the class roster of the players, the snapshots from before
+    /// the rosters have none
+    #[serde(default)]
+    pub student_ids: HashMap<Uuid, String>,
+    /// secrets of the clients of the players, the players without one cannot continue
+    /// with their results unless there is a roster
+    #[serde(default)]
+    pub resume_tokens: HashMap<Uuid, String>,
+}
+
+impl LobbySnapshot {
+    /// Loads the snapshot from a JSON file
+    /// # Errors
+    /// - if the file cannot be read
+    /// - if the file is not a valid snapshot
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("Cannot open snapshot {}", path.display()))?;
+
+        serde_json::from_reader(file)
+            .with_context(|| format!("Invalid snapshot {}", path.display()))
+    }
+
+    /// Writes the snapshot to a JSON file, the old snapshot is replaced only
+    /// when the new one is completely written
+    /// # Errors
+    /// - if the file cannot be written
+    pub fn write_to(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+
+        let file = File::create(&tmp_path)
+            .with_context(|| format!("Cannot create {}", tmp_path.display()))?;
+        serde_json::to_writer(file, self)?;
+
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Cannot replace snapshot {}", path.display()))?;
+
+        Ok(())
+    }
+}
+
+impl Lobby {
+    #[must_use]
+    pub fn snapshot(&self) -> LobbySnapshot {
+        LobbySnapshot {
+            saved_at: Utc::now(),
+            phase: self.phase.clone(),
+            questions: self.questions.clone(),
+            players: self
+                .get_players()
+                .into_iter()
+                // players who did not reconnect yet are still part of the game
+                .chain(self.resumed_players.values().cloned())
+                .collect(),
+            left_players: self
+                .left_players
+                .values()
+                .filter(|player| !self.resumed_players.contains_key(&player.uuid))
+                .cloned()
+                .collect(),
+            results: self.results.clone(),
+            choices_shown_at: self.choices_shown_at.clone(),
+            student_ids: self.student_ids.clone(),
+            resume_tokens: self.resume_tokens.clone(),
+        }
+    }
+
+    /// Saves the snapshot to the snapshot file, does nothing if there is no snapshot file
+    /// # Errors
+    /// - if the snapshot cannot be written
+    pub fn save_snapshot(&self) -> anyhow::Result<()> {
+        match &self.snapshot_file {
+            Some(path) => self.snapshot().write_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Restores the lobby from the snapshot, the players have to join again (from the same
+    /// client with the same nickname, or with the same student ID if there is a roster)
+    /// to continue with their results. The game is not locked, so they can come back.
+    #[must_use]
+    pub fn from_snapshot(snapshot: LobbySnapshot) -> Self {
+        let mut lobby = Lobby::new(snapshot.questions);
+        let last_question = lobby.questions.len().saturating_sub(1);
+
+        let interrupted_question = match snapshot.phase {
+            Phase::ActiveQuestion(index) => Some(index),
+            _ => None,
+        };
+
+        // the timer of the active question is gone, so the question is asked again,
+        // a question which is over is continued with the leaderboard
+        lobby.phase = match snapshot.phase {
+            Phase::ActiveQuestion(0) => Phase::WaitingForPlayers,
+            Phase::ActiveQuestion(index) => Phase::ShowingLeaderboard(index - 1),
+            Phase::AfterQuestion(index) | Phase::ShowingLeaderboard(index)
+                if index == last_question =>
+            {
+                Phase::GameEnded
+            }
+            Phase::AfterQuestion(index) => Phase::ShowingLeaderboard(index),
+            phase => phase,
+        };
+
+        lobby.results = snapshot.results;
+        lobby.choices_shown_at = snapshot.choices_shown_at;
+        if let Some(index) = interrupted_question {
+            lobby.results.remove(&index);
+            lobby.choices_shown_at.remove(&index);
+        }
+
+        lobby.student_ids = snapshot.student_ids;
+        lobby.resume_tokens = snapshot.resume_tokens;
+        lobby.left_players = snapshot
+            .left_players
+            .into_iter()
+            .chain(snapshot.players.iter().cloned())
+            .map(|player| (player.uuid, player))
+            .collect();
+        lobby.resumed_players = snapshot
+            .players
+            .into_iter()
+            .map(|player| (player.uuid, player))
+            .collect();
+
+        info!(
+            "Game restored from the snapshot taken at {}, phase {:?}",
+            snapshot.saved_at, lobby.phase
+        );
+
+        lobby
+    }
+
+    /// The player from the snapshot the joining player continues as, with a roster
+    /// the student with the same ID, otherwise the player with the same nickname
+    /// whose client sent the same resume token. Returns the reason for the player
+    /// if it cannot join, the nickname of a resumed player is not given to anybody else.
+    pub(super) fn find_resumed_player(
+        &self,
+        nickname: &str,
+        student_id: Option<&str>,
+        resume_token: Option<&str>,
+    ) -> Result<Option<Uuid>, String> {
+        if let Some(student_id) = student_id {
+            let same_student = self
+                .resumed_players
+                .keys()
+                .find(|uuid| self.student_ids.get(uuid).map(String::as_str) == Some(student_id));
+            if let Some(uuid) = same_student {
+                return Ok(Some(*uuid));
+            }
+        }
+
+        let Some(player) = self
+            .resumed_players
+            .values()
+            .find(|player| player.nickname == nickname)
+        else {
+            return Ok(None);
+        };
+
+        // the students from the roster are told apart by their ID only
+        let same_client = resume_token.is_some()
+            && self.resume_tokens.get(&player.uuid).map(String::as_str) == resume_token;
+        if self.student_ids.contains_key(&player.uuid) || !same_client {
+            return Err(NICKNAME_ALREADY_TAKEN_MSG.to_owned());
+        }
+
+        Ok(Some(player.uuid))
+    }
+
+    /// The results of the player from the snapshot are moved to the new uuid
+    pub(super) fn restore_resumed_player(&mut self, old_uuid: Uuid, uuid: Uuid) {
+        let Some(player) = self.resumed_players.remove(&old_uuid) else {
+            return;
+        };
+        self.left_players.remove(&old_uuid);
+        self.student_ids.remove(&old_uuid);
+        self.resume_tokens.remove(&old_uuid);
+
+        for records in self.results.values_mut() {
+            if let Some(record) = records.remove(&old_uuid) {
+                records.insert(uuid, record);
+            }
+        }
+
+        info!("Player {} continues the resumed game", player.nickname);
+    }
+}
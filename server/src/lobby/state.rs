@@ -1,22 +1,33 @@
-use crate::websocket::Websocket;
-use crate::Teacher;
+use crate::attach::TeacherHandle;
+use crate::journal::Journal;
+use crate::lobby::{JoinAttempts, PendingPlayersUpdate, Roster};
+use crate::messages::client::PlayerSocket;
+use crate::rooms::Rooms;
+use crate::tls::TlsSettings;
+use crate::websocket::{ConnectionCounter, ConnectionLimits, OutboxMetrics};
 use actix::Actor;
-use actix::Addr;
 
 use actix::dev::MessageResponse;
 use actix::dev::OneshotSender;
 use actix::Message;
 use chrono::DateTime;
 use chrono::Utc;
+use common::heartbeat::Heartbeat;
+use common::messages::network::PlayerData;
+use common::messages::protocol::Capability;
 use common::questions::QuestionSet;
+use common::syntax::CustomSyntax;
 use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ops::Deref;
+use std::path::PathBuf;
+use std::time::Duration;
 use uuid::Uuid;
 
-#[derive(Default, PartialEq, Clone, Debug)]
+#[derive(Default, PartialEq, Clone, Debug, Serialize, Deserialize)]
 
 pub enum Phase {
     #[default]
@@ -27,7 +38,7 @@ pub enum Phase {
     GameEnded,
 }
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct PlayerQuestionRecord {
     pub answer_order: usize,
     pub timestamp: DateTime<Utc>,
@@ -46,12 +57,35 @@ pub struct JoinedPlayer {
     pub uuid: Uuid,
     pub nickname: String,
     pub color: Color,
-    pub addr: Addr<Websocket>,
+    pub color_index: Option<usize>,
+    pub avatar: Option<String>,
+    pub addr: PlayerSocket,
     pub joined_at: DateTime<Utc>,
+    /// capabilities the client announced when it tried to join
+    pub capabilities: Vec<Capability>,
+    /// ID of the student from the class roster, `None` if there is no roster
+    pub student_id: Option<String>,
+    /// secret of the client, proves who the player is when the game is resumed
+    pub resume_token: Option<String>,
+    /// uuid of the player from the resumed game this player continues as
+    pub resumes: Option<Uuid>,
+}
+
+impl JoinedPlayer {
+    #[must_use]
+    pub fn data(&self) -> PlayerData {
+        PlayerData {
+            color: self.color,
+            color_index: self.color_index,
+            nickname: self.nickname.clone(),
+            avatar: self.avatar.clone(),
+            uuid: self.uuid,
+        }
+    }
 }
 
 impl Deref for JoinedPlayer {
-    type Target = Addr<Websocket>;
+    type Target = PlayerSocket;
 
     fn deref(&self) -> &Self::Target {
         &self.addr
@@ -60,8 +94,8 @@ impl Deref for JoinedPlayer {
 
 #[derive(Debug, Clone)]
 pub struct Lobby {
-    /// An address to the teacher actor
-    pub teacher: Option<Addr<Teacher>>,
+    /// The teacher of the game, in this process or attached from another one
+    pub teacher: Option<TeacherHandle>,
 
     /// Phase of the game  
     pub phase: Phase,
@@ -79,8 +113,101 @@ pub struct Lobby {
     /// All questions to be asked
     pub questions: QuestionSet,
 
-    /// Players who have sent a TryJoinRequest, but have not joined yet
+    /// When the choices of the questions were shown to the players
+    /// * `choices_shown_at[question_index] = time`
+    pub choices_shown_at: HashMap<usize, DateTime<Utc>>,
+
+    /// Players who have sent a `TryJoinRequest`, but have not joined yet
     pub waiting_players: HashSet<Uuid>,
+
+    /// Whether the teacher lets in every joining player
+    pub approve_joins: bool,
+
+    /// Players who sent a valid `JoinRequest` and wait until the teacher lets them in
+    pub awaiting_approval: HashMap<Uuid, JoinedPlayer>,
+
+    /// Custom syntaxes used in the questions, sent to the clients when they try to join
+    pub custom_syntaxes: Vec<CustomSyntax>,
+
+    /// Players who disconnected or were kicked, kept so their results can be exported
+    pub left_players: HashMap<Uuid, PlayerData>,
+
+    /// Where to export the results to, `None` disables the export
+    pub results_dir: Option<PathBuf>,
+
+    /// `SQLite` database where the game is stored when it ends, `None` disables the history
+    pub db: Option<PathBuf>,
+
+    /// Where the state of the game is periodically saved, `None` disables the snapshots
+    pub snapshot_file: Option<PathBuf>,
+
+    /// Players from the snapshot the game was resumed from, who have not joined again yet
+    pub resumed_players: HashMap<Uuid, PlayerData>,
+
+    /// Where the messages of the game are recorded, `None` disables the journal
+    pub journal: Option<Journal>,
+
+    /// Whether the game is replayed from a journal, the questions then do not end by themselves
+    pub replaying: bool,
+
+    /// Certificate the connections are encrypted with, `None` accepts plain `ws://` connections
+    pub tls: Option<TlsSettings>,
+
+    /// How the websockets check that the clients are alive
+    pub heartbeat: Heartbeat,
+
+    /// Joined players whose clients stopped responding, nobody waits for their answers
+    pub away_players: HashSet<Uuid>,
+
+    /// Capabilities the server announces to the clients
+    pub capabilities: Vec<Capability>,
+
+    /// How long the changes of the players are collected before they are sent
+    pub players_update_delay: Duration,
+
+    /// How often everybody gets the whole list of players
+    pub players_resync_period: Duration,
+
+    /// Changes of the players which were not sent to the players yet
+    pub pending_players_update: PendingPlayersUpdate,
+
+    /// How many messages may wait for a client before it is disconnected as too slow
+    pub outbox_capacity: usize,
+
+    /// Statistics of the outbound queues of the clients, shared by all the lobbies of the server
+    pub outbox_metrics: OutboxMetrics,
+
+    /// What a single client connection is allowed to send
+    pub connection_limits: ConnectionLimits,
+
+    /// Open connections from each IP address
+    pub connections: ConnectionCounter,
+
+    /// PIN of the game, the students use it to find this lobby when the server hosts more games
+    pub pin: String,
+
+    /// Games hosted by the server, the lobby leaves them when it stops
+    pub rooms: Rooms,
+
+    /// Code the students have to enter to join, `None` lets anybody who knows the address join
+    pub join_code: Option<String>,
+
+    /// Secret of the game, only its teacher knows it, so nobody else can attach
+    /// when the teacher lost the connection
+    pub teacher_key: String,
+
+    /// Wrong join codes sent from each IP address
+    pub join_attempts: JoinAttempts,
+
+    /// Students of the class, `None` lets the players join without a student ID
+    pub roster: Option<Roster>,
+
+    /// Student ID of every player who joined with one, kept after they leave for the export
+    pub student_ids: HashMap<Uuid, String>,
+
+    /// Secret of every player whose client sent one, saved in the snapshot so the players
+    /// can prove who they are when the game is resumed
+    pub resume_tokens: HashMap<Uuid, String>,
 }
 
 impl<A, M> MessageResponse<A, M> for Lobby
@@ -1,27 +1,55 @@
 use std::{
-    sync::mpsc::{self},
+    net::{Ipv4Addr, SocketAddr},
+    sync::{
+        mpsc::{self},
+        Arc,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 use anyhow::bail;
 use clap::Parser;
-use common::questions::QuestionSet;
-use server::{lobby::run_server, teacher::run_teacher, Args};
+use common::{heartbeat::Heartbeat, questions::QuestionSet, syntax, terminal::ui_theme};
+use server::{
+    history::{self, DEFAULT_DB_FILE},
+    journal::Journal,
+    lobby::{run_rooms, AttachSettings, LobbySnapshot, NewRoom},
+    messages::attach::AttachRequest,
+    replay,
+    teacher::{run_attached_teacher, run_teacher},
+    Args, Command, Lobby,
+};
 
 use simplelog::{
     ColorChoice, CombinedLogger, Config, LevelFilter, TermLogger, TerminalMode, WriteLogger,
 };
 
-use std::fs::File;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 
+#[allow(clippy::too_many_lines)]
 fn main() -> anyhow::Result<()> {
     let args: Args = Args::parse();
 
+    if let Some(Command::History { session }) = args.command {
+        let db = args.db.unwrap_or_else(|| DEFAULT_DB_FILE.into());
+        return history::print_history(&db, session);
+    }
+
+    // the teacher of another game must not overwrite the log of the server
+    let log_file = match &args.command {
+        Some(Command::Attach { log_file, .. }) => log_file,
+        _ => &args.log_file,
+    };
+
     CombinedLogger::init(vec![
         WriteLogger::new(
             LevelFilter::Debug,
             Config::default(),
-            File::create(args.log_file)?,
+            File::create(log_file)?,
         ),
         TermLogger::new(
             LevelFilter::Error,
@@ -31,11 +59,40 @@ fn main() -> anyhow::Result<()> {
         ),
     ])?;
 
-    let path = std::path::Path::new(&args.questions_file);
-    let mut questions = QuestionSet::from_file(path)?;
+    // custom syntaxes have to be loaded before the questions, so their languages are known
+    if let Some(syntax_dir) = &args.syntax_dir {
+        syntax::load_custom_dir(syntax_dir)?;
+    }
+    args.syntax_theme.ensure_loaded()?;
+    ui_theme::init(args.ui_theme);
+
+    if let Some(Command::Replay { journal, speed }) = &args.command {
+        return replay::replay(journal, *speed, args.syntax_theme);
+    }
+
+    if let Some(Command::Attach {
+        pin,
+        questions_file,
+        key,
+        ..
+    }) = &args.command
+    {
+        let Some(attach_port) = args.attach_port else {
+            bail!("The server's --attach-port is needed to attach to it");
+        };
 
-    questions.randomize_answers = args.randomize_answers;
-    questions.randomize_questions = args.randomize_questions;
+        let questions = questions_file
+            .as_deref()
+            .map(QuestionSet::from_file)
+            .transpose()?;
+        let request = AttachRequest {
+            pin: pin.clone(),
+            questions,
+            key: key.clone(),
+        };
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, attach_port));
+        return run_attached_teacher(addr, &request, args.syntax_theme);
+    }
 
     // construct address on which the server will listen
     let addr = format!("0.0.0.0:{}", args.port).parse()?;
@@ -44,26 +101,122 @@ fn main() -> anyhow::Result<()> {
     let (tx_server, rx_server) = mpsc::channel();
     let (tx_teacher, _rx_teacher) = mpsc::channel();
 
-    let quiz_name = questions.quiz_name.clone();
+    let tls = args.tls()?;
+    let roster = args.roster()?;
+
+    // every game of the server is set up the same way, also the ones the attached teachers start
+    let configure = {
+        let args = args.clone();
+        Arc::new(move |lobby: Lobby| {
+            lobby
+                .with_results_dir(Some(args.results_dir.clone()))
+                .with_db(args.db.clone())
+                .with_tls(tls.clone())
+                .with_heartbeat(Heartbeat::from_secs(
+                    args.heartbeat_interval,
+                    args.heartbeat_timeout,
+                ))
+                .with_json_only(args.json_only)
+                .with_outbox_capacity(args.outbox_capacity)
+                .with_connection_limits(args.connection_limits())
+                .with_join_code(args.join_code())
+                .with_approve_joins(args.approve_joins)
+                .with_roster(roster.clone())
+        })
+    };
+
+    let lobby = if let Some(snapshot) = &args.resume {
+        Lobby::from_snapshot(LobbySnapshot::load(snapshot)?)
+    } else {
+        let mut questions = QuestionSet::from_file(&args.questions_file()?)?;
+
+        questions.randomize_answers = args.randomize_answers;
+        questions.randomize_questions = args.randomize_questions;
+
+        Lobby::new(questions)
+    }
+    .with_pin(args.pin.clone());
+
+    let journal = match &args.journal {
+        Some(path) => Some(Journal::create(path, &lobby.questions)?),
+        None => None,
+    };
+    let lobby = configure(
+        lobby
+            .with_journal(journal)
+            .with_snapshot_file(Some(args.snapshot_file.clone())),
+    );
+
+    // the journal records only one game, so no other games are started with it
+    let attach_port = args.attach_port.filter(|_| args.journal.is_none());
+    let attach = attach_port.map(|attach_port| {
+        let args = args.clone();
+        let new_room: NewRoom = Arc::new(move |mut questions: QuestionSet, pin: String| {
+            questions.randomize_answers = args.randomize_answers;
+            questions.randomize_questions = args.randomize_questions;
+
+            let snapshot_file = room_snapshot_file(&args.snapshot_file, &pin);
+            configure(
+                Lobby::new(questions)
+                    .with_pin(Some(pin))
+                    .with_snapshot_file(Some(snapshot_file)),
+            )
+        });
+
+        AttachSettings {
+            addr: SocketAddr::from((Ipv4Addr::LOCALHOST, attach_port)),
+            new_room,
+        }
+    });
+
+    let quiz_name = lobby.questions.quiz_name.clone();
 
     let server_thread = thread::spawn(move || {
-        run_server(tx_server, questions, addr).expect("Failed to run server");
+        run_rooms(&tx_server, vec![lobby], addr, attach).expect("Failed to run server");
     });
 
     let teacher_thread = thread::spawn(move || {
-        let server = rx_server.recv().expect("Failed to receive server address");
-        run_teacher(server, tx_teacher, &quiz_name, args.syntax_theme)
-            .expect("Failed to run teacher");
+        let lobbies = rx_server.recv().expect("Failed to receive server address");
+        run_teacher(
+            lobbies[0].clone(),
+            &tx_teacher,
+            &quiz_name,
+            args.syntax_theme,
+        )
+        .expect("Failed to run teacher");
     });
 
-    // wait for threads to finish
-    if let Err(_e) = server_thread.join() {
-        bail!("Server thread panicked");
-    }
-
     if let Err(_e) = teacher_thread.join() {
         bail!("Teacher thread panicked");
     }
 
+    // the games of the attached teachers go on after the main one,
+    // the server stops right away when there are none
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while !server_thread.is_finished() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(50));
+    }
+    if attach_port.is_some() && !server_thread.is_finished() {
+        eprintln!("The server keeps running until the games of the attached teachers end (Ctrl+C stops it now)");
+    }
+
+    if let Err(_e) = server_thread.join() {
+        bail!("Server thread panicked");
+    }
+
     Ok(())
 }
+
+/// Snapshot file of a game started by an attached teacher, named after the PIN of the game
+fn room_snapshot_file(snapshot_file: &Path, pin: &str) -> PathBuf {
+    let stem = snapshot_file
+        .file_stem()
+        .map_or_else(|| "clihoot_snapshot".into(), |stem| stem.to_string_lossy());
+    let extension = snapshot_file
+        .extension()
+        .map_or_else(String::new, |extension| {
+            format!(".{}", extension.to_string_lossy())
+        });
+
+    snapshot_file.with_file_name(format!("{stem}_{pin}{extension}"))
+}
@@ -0,0 +1,107 @@
+use actix::Addr;
+use common::{
+    messages::network::{
+        NextQuestion, PlayersUpdate, QuestionEnded, QuestionUpdate, ShowLeaderboard,
+    },
+    questions::QuestionSet,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    lobby::{
+        DecideJoins, EndQuestion, ExportResults, HardStop, KickPlayer, StartQuestion,
+        SwitchToLeaderboard,
+    },
+    teacher::{
+        ReplayStarted, ResultsExported, ShowAnalysis, ShowApprovalQueue, ShowFingerprint,
+        ShowJoinCode, ShowMissingStudents, ShowPin, ShowWarning,
+    },
+};
+use crate::{Lobby, Teacher};
+
+/// The teacher in another process sends this first, right after it connects to the server
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachRequest {
+    /// PIN of the game, a random one is generated for a new game if it is not given
+    pub pin: Option<String>,
+    /// questions of a new game, `None` attaches to the game with the PIN
+    /// whose teacher lost the connection
+    pub questions: Option<QuestionSet>,
+    /// secret of the game the teacher got when it attached first, needed to attach again
+    pub key: Option<String>,
+}
+
+/// What the teacher needs to know about the game it attached to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attached {
+    pub quiz_name: String,
+    pub pin: String,
+    /// secret of the game, the teacher needs it to attach again
+    pub key: String,
+}
+
+/// The server answers the `AttachRequest` with the game,
+/// or with the reason why the teacher cannot attach
+pub type AttachResponse = Result<Attached, String>;
+
+/// Generates the enum of the messages for an actor in another process,
+/// each variant wraps the message of the same name
+macro_rules! remote_messages {
+    ($(#[$meta:meta])* $name:ident for $actor:ty { $($message:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Serialize, Deserialize)]
+        pub enum $name {
+            $($message($message),)*
+        }
+
+        $(
+            impl From<$message> for $name {
+                fn from(msg: $message) -> Self {
+                    Self::$message(msg)
+                }
+            }
+        )*
+
+        impl $name {
+            /// Passes the message on to the actor in this process
+            pub fn deliver(self, actor: &Addr<$actor>) {
+                match self {
+                    $(Self::$message(msg) => actor.do_send(msg),)*
+                }
+            }
+        }
+    };
+}
+
+remote_messages! {
+    /// What the lobby sends to a teacher in another process
+    ToTeacher for Teacher {
+        ShowPin,
+        ShowFingerprint,
+        ShowJoinCode,
+        ShowApprovalQueue,
+        ShowMissingStudents,
+        ShowWarning,
+        ShowAnalysis,
+        ResultsExported,
+        ReplayStarted,
+        PlayersUpdate,
+        NextQuestion,
+        QuestionUpdate,
+        QuestionEnded,
+        ShowLeaderboard,
+    }
+}
+
+remote_messages! {
+    /// What a teacher in another process sends to its lobby
+    ToLobby for Lobby {
+        StartQuestion,
+        EndQuestion,
+        KickPlayer,
+        DecideJoins,
+        SwitchToLeaderboard,
+        ExportResults,
+        HardStop,
+    }
+}
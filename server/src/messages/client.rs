@@ -1,11 +1,74 @@
-use actix::{Addr, Message};
-use common::messages::network::PlayerData;
+use std::net::IpAddr;
 
-use crate::websocket::Websocket;
+use actix::{dev::ToEnvelope, Actor, Addr, Handler, Message, Recipient};
+use common::messages::{
+    network::{self, PlayerData},
+    protocol::Capability,
+    ServerNetworkMessage,
+};
 
+use crate::messages::websocket::{Broadcast, GracefulStop};
+
+/// Where the lobby sends the messages for a joined player,
+/// usually the websocket of the player, or a player replayed from a journal
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerSocket {
+    messages: Recipient<ServerNetworkMessage>,
+    broadcasts: Recipient<Broadcast>,
+    stop: Recipient<GracefulStop>,
+}
+
+impl PlayerSocket {
+    pub fn do_send(&self, msg: ServerNetworkMessage) {
+        self.messages.do_send(msg);
+    }
+
+    /// Sends a message shared with other players, see [`Broadcast`]
+    pub fn broadcast(&self, msg: Broadcast) {
+        self.broadcasts.do_send(msg);
+    }
+
+    /// Hangs up on the player
+    pub fn stop(&self, reason: Option<String>) {
+        self.stop.do_send(GracefulStop { reason });
+    }
+}
+
+impl<A> From<Addr<A>> for PlayerSocket
+where
+    A: Actor + Handler<ServerNetworkMessage> + Handler<Broadcast> + Handler<GracefulStop>,
+    A::Context: ToEnvelope<A, ServerNetworkMessage>
+        + ToEnvelope<A, Broadcast>
+        + ToEnvelope<A, GracefulStop>,
+{
+    fn from(addr: Addr<A>) -> Self {
+        Self {
+            messages: addr.clone().recipient(),
+            broadcasts: addr.clone().recipient(),
+            stop: addr.recipient(),
+        }
+    }
+}
+
+/// The lobby answers `None` when the player waits for the teacher's approval,
+/// the `JoinResponse` is then sent to the player when the teacher decides
 #[derive(Debug, Message)]
-#[rtype(result = "common::messages::network::JoinResponse")]
+#[rtype(result = "Option<common::messages::network::JoinResponse>")]
 pub struct JoinRequest {
     pub player_data: PlayerData,
-    pub addr: Addr<Websocket>,
+    pub addr: PlayerSocket,
+    /// capabilities the client announced in its `TryJoinRequest`
+    pub capabilities: Vec<Capability>,
+    pub student_id: Option<String>,
+    /// secret the client announced in its `TryJoinRequest`
+    pub resume_token: Option<String>,
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "common::messages::network::TryJoinResponse")]
+pub struct TryJoinRequest {
+    pub request: network::TryJoinRequest,
+    /// address the client connected from, the wrong join codes are counted for it,
+    /// `None` for the players replayed from a journal
+    pub ip: Option<IpAddr>,
 }
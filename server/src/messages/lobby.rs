@@ -1,7 +1,10 @@
-use actix::{prelude::Message, Addr};
+use std::path::PathBuf;
+
+use actix::prelude::Message;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::Teacher;
+use crate::{attach::TeacherHandle, journal::JournalEntry, messages::attach::Attached};
 
 /// The teacher sends this to the lobby to set the locked state
 #[derive(Message, Debug)]
@@ -10,34 +13,59 @@ pub struct SetLockMessage {
     pub locked: bool,
 }
 
-/// The teacher sends this to the lobby to register itself
+/// The teacher sends this to the lobby to register itself, the lobby answers with the game,
+/// or refuses when the game already has a teacher or the teacher does not know its key
 #[derive(Message, Debug)]
-#[rtype(result = "()")]
+#[rtype(result = "Result<Attached, String>")]
 pub struct RegisterTeacher {
-    pub teacher: Addr<Teacher>,
+    pub teacher: TeacherHandle,
+    /// secret of the game, needed when the game had a teacher before
+    pub key: Option<String>,
 }
 
-#[derive(Message, Debug)]
+#[derive(Message, Debug, Serialize, Deserialize)]
 #[rtype(result = "anyhow::Result<()>")]
 pub struct StartQuestion;
 
-#[derive(Message, Debug)]
+#[derive(Message, Debug, Serialize, Deserialize)]
 #[rtype(result = "()")]
 pub struct HardStop;
 
-#[derive(Message, Debug)]
+#[derive(Message, Debug, Serialize, Deserialize)]
 #[rtype(result = "anyhow::Result<()>")]
 pub struct EndQuestion {
     pub index: usize,
 }
 
-#[derive(Debug, Clone, Message)]
+#[derive(Debug, Clone, Message, Serialize, Deserialize)]
 #[rtype(result = "anyhow::Result<()>")]
 pub struct KickPlayer {
     pub player_uuid: Uuid,
     pub reason: Option<String>,
 }
 
-#[derive(Debug, Clone, Message)]
+/// The teacher sends this to the lobby to let the players waiting for approval in, or not
+#[derive(Debug, Clone, Message, Serialize, Deserialize)]
+#[rtype(result = "()")]
+pub struct DecideJoins {
+    pub players: Vec<Uuid>,
+    pub approved: bool,
+}
+
+#[derive(Debug, Clone, Message, Serialize, Deserialize)]
 #[rtype(result = "anyhow::Result<()>")]
 pub struct SwitchToLeaderboard;
+
+/// The teacher sends this to the lobby to export the results so far
+#[derive(Debug, Clone, Message, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<Vec<PathBuf>>")]
+pub struct ExportResults;
+
+/// Starts feeding the journal to the lobby, sent when the teacher is registered
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+pub struct StartReplay {
+    pub entries: Vec<JournalEntry>,
+    /// 2.0 replays the game twice as fast
+    pub speed: f64,
+}
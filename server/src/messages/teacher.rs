@@ -1,5 +1,81 @@
+use std::path::PathBuf;
+
 use actix::prelude::Message;
+use common::messages::network::PlayerData;
+use serde::{Deserialize, Serialize};
+
+use crate::lobby::{QuestionAnalysis, RosterStudent};
 
 #[derive(Message, Debug)]
 #[rtype(result = "()")]
 pub struct HardStop;
+
+/// The lobby sends this to the teacher after it tried to export the results
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ResultsExported {
+    /// paths of the written files, or a description of the error
+    pub outcome: Result<Vec<PathBuf>, String>,
+}
+
+/// The lobby sends this to the teacher when the game ends
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowAnalysis {
+    pub questions: Vec<QuestionAnalysis>,
+}
+
+/// The lobby sends this to the teacher when the connections are encrypted,
+/// so the fingerprint can be shown to the students
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowFingerprint {
+    pub fingerprint: String,
+}
+
+/// The lobby sends this to the teacher when it registers, so the PIN of the game
+/// can be shown to the students
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowPin {
+    pub pin: String,
+}
+
+/// The lobby sends this to the teacher when it registers and the game is protected
+/// by a join code, so the code can be shown to the students
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowJoinCode {
+    pub join_code: String,
+}
+
+/// The lobby sends this to the teacher whenever the players waiting for approval change
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowApprovalQueue {
+    pub players: Vec<PlayerData>,
+}
+
+/// The lobby sends this to the teacher whenever the players change and there is a class roster
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowMissingStudents {
+    /// students from the roster who are not in the game
+    pub missing: Vec<RosterStudent>,
+    pub roster_size: usize,
+}
+
+/// The lobby sends this to the teacher when a client broke the connection limits
+/// or sent something it should not have
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ShowWarning {
+    pub message: String,
+}
+
+/// The lobby sends this to the teacher when the game is replayed from a journal
+#[derive(Message, Debug, Serialize, Deserialize)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct ReplayStarted {
+    pub speed: f64,
+}
@@ -1,4 +1,9 @@
 use actix::prelude::Message;
+use common::messages::{
+    encoding::{Encoding, SharedMessage},
+    ServerNetworkMessage,
+};
+use std::net::SocketAddr;
 use uuid::Uuid;
 
 #[derive(Message)]
@@ -17,3 +22,57 @@ pub struct HardStop;
 pub struct DisconnectFromLobby {
     pub player_id: Uuid,
 }
+
+/// The reader of the socket sends this to the websocket whenever anything comes from the client
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct ClientActive;
+
+/// The `Websocket` sends this to the lobby when the client stops or starts responding to the heartbeat
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct SetPlayerAway {
+    pub player_id: Uuid,
+    pub away: bool,
+}
+
+/// Sent to the websocket when the lobby refused the player, so the client
+/// may send another `TryJoinRequest`, e.g. with the right join code
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct JoinRefused;
+
+/// Sent to the websocket when the client and the server agreed on the encoding
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct SetEncoding {
+    pub encoding: Encoding,
+}
+
+/// The lobby sends this to the websockets when the same message goes to many players,
+/// so it is serialized only once, not once for every player
+#[derive(Message, Debug, Clone)]
+#[rtype(result = "anyhow::Result<()>")]
+pub struct Broadcast(pub SharedMessage<ServerNetworkMessage>);
+
+/// Sent to the websocket when the client broke a connection limit or sent something invalid,
+/// the client is disconnected and the teacher is warned
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct ClientMisbehaved {
+    /// what the teacher is told
+    pub reason: String,
+    /// what the client is told, the default goodbye if `None`
+    pub goodbye: Option<String>,
+}
+
+/// Sent to the lobby about a client which was disconnected or refused for misbehaving,
+/// so the teacher can see it
+#[derive(Message, Debug)]
+#[rtype(result = "()")]
+pub struct ConnectionWarning {
+    pub who: SocketAddr,
+    /// `None` if the client did not try to join yet
+    pub player_id: Option<Uuid>,
+    pub reason: String,
+}
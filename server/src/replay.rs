@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use actix::{Actor, Addr, Context, Handler};
+use anyhow::bail;
+use common::{
+    messages::{protocol::Capability, ClientNetworkMessage, ServerNetworkMessage},
+    terminal::highlight::Theme,
+};
+use log::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    journal::{JournalEntry, JournalEvent},
+    messages::{
+        client::{JoinRequest, TryJoinRequest},
+        lobby::{
+            DecideJoins, EndQuestion, KickPlayer, SetLockMessage, StartQuestion, StartReplay,
+            SwitchToLeaderboard,
+        },
+        websocket::{Broadcast, DisconnectFromLobby, GracefulStop},
+    },
+    teacher::run_teacher,
+    Lobby,
+};
+
+/// Stands in for the websocket of a replayed player, the messages for the player are only logged
+pub struct ReplayPlayer {
+    uuid: Uuid,
+}
+
+impl Actor for ReplayPlayer {
+    type Context = Context<Self>;
+}
+
+impl Handler<ServerNetworkMessage> for ReplayPlayer {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ServerNetworkMessage, _: &mut Context<Self>) -> Self::Result {
+        debug!("Replayed player {} received {msg:?}", self.uuid);
+        Ok(())
+    }
+}
+
+impl Handler<Broadcast> for ReplayPlayer {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: Broadcast, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Replayed player {} received {:?}",
+            self.uuid,
+            msg.0.message()
+        );
+        Ok(())
+    }
+}
+
+impl Handler<GracefulStop> for ReplayPlayer {
+    type Result = ();
+
+    fn handle(&mut self, msg: GracefulStop, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Replayed player {} was stopped: {:?}",
+            self.uuid, msg.reason
+        );
+    }
+}
+
+/// Feeds the entries of the journal to the lobby, keeping the gaps between them
+/// (divided by `speed`), the messages sent by the server are not replayed,
+/// the lobby sends them again
+pub async fn feed(lobby: Addr<Lobby>, entries: Vec<JournalEntry>, speed: f64) {
+    let mut players: HashMap<Uuid, Addr<ReplayPlayer>> = HashMap::new();
+    let mut previous_at = entries.first().map(|entry| entry.at);
+
+    for entry in entries {
+        if let Some(previous_at) = previous_at {
+            let gap = (entry.at - previous_at).to_std().unwrap_or_default();
+            tokio::time::sleep(gap.div_f64(speed)).await;
+        }
+        previous_at = Some(entry.at);
+
+        // the lobby may refuse the message the same way it did during the game
+        let result = match entry.event {
+            JournalEvent::GameStarted { .. } | JournalEvent::ToClient { .. } => continue,
+            JournalEvent::FromClient { message, .. } => match message {
+                ClientNetworkMessage::TryJoinRequest(request) => lobby
+                    .send(TryJoinRequest { request, ip: None })
+                    .await
+                    .map(|_| Ok(())),
+                ClientNetworkMessage::JoinRequest(msg) => {
+                    let uuid = msg.player_data.uuid;
+                    let player = players
+                        .entry(uuid)
+                        .or_insert_with(|| ReplayPlayer { uuid }.start());
+
+                    lobby
+                        .send(JoinRequest {
+                            player_data: msg.player_data,
+                            addr: player.clone().into(),
+                            capabilities: Capability::supported(),
+                            student_id: msg.student_id,
+                            // a replay never continues a resumed game
+                            resume_token: None,
+                        })
+                        .await
+                        .map(|_| Ok(()))
+                }
+                ClientNetworkMessage::AnswerSelected(msg) => lobby.send(msg).await,
+            },
+            JournalEvent::StartQuestion => lobby.send(StartQuestion).await,
+            JournalEvent::EndQuestion { index } => lobby.send(EndQuestion { index }).await,
+            JournalEvent::SwitchToLeaderboard => lobby.send(SwitchToLeaderboard).await,
+            JournalEvent::KickPlayer {
+                player_uuid,
+                reason,
+            } => {
+                lobby
+                    .send(KickPlayer {
+                        player_uuid,
+                        reason,
+                    })
+                    .await
+            }
+            JournalEvent::SetLock { locked } => lobby.send(SetLockMessage { locked }).await.map(Ok),
+            JournalEvent::DecideJoins { players, approved } => {
+                lobby.send(DecideJoins { players, approved }).await.map(Ok)
+            }
+            JournalEvent::Disconnect { player_id } => {
+                lobby.send(DisconnectFromLobby { player_id }).await.map(Ok)
+            }
+        };
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => debug!("Replayed message was refused by the lobby: {err:#}"),
+            Err(err) => warn!("Cannot deliver the replayed message: {err}"),
+        }
+    }
+
+    info!("Replay finished");
+}
+
+fn run_lobby(tx: &Sender<Addr<Lobby>>, lobby: Lobby) -> anyhow::Result<()> {
+    let system = actix::System::new();
+
+    system.block_on(async {
+        // no connections are accepted, the players come from the journal
+        let _ = tx.send(lobby.start());
+    });
+
+    system.run()?;
+
+    Ok(())
+}
+
+/// Replays the journal in the teacher's terminal, `speed` 2.0 replays the game twice as fast
+/// # Errors
+/// - if the journal cannot be read
+/// - if the journal does not start with the questions of the game
+pub fn replay(journal: &Path, speed: f64, syntax_theme: Theme) -> anyhow::Result<()> {
+    let entries = JournalEntry::read_all(journal)?;
+
+    let Some(JournalEvent::GameStarted { questions }) = entries.first().map(|e| e.event.clone())
+    else {
+        bail!("The journal does not start with the questions of the game");
+    };
+
+    let quiz_name = questions.quiz_name.clone();
+    // the players joined when the teacher let them in, not when they asked for it
+    let approve_joins = entries
+        .iter()
+        .any(|entry| matches!(entry.event, JournalEvent::DecideJoins { .. }));
+    let lobby = Lobby::new(questions).with_approve_joins(approve_joins);
+
+    let (tx_server, rx_server) = mpsc::channel();
+    let (tx_teacher, rx_teacher) = mpsc::channel();
+
+    let server_thread = thread::spawn(move || {
+        if let Err(err) = run_lobby(&tx_server, lobby) {
+            error!("Failed to run the replayed lobby: {err:#}");
+        }
+    });
+
+    let server = rx_server.recv()?;
+    let teacher_server = server.clone();
+    let teacher_thread = thread::spawn(move || {
+        if let Err(err) = run_teacher(teacher_server, &tx_teacher, &quiz_name, syntax_theme) {
+            error!("Failed to run teacher: {err:#}");
+        }
+    });
+
+    // the teacher registers itself in the lobby before it sends us its address
+    let _teacher = rx_teacher.recv()?;
+    server.do_send(StartReplay { entries, speed });
+
+    if teacher_thread.join().is_err() {
+        bail!("Teacher thread panicked");
+    }
+
+    if server_thread.join().is_err() {
+        bail!("Server thread panicked");
+    }
+
+    Ok(())
+}
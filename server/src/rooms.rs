@@ -0,0 +1,98 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use actix::Addr;
+use common::constants::{PIN_REQUIRED_MSG, UNKNOWN_PIN_MSG};
+use rand::{distributions::Alphanumeric, Rng};
+
+use crate::Lobby;
+
+/// Number of the digits of a generated game PIN
+pub const PIN_LENGTH: usize = 6;
+
+/// Random game PIN, short enough for the students to type it
+#[must_use]
+pub fn generate_pin() -> String {
+    let mut rng = rand::thread_rng();
+    (0..PIN_LENGTH)
+        .map(|_| char::from(b'0' + rng.gen_range(0..10)))
+        .collect()
+}
+
+/// Number of the characters of a generated teacher key
+pub const TEACHER_KEY_LENGTH: usize = 16;
+
+/// Random secret of a game, the teacher who lost the connection needs it to attach again
+#[must_use]
+pub fn generate_teacher_key() -> String {
+    rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(TEACHER_KEY_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Lobbies hosted by the server, each found by the PIN of its game
+#[derive(Debug, Clone, Default)]
+pub struct Rooms(Arc<RwLock<HashMap<String, Addr<Lobby>>>>);
+
+impl Rooms {
+    /// Adds the lobby of the game with the PIN
+    /// # Errors
+    /// - if there already is a game with the PIN
+    pub fn insert(&self, pin: String, lobby: Addr<Lobby>) -> anyhow::Result<()> {
+        let mut rooms = self.0.write().unwrap_or_else(PoisonError::into_inner);
+
+        if rooms.contains_key(&pin) {
+            anyhow::bail!("There already is a game with the PIN {pin}");
+        }
+
+        rooms.insert(pin, lobby);
+        Ok(())
+    }
+
+    /// Removes the lobby of the game with the PIN, when the game ends
+    pub fn remove(&self, pin: &str) {
+        let mut rooms = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        rooms.remove(pin);
+    }
+
+    #[must_use]
+    pub fn contains(&self, pin: &str) -> bool {
+        let rooms = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        rooms.contains_key(pin)
+    }
+
+    /// Lobby of the game with the PIN, the PIN may be left out when there is only one game,
+    /// so the clients which do not know about the PINs can join
+    /// # Errors
+    /// - with the reason for the client, if there is no such game
+    pub fn find(&self, pin: Option<&str>) -> Result<Addr<Lobby>, &'static str> {
+        let rooms = self.0.read().unwrap_or_else(PoisonError::into_inner);
+
+        match pin {
+            Some(pin) => rooms.get(pin.trim()).cloned().ok_or(UNKNOWN_PIN_MSG),
+            None if rooms.len() == 1 => rooms.values().next().cloned().ok_or(PIN_REQUIRED_MSG),
+            None => Err(PIN_REQUIRED_MSG),
+        }
+    }
+
+    /// Lobbies of all the games
+    #[must_use]
+    pub fn lobbies(&self) -> Vec<Addr<Lobby>> {
+        let rooms = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        rooms.values().cloned().collect()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
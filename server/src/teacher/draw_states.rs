@@ -1,17 +1,154 @@
-use common::terminal::render;
-use ratatui::Frame;
+use common::{
+    messages::network::PlayerData,
+    terminal::{render, ui_theme::palette},
+};
+use ratatui::{
+    prelude::*,
+    widgets::{
+        block::{Position, Title},
+        BorderType, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+    },
+};
+
+use crate::lobby::{QuestionAnalysis, RosterStudent};
 
 pub fn render_teacher_help(frame: &mut Frame) {
     let help_text = [
         ("ENTER", "Move to the next state"),
         ("CTRL C", "Exit the game"),
         ("x", "Kick a player"),
+        ("e", "Export the results so far"),
         ("h", "Show this help"),
         ("↑↓ | ws", "Move up and down"),
+        ("PgUp PgDn", "Scroll the code"),
+        ("r", "Review the questions after the game"),
+        ("←→ | ad", "Previous and next question in the review"),
+        ("y | n", "Let the selected waiting player in or not"),
+        ("a | r", "Let all the waiting players in or not"),
     ];
     render::help(frame, &help_text);
 }
 
+/// Shows the fingerprint on the bottom border, so the students can check they connect
+/// to this server
+pub fn render_fingerprint(frame: &mut Frame, fingerprint: &str) {
+    let area = frame.size();
+    let line = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+
+    let paragraph = Paragraph::new(format!(" TLS fingerprint (SHA-256): {fingerprint} "))
+        .alignment(Alignment::Center);
+    frame.render_widget(paragraph, line);
+}
+
+/// Shows the PIN of the game on the bottom border
+pub fn render_pin(frame: &mut Frame, pin: &str) {
+    let area = frame.size();
+    let line = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+
+    frame.render_widget(Paragraph::new(format!(" PIN {pin} ")), line);
+}
+
+/// Shows the players waiting for approval over the lower part of the players list
+pub fn render_approval_queue(frame: &mut Frame, players: &[PlayerData], state: &mut ListState) {
+    let area = frame.size();
+    let height = u16::try_from(players.len() + 2)
+        .unwrap_or(u16::MAX)
+        .min(area.height / 2);
+    let width = area.width * 3 / 5;
+    let area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.bottom().saturating_sub(height + 2),
+        width,
+        height,
+    );
+
+    let block = render::get_bordered_block()
+        .title(
+            Title::from(format!(" Waiting for approval: {} ", players.len()))
+                .alignment(Alignment::Center),
+        )
+        .title(
+            Title::from(" y let in · n refuse · a let all in · r refuse all ")
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        )
+        .border_type(BorderType::Thick)
+        .style(palette().popup);
+
+    let items: Vec<_> = players
+        .iter()
+        .map(|player| ListItem::new(player.display_name()).fg(player.display_color()))
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(render::get_highlighted_style())
+        .highlight_symbol(">> ");
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, state);
+}
+
+/// Shows the students from the roster who have not joined yet in the lower right corner,
+/// next to the players list
+pub fn render_missing_students(frame: &mut Frame, missing: &[RosterStudent], roster_size: usize) {
+    let area = frame.size();
+
+    let title = format!(" Missing {}/{roster_size} ", missing.len());
+    let lines: Vec<String> = missing
+        .iter()
+        .map(|student| format!("{} ({})", student.name, student.id))
+        .collect();
+
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .chain([title.chars().count()])
+        .max()
+        .unwrap_or(0);
+    let width = u16::try_from(width + 2)
+        .unwrap_or(u16::MAX)
+        .min(area.width / 4);
+    let height = u16::try_from(lines.len() + 2)
+        .unwrap_or(u16::MAX)
+        .min(area.height / 2);
+    let area = Rect::new(
+        area.right().saturating_sub(width + 2),
+        area.bottom().saturating_sub(height + 2),
+        width,
+        height,
+    );
+
+    let block = render::get_bordered_block()
+        .title(Title::from(title).alignment(Alignment::Center))
+        .style(palette().popup);
+    let items: Vec<_> = lines.into_iter().map(ListItem::new).collect();
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(List::new(items).block(block), area);
+}
+
+/// Shows the join code big, so the students can read it from the back of the classroom
+pub fn render_join_code(frame: &mut Frame, join_code: &str) {
+    render::banner(frame, " Join code ", join_code);
+}
+
+/// Shows the latest warning on the top border, so it does not get in the way of the game
+pub fn render_warning(frame: &mut Frame, warning: &str, count: usize) {
+    let area = frame.size();
+    let line = Rect::new(area.x, area.y, area.width, 1);
+
+    let text = if count > 1 {
+        format!(" {count} warnings, latest: {warning} ")
+    } else {
+        format!(" Warning: {warning} ")
+    };
+
+    let paragraph = Paragraph::new(text)
+        .style(palette().error)
+        .alignment(Alignment::Right);
+    frame.render_widget(paragraph, line);
+}
+
 pub fn render_kick_popup(frame: &mut Frame) {
     let message = "Are you sure you want to kick this player?\n They will not be able to rejoin";
     render::confirm(frame, message);
@@ -21,3 +158,121 @@ pub fn render_skip_question_popup(frame: &mut Frame) {
     let message = "Are you sure you want to skip this question?\n Players who haven't answered will get 0 points";
     render::confirm(frame, message);
 }
+
+pub fn render_notice(frame: &mut Frame, message: &str) {
+    render::notice(frame, " Notice ", message);
+}
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.0} %", v * 100.0))
+}
+
+pub fn render_analysis(
+    frame: &mut Frame,
+    analysis: &[QuestionAnalysis],
+    table_state: &mut TableState,
+    quiz_name: &str,
+) {
+    let layout = render::list_layout(
+        frame,
+        vec![
+            Constraint::Length(1),
+            Constraint::Percentage(50),
+            Constraint::Min(4),
+        ],
+        "Item analysis, choose a question to see its choices (r to review it, Enter to finish):",
+        " Analysis ",
+        quiz_name,
+    );
+
+    let rows: Vec<_> = analysis
+        .iter()
+        .map(|question| {
+            Row::new(vec![
+                question.question.to_string(),
+                question.question_text.clone(),
+                percent(question.difficulty),
+                question
+                    .discrimination
+                    .map_or_else(|| "-".to_string(), |d| format!("{d:+.2}")),
+                question
+                    .mean_response_time
+                    .map_or_else(|| "-".to_string(), |t| format!("{t:.1} s")),
+                format!("{}/{}", question.answered, question.players),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Min(20),
+        Constraint::Length(8),
+        Constraint::Length(15),
+        Constraint::Length(9),
+        Constraint::Length(9),
+    ];
+    let header = Row::new(vec![
+        "#",
+        "Question",
+        "Correct",
+        "Discrimination",
+        "Avg time",
+        "Answered",
+    ])
+    .underlined();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(render::get_bordered_block())
+        .highlight_style(render::get_highlighted_style())
+        .highlight_symbol(">> ");
+
+    frame.render_stateful_widget(table, layout[1], table_state);
+
+    let Some(question) = table_state.selected().and_then(|i| analysis.get(i)) else {
+        return;
+    };
+
+    // wrong choices picked mostly by the upper group are worth a second look
+    let rows: Vec<_> = question
+        .choices
+        .iter()
+        .map(|choice| {
+            let row = Row::new(vec![
+                choice.text.clone(),
+                if choice.is_correct { "yes" } else { "no" }.to_string(),
+                choice.picked_total.to_string(),
+                choice.picked_by_upper.to_string(),
+                choice.picked_by_lower.to_string(),
+            ]);
+
+            if choice.is_correct {
+                row.style(palette().correct_item)
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Min(20),
+        Constraint::Length(8),
+        Constraint::Length(7),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+    let header = Row::new(vec![
+        "Choice",
+        "Correct",
+        "Picked",
+        "Upper 27%",
+        "Lower 27%",
+    ])
+    .underlined();
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(render::get_bordered_block());
+
+    frame.render_widget(table, layout[2]);
+}
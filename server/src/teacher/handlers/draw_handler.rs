@@ -9,12 +9,17 @@ use common::{
 use ratatui::prelude::*;
 
 use crate::teacher::{
-    draw_states::{render_kick_popup, render_skip_question_popup, render_teacher_help},
+    draw_states::{
+        render_analysis, render_approval_queue, render_fingerprint, render_join_code,
+        render_kick_popup, render_missing_students, render_notice, render_pin,
+        render_skip_question_popup, render_teacher_help, render_warning,
+    },
     states::TeacherTerminalState,
     terminal::TeacherTerminal,
 };
 
 impl TerminalDraw for TeacherTerminal {
+    #[allow(clippy::too_many_lines)]
     fn redraw<B: Backend>(&mut self, term: &mut Terminal<B>) -> anyhow::Result<()> {
         term.draw(|frame| {
             if frame.size().height < MINIMAL_SCREEN_HEIGHT
@@ -42,6 +47,19 @@ impl TerminalDraw for TeacherTerminal {
                         None,
                         &self.quiz_name,
                     );
+                    if let Some(join_code) = &self.join_code {
+                        render_join_code(frame, join_code);
+                    }
+                    if let Some(missing) = &self.missing_students {
+                        render_missing_students(frame, missing, self.roster_size);
+                    }
+                    if !self.awaiting_approval.is_empty() {
+                        render_approval_queue(
+                            frame,
+                            &self.awaiting_approval,
+                            &mut state.approval_state,
+                        );
+                    }
                     if state.kick_popup_visible {
                         render_kick_popup(frame);
                     }
@@ -67,7 +85,9 @@ impl TerminalDraw for TeacherTerminal {
                             usize::try_from(state.duration_from_start.num_seconds())
                                 .unwrap_or(usize::MAX),
                             false,
-                            self.syntax_theme,
+                            &self.syntax_theme,
+                            &mut state.code_scroll,
+                            &mut state.code_cache,
                             &self.quiz_name,
                         );
 
@@ -90,7 +110,9 @@ impl TerminalDraw for TeacherTerminal {
                         render::question::answers(
                             frame,
                             &state.answers,
-                            self.syntax_theme,
+                            &self.syntax_theme,
+                            &mut state.code_scroll,
+                            &mut state.code_cache,
                             &self.quiz_name,
                         );
                     }
@@ -107,14 +129,77 @@ impl TerminalDraw for TeacherTerminal {
                         render_kick_popup(frame);
                     }
                 }
+                TeacherTerminalState::Analysis(state) => {
+                    render_analysis(
+                        frame,
+                        &state.analysis,
+                        &mut state.table_state,
+                        &self.quiz_name,
+                    );
+                }
+                TeacherTerminalState::Review(state) => {
+                    if frame.size().height < MINIMAL_QUESTION_HEIGHT
+                        || frame.size().width < MINIMAL_QUESTION_WIDTH
+                    {
+                        render::resize(
+                            frame,
+                            &self.quiz_name,
+                            MINIMAL_QUESTION_HEIGHT,
+                            MINIMAL_QUESTION_WIDTH,
+                        );
+                    } else {
+                        render::question::answers(
+                            frame,
+                            &self.ended_questions[state.index],
+                            &self.syntax_theme,
+                            &mut state.code_scroll,
+                            &mut state.code_cache,
+                            &self.quiz_name,
+                        );
+                    }
+                }
                 TeacherTerminalState::EndGame => {
-                    render::end_game(frame, &self.quiz_name);
+                    if self.ended_questions.is_empty() {
+                        render::end_game(frame, &self.quiz_name);
+                    } else {
+                        render::end_game_with_hint(
+                            frame,
+                            "Press r to review the questions, CTRL C to close",
+                            &self.quiz_name,
+                        );
+                    }
                 }
                 TeacherTerminalState::Error(state) => {
                     render::error(frame, &state.message, &self.quiz_name);
                 }
             }
 
+            if let (
+                Some(fingerprint),
+                TeacherTerminalState::StartGame | TeacherTerminalState::WaitingForGame(_),
+            ) = (&self.tls_fingerprint, &self.state)
+            {
+                render_fingerprint(frame, fingerprint);
+            }
+
+            if let Some(pin) = &self.pin {
+                let joining = matches!(
+                    self.state,
+                    TeacherTerminalState::StartGame | TeacherTerminalState::WaitingForGame(_)
+                );
+                if joining {
+                    render_pin(frame, pin);
+                }
+            }
+
+            if let Some(warning) = &self.last_warning {
+                render_warning(frame, warning, self.warnings_count);
+            }
+
+            if let Some(notice) = &self.notice {
+                render_notice(frame, notice);
+            }
+
             if self.help_visible {
                 render_teacher_help(frame);
             }
@@ -5,7 +5,8 @@ use crate::{messages::lobby, teacher::terminal::TeacherTerminal};
 
 impl TerminalStop for TeacherTerminal {
     fn stop(&mut self) -> anyhow::Result<()> {
-        // lobby is on a different thread
+        // lobby is on a different thread (or in the server the teacher attached to),
+        // it stops only its own game, this stops only the teacher
         self.lobby.do_send(lobby::HardStop);
         System::current().stop();
 
@@ -1,22 +1,28 @@
-use actix::Addr;
 use crossterm::event::KeyCode;
 use uuid::Uuid;
 
 use log::debug;
-use ratatui::widgets::ListState;
+use ratatui::widgets::{ListState, TableState};
 
 use common::{
     constants::PLAYER_KICKED_MESSAGE,
-    terminal::{actor::TerminalHandleInput, input_utils::move_in_list},
+    messages::network::PlayerData,
+    questions::CodeBlock,
+    terminal::{
+        actor::TerminalHandleInput,
+        input_utils::{move_in_list, scroll_code},
+    },
 };
 
 use crate::{
-    messages::lobby::{EndQuestion, KickPlayer, StartQuestion, SwitchToLeaderboard},
+    attach::LobbyHandle,
+    messages::lobby::{
+        DecideJoins, EndQuestion, ExportResults, KickPlayer, StartQuestion, SwitchToLeaderboard,
+    },
     teacher::{
-        states::{TeacherTerminalState, WaitingForGameState},
+        states::{AnalysisState, ReviewState, TeacherTerminalState, WaitingForGameState},
         terminal::TeacherTerminal,
     },
-    Lobby,
 };
 
 impl TerminalHandleInput for TeacherTerminal {
@@ -30,16 +36,33 @@ impl TerminalHandleInput for TeacherTerminal {
             return;
         }
 
+        if self.notice.is_some() {
+            self.notice = None;
+            return;
+        }
+
         if key_code == KeyCode::Char('h') {
             self.help_visible = true;
             return;
         }
 
+        if key_code == KeyCode::Char('e') {
+            // the lobby lets us know about the result with `ResultsExported`
+            self.lobby.do_send(ExportResults);
+            return;
+        }
+
+        // in a replay the journal controls the game, the teacher only watches
+        if self.replay && controls_game(&self.state, key_code) {
+            return;
+        }
+
         match &mut self.state {
             TeacherTerminalState::StartGame => {
                 if key_code == KeyCode::Enter {
                     self.state = TeacherTerminalState::WaitingForGame(WaitingForGameState {
                         list_state: ListState::default().with_selected(Some(0)),
+                        approval_state: ListState::default().with_selected(Some(0)),
                         kick_popup_visible: false,
                     });
                 }
@@ -61,12 +84,15 @@ impl TerminalHandleInput for TeacherTerminal {
                     return;
                 }
 
+                // the players waiting for approval come first
+                if handle_approval(&self.lobby, &self.awaiting_approval, state, key_code) {
+                    return;
+                }
+
                 match key_code {
                     KeyCode::Enter => self.lobby.do_send(StartQuestion),
-                    KeyCode::Char('x') => {
-                        if !self.players.is_empty() {
-                            state.kick_popup_visible = true;
-                        }
+                    KeyCode::Char('x') if !self.players.is_empty() => {
+                        state.kick_popup_visible = true;
                     }
                     _ => {}
                 };
@@ -86,11 +112,32 @@ impl TerminalHandleInput for TeacherTerminal {
                 if key_code == KeyCode::Enter {
                     state.skip_popup_visible = true;
                 }
+
+                scroll_code(
+                    &mut state.code_scroll,
+                    state
+                        .question
+                        .code_block
+                        .as_ref()
+                        .map_or(0, CodeBlock::lines_count),
+                    key_code,
+                );
             }
-            TeacherTerminalState::Answers(_) => {
+            TeacherTerminalState::Answers(state) => {
                 if key_code == KeyCode::Enter {
                     self.lobby.do_send(SwitchToLeaderboard);
                 }
+
+                scroll_code(
+                    &mut state.code_scroll,
+                    state
+                        .answers
+                        .question
+                        .code_block
+                        .as_ref()
+                        .map_or(0, CodeBlock::lines_count),
+                    key_code,
+                );
             }
             TeacherTerminalState::Results(state) => {
                 let mut selected = state.table_state.selected().unwrap_or(0);
@@ -117,10 +164,18 @@ impl TerminalHandleInput for TeacherTerminal {
                 }
 
                 if key_code == KeyCode::Enter {
-                    if state.results.was_final_round {
-                        self.state = TeacherTerminalState::EndGame;
+                    if !state.results.was_final_round {
+                        self.lobby.do_send(StartQuestion);
+                        return;
                     }
-                    self.lobby.do_send(StartQuestion);
+
+                    self.state = match self.analysis.take() {
+                        Some(analysis) => TeacherTerminalState::Analysis(AnalysisState {
+                            analysis,
+                            table_state: TableState::default().with_selected(Some(0)),
+                        }),
+                        None => TeacherTerminalState::EndGame,
+                    };
                     return;
                 }
 
@@ -131,8 +186,65 @@ impl TerminalHandleInput for TeacherTerminal {
                 move_in_list(&mut selected, self.players.len(), key_code);
                 state.table_state.select(Some(selected));
             }
+            TeacherTerminalState::Analysis(state) => {
+                if key_code == KeyCode::Enter {
+                    self.state = TeacherTerminalState::EndGame;
+                    return;
+                }
+
+                let mut selected = state.table_state.selected().unwrap_or(0);
+
+                if key_code == KeyCode::Char('r') {
+                    // open the review at the question selected in the analysis
+                    let index = state.analysis.get(selected).and_then(|question| {
+                        self.ended_questions
+                            .iter()
+                            .position(|ended| ended.question_index + 1 == question.question)
+                    });
+                    if let Some(index) = index {
+                        self.state = TeacherTerminalState::Review(ReviewState::new(index));
+                    }
+                    return;
+                }
+
+                move_in_list(&mut selected, state.analysis.len(), key_code);
+                state.table_state.select(Some(selected));
+            }
+            TeacherTerminalState::Review(state) => {
+                let previous_index = state.index;
+                match key_code {
+                    KeyCode::Enter => {
+                        self.state = TeacherTerminalState::EndGame;
+                        return;
+                    }
+                    KeyCode::Left | KeyCode::Char('a') => {
+                        state.index = state.index.saturating_sub(1);
+                    }
+                    KeyCode::Right | KeyCode::Char('d') => {
+                        state.index = (state.index + 1).min(self.ended_questions.len() - 1);
+                    }
+                    _ => {}
+                }
+
+                if state.index != previous_index {
+                    *state = ReviewState::new(state.index);
+                    return;
+                }
+
+                scroll_code(
+                    &mut state.code_scroll,
+                    self.ended_questions[state.index]
+                        .question
+                        .code_block
+                        .as_ref()
+                        .map_or(0, CodeBlock::lines_count),
+                    key_code,
+                );
+            }
             TeacherTerminalState::EndGame => {
-                debug!("EndGame - doing nothing: {:?}", key_code);
+                if key_code == KeyCode::Char('r') && !self.ended_questions.is_empty() {
+                    self.state = TeacherTerminalState::Review(ReviewState::new(0));
+                }
             }
             TeacherTerminalState::Error(_) => {
                 debug!("Error - doing nothing: {:?}", key_code);
@@ -141,10 +253,62 @@ impl TerminalHandleInput for TeacherTerminal {
     }
 }
 
-fn handle_kick_player(lobby_addr: &Addr<Lobby>, key_code: KeyCode, player_uuid: Uuid) -> bool {
+/// Whether the key would start, end or skip a question, or kick a player
+fn controls_game(state: &TeacherTerminalState, key_code: KeyCode) -> bool {
+    match state {
+        TeacherTerminalState::WaitingForGame(_) => matches!(
+            key_code,
+            KeyCode::Enter | KeyCode::Char('x' | 'y' | 'n' | 'a' | 'r')
+        ),
+        TeacherTerminalState::Question(_) | TeacherTerminalState::Answers(_) => {
+            matches!(key_code, KeyCode::Enter | KeyCode::Char('x'))
+        }
+        TeacherTerminalState::Results(state) => {
+            key_code == KeyCode::Char('x')
+                || (key_code == KeyCode::Enter && !state.results.was_final_round)
+        }
+        _ => false,
+    }
+}
+
+/// Lets the players waiting for approval in or not, returns whether the key was used for it
+fn handle_approval(
+    lobby: &LobbyHandle,
+    awaiting: &[PlayerData],
+    state: &mut WaitingForGameState,
+    key_code: KeyCode,
+) -> bool {
+    if awaiting.is_empty() {
+        return false;
+    }
+
+    let mut selected = state
+        .approval_state
+        .selected()
+        .unwrap_or(0)
+        .min(awaiting.len() - 1);
+
+    let (players, approved) = match key_code {
+        KeyCode::Char('y') => (vec![awaiting[selected].uuid], true),
+        KeyCode::Char('n') => (vec![awaiting[selected].uuid], false),
+        KeyCode::Char('a') => (awaiting.iter().map(|player| player.uuid).collect(), true),
+        KeyCode::Char('r') => (awaiting.iter().map(|player| player.uuid).collect(), false),
+        _ => {
+            let moved = move_in_list(&mut selected, awaiting.len(), key_code);
+            state.approval_state.select(Some(selected));
+            return moved;
+        }
+    };
+
+    // the lobby sends the new queue back
+    lobby.do_send(DecideJoins { players, approved });
+    true
+}
+
+fn handle_kick_player(lobby: &LobbyHandle, key_code: KeyCode, player_uuid: Uuid) -> bool {
     match key_code {
         KeyCode::Char('y') => {
-            lobby_addr.do_send(KickPlayer {
+            lobby.do_send(KickPlayer {
                 player_uuid,
                 reason: Some(PLAYER_KICKED_MESSAGE.to_string()),
             });
@@ -5,5 +5,14 @@ pub mod next_question_handler;
 pub mod players_update_handler;
 pub mod question_ended_handler;
 pub mod question_update_handler;
+pub mod replay_started_handler;
+pub mod results_exported_handler;
+pub mod show_analysis_handler;
+pub mod show_approval_queue_handler;
+pub mod show_fingerprint_handler;
+pub mod show_join_code_handler;
 pub mod show_leaderboard_handler;
+pub mod show_missing_students_handler;
+pub mod show_pin_handler;
+pub mod show_warning_handler;
 pub mod tick_handler;
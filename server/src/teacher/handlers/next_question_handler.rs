@@ -1,4 +1,7 @@
-use common::{messages::network::NextQuestion, terminal::actor::TerminalHandleNextQuestion};
+use common::{
+    messages::network::NextQuestion,
+    terminal::{actor::TerminalHandleNextQuestion, highlight::CodeCache},
+};
 
 use crate::teacher::{
     states::{QuestionState, TeacherTerminalState},
@@ -19,6 +22,8 @@ impl TerminalHandleNextQuestion for TeacherTerminal {
             start_time: chrono::Utc::now(),
             duration_from_start: chrono::Duration::zero(),
             skip_popup_visible: false,
+            code_scroll: 0,
+            code_cache: CodeCache::default(),
         });
 
         Ok(())
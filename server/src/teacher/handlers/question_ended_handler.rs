@@ -1,5 +1,8 @@
 use anyhow::bail;
-use common::{messages::network::QuestionEnded, terminal::actor::TerminalHandleQuestionEnded};
+use common::{
+    messages::network::QuestionEnded,
+    terminal::{actor::TerminalHandleQuestionEnded, highlight::CodeCache},
+};
 use log::debug;
 
 use crate::teacher::{
@@ -24,8 +27,12 @@ impl TerminalHandleQuestionEnded for TeacherTerminal {
             );
         }
 
+        self.ended_questions.push(question_ended.clone());
+
         self.state = TeacherTerminalState::Answers(AnswersState {
             answers: question_ended,
+            code_scroll: 0,
+            code_cache: CodeCache::default(),
         });
 
         Ok(())
@@ -0,0 +1,35 @@
+use actix::{Context, Handler};
+use log::debug;
+use ratatui::widgets::ListState;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{
+    messages::teacher::ReplayStarted,
+    teacher::states::{TeacherTerminalState, WaitingForGameState},
+    Teacher,
+};
+
+impl Handler<ReplayStarted> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ReplayStarted, _: &mut Context<Self>) -> Self::Result {
+        debug!("Teacher: replay started at speed {}", msg.speed);
+
+        self.inner.replay = true;
+        self.inner.notice = Some(format!(
+            "Replaying the game at speed {}x,\nthe game is controlled by the journal",
+            msg.speed
+        ));
+
+        if matches!(self.inner.state, TeacherTerminalState::StartGame) {
+            self.inner.state = TeacherTerminalState::WaitingForGame(WaitingForGameState {
+                list_state: ListState::default().with_selected(Some(0)),
+                approval_state: ListState::default().with_selected(Some(0)),
+                kick_popup_visible: false,
+            });
+        }
+
+        self.inner.redraw(&mut self.terminal)
+    }
+}
@@ -0,0 +1,25 @@
+use actix::{Context, Handler};
+use itertools::Itertools;
+use log::debug;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ResultsExported, Teacher};
+
+impl Handler<ResultsExported> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ResultsExported, _: &mut Context<Self>) -> Self::Result {
+        debug!("Teacher: results exported: {:?}", msg.outcome);
+
+        self.inner.notice = Some(match msg.outcome {
+            Ok(paths) => format!(
+                "Results were exported to:\n{}",
+                paths.iter().map(|path| path.display()).join("\n")
+            ),
+            Err(err) => format!("Failed to export the results:\n{err}"),
+        });
+
+        self.inner.redraw(&mut self.terminal)
+    }
+}
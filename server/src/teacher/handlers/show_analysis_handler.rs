@@ -0,0 +1,17 @@
+use actix::{Context, Handler};
+use log::debug;
+
+use crate::{messages::teacher::ShowAnalysis, Teacher};
+
+impl Handler<ShowAnalysis> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowAnalysis, _: &mut Context<Self>) -> Self::Result {
+        debug!("Teacher: received the item analysis");
+
+        // shown after the teacher leaves the final leaderboard
+        self.inner.analysis = Some(msg.questions);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use actix::{Context, Handler};
+use log::debug;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ShowApprovalQueue, teacher::states::TeacherTerminalState, Teacher};
+
+impl Handler<ShowApprovalQueue> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowApprovalQueue, _: &mut Context<Self>) -> Self::Result {
+        debug!("Teacher: {} players wait for approval", msg.players.len());
+
+        // the selection stays in the list when the players in it change
+        if let TeacherTerminalState::WaitingForGame(state) = &mut self.inner.state {
+            let selected = state.approval_state.selected().unwrap_or(0);
+            let last = msg.players.len().saturating_sub(1);
+            state.approval_state.select(Some(selected.min(last)));
+        }
+
+        self.inner.awaiting_approval = msg.players;
+        self.inner.redraw(&mut self.terminal)
+    }
+}
@@ -0,0 +1,20 @@
+use actix::{Context, Handler};
+use log::debug;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ShowFingerprint, Teacher};
+
+impl Handler<ShowFingerprint> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowFingerprint, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Teacher: server certificate fingerprint {}",
+            msg.fingerprint
+        );
+
+        self.inner.tls_fingerprint = Some(msg.fingerprint);
+        self.inner.redraw(&mut self.terminal)
+    }
+}
@@ -0,0 +1,17 @@
+use actix::{Context, Handler};
+use log::info;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ShowJoinCode, Teacher};
+
+impl Handler<ShowJoinCode> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowJoinCode, _: &mut Context<Self>) -> Self::Result {
+        info!("Teacher: the join code of the game is {}", msg.join_code);
+
+        self.inner.join_code = Some(msg.join_code);
+        self.inner.redraw(&mut self.terminal)
+    }
+}
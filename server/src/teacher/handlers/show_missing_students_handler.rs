@@ -0,0 +1,22 @@
+use actix::{Context, Handler};
+use log::debug;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ShowMissingStudents, Teacher};
+
+impl Handler<ShowMissingStudents> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowMissingStudents, _: &mut Context<Self>) -> Self::Result {
+        debug!(
+            "Teacher: {}/{} students from the roster are missing",
+            msg.missing.len(),
+            msg.roster_size
+        );
+
+        self.inner.missing_students = Some(msg.missing);
+        self.inner.roster_size = msg.roster_size;
+        self.inner.redraw(&mut self.terminal)
+    }
+}
@@ -0,0 +1,17 @@
+use actix::{Context, Handler};
+use log::info;
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ShowPin, Teacher};
+
+impl Handler<ShowPin> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowPin, _: &mut Context<Self>) -> Self::Result {
+        info!("Teacher: the PIN of the game is {}", msg.pin);
+
+        self.inner.pin = Some(msg.pin);
+        self.inner.redraw(&mut self.terminal)
+    }
+}
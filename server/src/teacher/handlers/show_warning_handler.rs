@@ -0,0 +1,15 @@
+use actix::{Context, Handler};
+
+use common::terminal::actor::TerminalDraw;
+
+use crate::{messages::teacher::ShowWarning, Teacher};
+
+impl Handler<ShowWarning> for Teacher {
+    type Result = anyhow::Result<()>;
+
+    fn handle(&mut self, msg: ShowWarning, _: &mut Context<Self>) -> Self::Result {
+        self.inner.warnings_count += 1;
+        self.inner.last_warning = Some(msg.message);
+        self.inner.redraw(&mut self.terminal)
+    }
+}
@@ -1,11 +1,24 @@
-use std::sync::mpsc::Sender;
+use std::{
+    io::BufReader,
+    net::{SocketAddr, TcpStream},
+    sync::{mpsc::Sender, Arc, Mutex},
+    thread,
+};
 
 use actix::{prelude::Actor, Addr};
+use anyhow::Context;
+use log::{error, info};
 
-use common::terminal::actor::TerminalActor;
-use common::terminal::highlight::Theme;
+use common::terminal::{actor::TerminalActor, highlight::Theme, messages::Stop};
 
-use crate::{messages::lobby::RegisterTeacher, Lobby};
+use crate::{
+    attach::{read_line, write_line, LobbyHandle, TeacherHandle},
+    messages::{
+        attach::{AttachRequest, AttachResponse, Attached, ToTeacher},
+        lobby::RegisterTeacher,
+    },
+    Lobby,
+};
 
 use super::terminal::TeacherTerminal;
 
@@ -13,38 +26,103 @@ pub type Teacher = TerminalActor<TeacherTerminal>;
 
 pub fn run_teacher(
     lobby: Addr<Lobby>,
-    tx: Sender<Addr<Teacher>>,
+    tx: &Sender<Addr<Teacher>>,
     quiz_name: &str,
     syntax_theme: Theme,
 ) -> anyhow::Result<()> {
     let system = actix::System::new();
 
-    system.block_on(init(lobby, tx, quiz_name, syntax_theme))?;
+    let teacher = system.block_on(init(lobby, quiz_name, syntax_theme))?;
+    tx.send(teacher)?;
 
     system.run()?;
 
     Ok(())
 }
 
+/// Runs the teacher of a game hosted by the server on the address, in this terminal,
+/// the server creates the game if the request has the questions
+/// # Errors
+/// - if the server cannot be reached
+/// - if the server refuses the teacher, e.g. when there is no game with the PIN
+/// - if the terminal cannot be set up
+pub fn run_attached_teacher(
+    addr: SocketAddr,
+    request: &AttachRequest,
+    syntax_theme: Theme,
+) -> anyhow::Result<()> {
+    let mut connection = TcpStream::connect(addr)
+        .with_context(|| format!("Failed to connect to the server on {addr}"))?;
+    write_line(&mut connection, request)?;
+
+    let mut reader = BufReader::new(connection.try_clone()?);
+    let response: AttachResponse =
+        read_line(&mut reader)?.context("The server closed the connection")?;
+    let Attached {
+        quiz_name,
+        pin,
+        key,
+    } = response.map_err(anyhow::Error::msg)?;
+    // the key is kept off the screen, the students may see it
+    info!("Attached to the game {pin} '{quiz_name}' on {addr}, its teacher key is {key}");
+
+    let system = actix::System::new();
+
+    let teacher = system.block_on(async {
+        let lobby = LobbyHandle::Remote(Arc::new(Mutex::new(connection)));
+        anyhow::Ok(
+            TerminalActor::new(TeacherTerminal::new(quiz_name, lobby, syntax_theme))?.start(),
+        )
+    })?;
+
+    // the messages of the lobby are read on their own thread, the reads block
+    let reader_thread = thread::spawn(move || {
+        loop {
+            match read_line::<ToTeacher>(&mut reader) {
+                Ok(Some(msg)) => msg.deliver(&teacher),
+                Ok(None) => break,
+                Err(err) => {
+                    error!("Failed to read a message from the server: {err:#}");
+                    break;
+                }
+            }
+        }
+
+        error!("Lost the connection to the server");
+        teacher.do_send(Stop);
+    });
+
+    system.run()?;
+
+    // the terminal is the teacher's own again, the game goes on without them
+    if reader_thread.is_finished() {
+        eprintln!(
+            "The game {pin} goes on, take it back with: server attach --attach-port {} --pin {pin} --key {key}",
+            addr.port()
+        );
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::unused_async)]
 async fn init(
     lobby: Addr<Lobby>,
-    tx: Sender<Addr<Teacher>>,
     quiz_name: &str,
     syntax_theme: Theme,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Addr<Teacher>> {
     let teacher = TerminalActor::new(TeacherTerminal::new(
         quiz_name.to_string(),
-        lobby.clone(),
+        lobby.clone().into(),
         syntax_theme,
     ))?
     .start();
 
-    tx.send(teacher.clone())?;
-
+    // register first, so the lobby knows the teacher before anybody gets the address
     lobby.do_send(RegisterTeacher {
-        teacher: teacher.clone(),
+        teacher: TeacherHandle::Local(teacher.clone()),
+        key: None,
     });
 
-    Ok(())
+    Ok(teacher)
 }
@@ -1,10 +1,17 @@
 use ratatui::widgets::{ListState, TableState};
 
-use common::messages::network::{NextQuestion, QuestionEnded, ShowLeaderboard};
+use crate::lobby::QuestionAnalysis;
+
+use common::{
+    messages::network::{NextQuestion, QuestionEnded, ShowLeaderboard},
+    terminal::highlight::CodeCache,
+};
 
 #[derive(Debug)]
 pub struct WaitingForGameState {
     pub(super) list_state: ListState,
+    /// selected player of the ones waiting for approval
+    pub(super) approval_state: ListState,
     pub(super) kick_popup_visible: bool,
 }
 
@@ -15,11 +22,15 @@ pub struct QuestionState {
     pub(super) start_time: chrono::DateTime<chrono::Utc>,
     pub(super) duration_from_start: chrono::Duration,
     pub(super) skip_popup_visible: bool,
+    pub(super) code_scroll: u16,
+    pub(super) code_cache: CodeCache,
 }
 
 #[derive(Debug)]
 pub struct AnswersState {
     pub(super) answers: QuestionEnded,
+    pub(super) code_scroll: u16,
+    pub(super) code_cache: CodeCache,
 }
 
 #[derive(Debug)]
@@ -29,6 +40,30 @@ pub struct ResultsState {
     pub(super) kick_popup_visible: bool,
 }
 
+#[derive(Debug)]
+pub struct AnalysisState {
+    pub(super) analysis: Vec<QuestionAnalysis>,
+    pub(super) table_state: TableState,
+}
+
+#[derive(Debug)]
+pub struct ReviewState {
+    /// position in `TeacherTerminal::ended_questions`
+    pub(super) index: usize,
+    pub(super) code_scroll: u16,
+    pub(super) code_cache: CodeCache,
+}
+
+impl ReviewState {
+    pub(super) fn new(index: usize) -> Self {
+        Self {
+            index,
+            code_scroll: 0,
+            code_cache: CodeCache::default(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ErrorState {
     pub(super) message: String,
@@ -41,6 +76,8 @@ pub enum TeacherTerminalState {
     Question(QuestionState),
     Answers(AnswersState),
     Results(ResultsState),
+    Analysis(AnalysisState),
+    Review(ReviewState),
     EndGame,
     Error(ErrorState),
 }
@@ -1,27 +1,66 @@
-use actix::prelude::*;
+use common::{
+    messages::network::{PlayerData, QuestionEnded},
+    terminal::highlight::Theme,
+};
 
-use common::{messages::network::PlayerData, terminal::highlight::Theme};
-
-use crate::{teacher::states::TeacherTerminalState, Lobby};
+use crate::{
+    attach::LobbyHandle,
+    lobby::{QuestionAnalysis, RosterStudent},
+    teacher::states::TeacherTerminalState,
+};
 
 #[allow(clippy::module_name_repetitions)]
 pub struct TeacherTerminal {
     pub quiz_name: String,
-    pub lobby: Addr<Lobby>,
+    pub lobby: LobbyHandle,
     pub players: Vec<PlayerData>,
+    /// players who wait until the teacher lets them in
+    pub awaiting_approval: Vec<PlayerData>,
     pub help_visible: bool,
+    /// message shown in a pop-up until any key is pressed
+    pub notice: Option<String>,
+    /// item analysis of the questions, received when the game ends
+    pub analysis: Option<Vec<QuestionAnalysis>>,
+    /// every finished question with its statistics, for the review at the end of the game
+    pub ended_questions: Vec<QuestionEnded>,
+    /// whether the game is replayed from a journal, the teacher then only watches
+    pub replay: bool,
+    /// fingerprint of the server certificate, shown while the students join
+    pub tls_fingerprint: Option<String>,
+    /// the latest warning about a misbehaving client, shown with the count of all of them
+    pub last_warning: Option<String>,
+    pub warnings_count: usize,
+    /// PIN of the game, the students need it when the server hosts more games
+    pub pin: Option<String>,
+    /// code the students have to enter to join, shown big while they join
+    pub join_code: Option<String>,
+    /// students from the class roster who are not in the game, `None` if there is no roster
+    pub missing_students: Option<Vec<RosterStudent>>,
+    pub roster_size: usize,
     pub state: TeacherTerminalState,
     pub syntax_theme: Theme,
 }
 
 impl TeacherTerminal {
     #[must_use]
-    pub fn new(quiz_name: String, lobby: Addr<Lobby>, syntax_theme: Theme) -> Self {
+    pub fn new(quiz_name: String, lobby: LobbyHandle, syntax_theme: Theme) -> Self {
         Self {
             quiz_name,
             lobby,
             players: Vec::new(),
+            awaiting_approval: Vec::new(),
             help_visible: false,
+            notice: None,
+            analysis: None,
+            ended_questions: Vec::new(),
+            replay: false,
+            tls_fingerprint: None,
+            last_warning: None,
+            warnings_count: 0,
+            pin: None,
+            join_code: None,
+            missing_students: None,
+            roster_size: 0,
             state: TeacherTerminalState::StartGame,
             syntax_theme,
         }
@@ -0,0 +1,79 @@
+use std::{fmt, fs::File, io::BufReader, path::Path, sync::Arc};
+
+use anyhow::Context;
+use common::tls::{fingerprint, read_certificates};
+use tokio_rustls::{
+    rustls::{
+        pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+        ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+/// Everything the server needs to accept `wss://` connections
+#[derive(Clone)]
+pub struct TlsSettings {
+    pub acceptor: TlsAcceptor,
+    /// SHA-256 fingerprint of the server certificate, the students can pin it
+    pub fingerprint: String,
+}
+
+impl fmt::Debug for TlsSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsSettings")
+            .field("fingerprint", &self.fingerprint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TlsSettings {
+    /// Loads the certificate chain and the private key from PEM files
+    /// # Errors
+    /// - if the files cannot be read
+    /// - if the key does not belong to the certificate
+    pub fn from_pem(cert: &Path, key: &Path) -> anyhow::Result<Self> {
+        let certificates = read_certificates(cert)?;
+
+        let file = File::open(key).with_context(|| format!("Cannot open key {}", key.display()))?;
+        let key = rustls_pemfile::private_key(&mut BufReader::new(file))
+            .with_context(|| format!("Invalid key {}", key.display()))?
+            .with_context(|| format!("There is no private key in {}", key.display()))?;
+
+        Self::new(certificates, key)
+    }
+
+    /// Generates a self-signed certificate for this run of the server, the students
+    /// check it by its fingerprint
+    /// # Errors
+    /// - if the certificate cannot be generated
+    pub fn self_signed() -> anyhow::Result<Self> {
+        let certificate = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+
+        let key = PrivatePkcs8KeyDer::from(certificate.serialize_private_key_der()).into();
+        let certificate = CertificateDer::from(certificate.serialize_der()?);
+
+        Self::new(vec![certificate], key)
+    }
+
+    fn new(
+        certificates: Vec<CertificateDer<'static>>,
+        key: PrivateKeyDer<'static>,
+    ) -> anyhow::Result<Self> {
+        // the first certificate of the chain is the one of the server
+        let fingerprint = fingerprint(
+            certificates
+                .first()
+                .context("The certificate chain is empty")?,
+        );
+
+        let config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certificates, key)
+            .context("The key does not match the certificate")?;
+
+        Ok(Self {
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+            fingerprint,
+        })
+    }
+}
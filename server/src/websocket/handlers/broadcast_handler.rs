@@ -0,0 +1,13 @@
+use actix::Handler;
+
+use crate::{messages::websocket::Broadcast, websocket::Websocket};
+
+impl Handler<Broadcast> for Websocket {
+    type Result = anyhow::Result<()>;
+
+    /// Sends the frame shared with the other websockets, encoding it only if no other
+    /// websocket with the same encoding did
+    fn handle(&mut self, Broadcast(msg): Broadcast, ctx: &mut Self::Context) -> Self::Result {
+        self.send_to_client(msg.message(), |encoding| msg.frame(encoding), ctx)
+    }
+}
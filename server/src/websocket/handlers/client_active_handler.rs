@@ -0,0 +1,27 @@
+use std::time::Instant;
+
+use actix::Handler;
+
+use crate::{
+    messages::websocket::{ClientActive, SetPlayerAway},
+    websocket::Websocket,
+};
+
+impl Handler<ClientActive> for Websocket {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ClientActive, _ctx: &mut Self::Context) -> Self::Result {
+        self.last_heard = Instant::now();
+
+        if self.away {
+            self.away = false;
+
+            if let (Some(player_id), Some(lobby)) = (self.player_id, &self.lobby_addr) {
+                lobby.do_send(SetPlayerAway {
+                    player_id,
+                    away: false,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use actix::{AsyncContext, Handler};
+
+use crate::{
+    messages::websocket::{ClientMisbehaved, ConnectionWarning, GracefulStop},
+    websocket::Websocket,
+};
+
+impl Handler<ClientMisbehaved> for Websocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: ClientMisbehaved, ctx: &mut Self::Context) -> Self::Result {
+        for lobby in self.lobbies() {
+            lobby.do_send(ConnectionWarning {
+                who: self.who,
+                player_id: self.player_id,
+                reason: msg.reason.clone(),
+            });
+        }
+
+        ctx.notify(GracefulStop {
+            reason: msg.goodbye,
+        });
+    }
+}
@@ -1,34 +1,64 @@
-use std::sync::Arc;
-
-use actix::{Addr, AsyncContext, Handler};
+use actix::{ActorFutureExt, ContextFutureSpawner};
+use actix::{Addr, AsyncContext, Handler, WrapFuture};
 use common::messages::{
-    network::{AnswerSelected, JoinRequest, TryJoinRequest},
+    encoding::Encoding,
+    network::{AnswerSelected, CanJoin, TryJoinResponse},
+    protocol::ProtocolVersion,
     ClientNetworkMessage, ServerNetworkMessage,
 };
-use futures_util::stream::SplitSink;
-use tokio::{net::TcpStream, sync::Mutex};
 use tungstenite::Message;
+use uuid::Uuid;
 
 use crate::{
-    messages::{client, websocket::GracefulStop},
-    websocket::{send_message, Websocket},
+    journal::{Journal, JournalEvent},
+    messages::{
+        client,
+        websocket::{ClientMisbehaved, GracefulStop, JoinRefused, SetEncoding},
+    },
+    websocket::{Outbox, Websocket},
     Lobby,
 };
 
-use log::error;
+use log::{error, info};
 
-pub type Sender = Arc<Mutex<SplitSink<tokio_tungstenite::WebSocketStream<TcpStream>, Message>>>;
+// the try join response is sent right away, it does not go through the websocket actor
+fn record_response(journal: Option<&Journal>, player: Uuid, message: &ServerNetworkMessage) {
+    if let Some(journal) = journal {
+        journal.record(&JournalEvent::ToClient {
+            player: Some(player),
+            message: message.clone(),
+        });
+    }
+}
 
 async fn handle_try_join_request(
     lobby: Addr<Lobby>,
-    msg: TryJoinRequest,
-    sender: Sender,
+    msg: client::TryJoinRequest,
+    outbox: Outbox,
+    addr: Addr<Websocket>,
+    journal: Option<Journal>,
 ) -> anyhow::Result<()> {
-    let res = lobby.send(msg).await?;
+    let player = msg.request.uuid;
+    let client_capabilities = msg.request.capabilities.clone();
+    let response = lobby.send(msg).await?;
+
+    // e.g. after a wrong join code, the client may try again
+    if let CanJoin::No(_) = response.can_join {
+        addr.do_send(JoinRefused);
+    }
+
+    // the response itself is always JSON, the client switches after it reads
+    // the capabilities of the server
+    addr.do_send(SetEncoding {
+        encoding: Encoding::negotiate(&client_capabilities, &response.capabilities),
+    });
 
-    let msg = serde_json::to_string(&ServerNetworkMessage::TryJoinResponse(res))?;
+    let res = ServerNetworkMessage::TryJoinResponse(response);
 
-    let () = send_message(sender, Message::Text(msg)).await;
+    record_response(journal.as_ref(), player, &res);
+    let msg = serde_json::to_string(&res)?;
+
+    outbox.push(Message::text(msg), None)?;
 
     Ok(())
 }
@@ -43,39 +73,27 @@ async fn handle_answer_selected(
     if let Err(e) = res {
         // an error means that the client tries to cheat and therefore, we will disconnect
         error!("Player tried to cheat: {e}");
-        addr.do_send(GracefulStop { reason: None });
+        addr.do_send(ClientMisbehaved {
+            reason: format!("Sent an invalid answer: {e}"),
+            goodbye: None,
+        });
         return Err(e);
     }
 
     Ok(())
 }
 
-async fn handle_join_request(
-    lobby: Addr<Lobby>,
-    msg: JoinRequest,
-    sender: Sender,
-    addr: Addr<Websocket>,
-) -> anyhow::Result<()> {
-    let res = lobby
-        .send(client::JoinRequest {
-            player_data: msg.player_data,
-            addr,
-        })
-        .await?;
-
-    let msg = serde_json::to_string(&ServerNetworkMessage::JoinResponse(res))?;
-
-    let () = send_message(sender, Message::Text(msg)).await;
-
-    Ok(())
-}
-
 impl Handler<ClientNetworkMessage> for Websocket {
     type Result = ();
 
     /// Handles mapping of messages
     /// - the websocket --> this function --> lobby
     fn handle(&mut self, msg: ClientNetworkMessage, ctx: &mut Self::Context) -> Self::Result {
+        self.record(&JournalEvent::FromClient {
+            player: self.player_id,
+            message: msg.clone(),
+        });
+
         match msg {
             ClientNetworkMessage::TryJoinRequest(msg) => {
                 if self.player_id.is_some() {
@@ -84,42 +102,99 @@ impl Handler<ClientNetworkMessage> for Websocket {
                     return;
                 }
 
-                self.player_id = Some(msg.uuid);
+                let lobby = match self.rooms.find(msg.pin.as_deref()) {
+                    Ok(lobby) => lobby,
+                    Err(reason) => {
+                        // the client may try again with another PIN
+                        info!("'{}' did not find its game: {reason}", self.who);
+                        let response = ServerNetworkMessage::TryJoinResponse(TryJoinResponse {
+                            uuid: msg.uuid,
+                            can_join: CanJoin::No(reason.to_owned()),
+                            quiz_name: String::new(),
+                            custom_syntaxes: Vec::new(),
+                            protocol: ProtocolVersion::CURRENT,
+                            capabilities: Vec::new(),
+                            student_id_required: false,
+                        });
+
+                        let frame = |encoding: Encoding| encoding.encode(&response);
+                        if let Err(err) = self.send_to_client(&response, frame, ctx) {
+                            error!("Failed to send the try join response: {err}");
+                        }
+                        return;
+                    }
+                };
 
+                self.player_id = Some(msg.uuid);
+                self.capabilities.clone_from(&msg.capabilities);
+                self.resume_token.clone_from(&msg.resume_token);
+                self.lobby_addr = Some(lobby.clone());
+
+                let request = client::TryJoinRequest {
+                    request: msg,
+                    ip: Some(self.who.ip()),
+                };
                 tokio::spawn(handle_try_join_request(
-                    self.lobby_addr.clone(),
-                    msg,
-                    self.sender.clone(),
+                    lobby,
+                    request,
+                    self.outbox.clone(),
+                    ctx.address(),
+                    self.journal.clone(),
                 ));
             }
             ClientNetworkMessage::JoinRequest(msg) => {
                 // If player is cheating by sending a different uuid, just hang up
-                if self.player_id != Some(msg.player_data.uuid) {
+                let (Some(lobby), true) = (
+                    &self.lobby_addr,
+                    self.player_id == Some(msg.player_data.uuid),
+                ) else {
                     error!("Player tried to cheat by sending a different uuid",);
                     ctx.notify(GracefulStop { reason: None });
                     return;
-                }
-
-                tokio::spawn(handle_join_request(
-                    self.lobby_addr.clone(),
-                    msg,
-                    self.sender.clone(),
-                    ctx.address(),
-                ));
+                };
+
+                let request = client::JoinRequest {
+                    player_data: msg.player_data,
+                    addr: ctx.address().into(),
+                    capabilities: self.capabilities.clone(),
+                    student_id: msg.student_id,
+                    resume_token: self.resume_token.clone(),
+                };
+
+                // the websocket waits for the response, so the lobby's messages for the player
+                // (e.g. the players delta) cannot get to the client before it
+                lobby
+                    .send(request)
+                    .into_actor(self)
+                    .map(|response, websocket, ctx| {
+                        let response = match response {
+                            Ok(Some(response)) => ServerNetworkMessage::JoinResponse(response),
+                            // the lobby lets the player know when the teacher decides
+                            Ok(None) => return,
+                            Err(err) => {
+                                error!("Lobby did not respond to the join request: {err}");
+                                return;
+                            }
+                        };
+
+                        let frame = |encoding: Encoding| encoding.encode(&response);
+                        if let Err(err) = websocket.send_to_client(&response, frame, ctx) {
+                            error!("Failed to send the join response: {err}");
+                        }
+                    })
+                    .wait(ctx);
             }
             ClientNetworkMessage::AnswerSelected(msg) => {
                 // If player is cheating by sending a different uuid, just hang up
-                if self.player_id != Some(msg.player_uuid) {
+                let (Some(lobby), true) =
+                    (&self.lobby_addr, self.player_id == Some(msg.player_uuid))
+                else {
                     error!("Player tried to cheat by sending a different uuid");
                     ctx.notify(GracefulStop { reason: None });
                     return;
-                }
+                };
 
-                tokio::spawn(handle_answer_selected(
-                    self.lobby_addr.clone(),
-                    msg,
-                    ctx.address(),
-                ));
+                tokio::spawn(handle_answer_selected(lobby.clone(), msg, ctx.address()));
             }
         }
     }
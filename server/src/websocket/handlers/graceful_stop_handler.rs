@@ -1,6 +1,4 @@
-use std::borrow::Cow;
-
-use actix::{dev::ContextFutureSpawner, AsyncContext, Handler};
+use actix::{AsyncContext, Handler};
 use common::constants::DEFAULT_GOODBYE_MESSAGE;
 use tungstenite::{
     protocol::{frame::coding::CloseCode, CloseFrame},
@@ -9,7 +7,7 @@ use tungstenite::{
 
 use crate::{
     messages::websocket::{GracefulStop, HardStop},
-    websocket::{prepare_explicit_message, Websocket},
+    websocket::Websocket,
 };
 
 impl Handler<GracefulStop> for Websocket {
@@ -23,11 +21,11 @@ impl Handler<GracefulStop> for Websocket {
         // also send close message to the client
         let msg = Message::Close(Some(CloseFrame {
             code: CloseCode::Normal,
-            reason: Cow::from(reason),
+            reason: reason.into(),
         }));
 
-        // send a goodbye message
-        prepare_explicit_message::<Self>(self.sender.clone(), msg).wait(ctx);
+        // send a goodbye message as the last one
+        self.outbox.close(Some(msg));
 
         ctx.notify(HardStop {});
     }
@@ -0,0 +1,14 @@
+use actix::Handler;
+use log::debug;
+
+use crate::{messages::websocket::JoinRefused, websocket::Websocket};
+
+impl Handler<JoinRefused> for Websocket {
+    type Result = ();
+
+    fn handle(&mut self, _msg: JoinRefused, _ctx: &mut Self::Context) -> Self::Result {
+        debug!("{} was refused, it may try to join again", self.who);
+        self.player_id = None;
+        self.lobby_addr = None;
+    }
+}
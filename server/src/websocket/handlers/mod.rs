@@ -1,6 +1,9 @@
+mod broadcast_handler;
+mod client_active_handler;
+mod client_misbehaved_handler;
 mod client_network_message_handler;
 mod graceful_stop_handler;
 mod hard_stop_handler;
+mod join_refused_handler;
 mod server_network_message_handler;
-
-pub use client_network_message_handler::*;
+mod set_encoding_handler;
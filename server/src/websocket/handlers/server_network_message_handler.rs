@@ -1,7 +1,7 @@
-use actix::{dev::ContextFutureSpawner, Handler};
+use actix::Handler;
 use common::messages::ServerNetworkMessage;
 
-use crate::websocket::{prepare_message, Websocket};
+use crate::websocket::Websocket;
 
 impl Handler<ServerNetworkMessage> for Websocket {
     type Result = anyhow::Result<()>;
@@ -9,10 +9,6 @@ impl Handler<ServerNetworkMessage> for Websocket {
     /// Handles mapping of messages
     /// - lobby --> this function --> the websocket  
     fn handle(&mut self, msg: ServerNetworkMessage, ctx: &mut Self::Context) -> Self::Result {
-        let msg = serde_json::to_string(&msg)?;
-
-        prepare_message::<Self>(self.sender.clone(), msg).wait(ctx);
-
-        Ok(())
+        self.send_to_client(&msg, |encoding| encoding.encode(&msg), ctx)
     }
 }
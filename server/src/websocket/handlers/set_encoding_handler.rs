@@ -0,0 +1,16 @@
+use actix::Handler;
+use log::debug;
+
+use crate::{messages::websocket::SetEncoding, websocket::Websocket};
+
+impl Handler<SetEncoding> for Websocket {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetEncoding, _ctx: &mut Self::Context) -> Self::Result {
+        debug!(
+            "Messages for {} are encoded as {:?}",
+            self.who, msg.encoding
+        );
+        self.encoding = msg.encoding;
+    }
+}
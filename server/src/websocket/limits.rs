@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tungstenite::protocol::WebSocketConfig;
+
+/// The biggest message a client may send, in bytes, the biggest legitimate one
+/// (the `JoinRequest`) is well under a kilobyte
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 16 * 1024;
+
+/// How many frames a client may send per second on average, a student clicking through
+/// the game with the heartbeat running sends a few
+pub const DEFAULT_MAX_MESSAGES_PER_SEC: u32 = 20;
+
+/// How many seconds a client has for the TLS and websocket handshakes, a client on
+/// a slow school network is done in a few
+pub const DEFAULT_HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+
+/// What a single connection is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    pub max_frame_size: usize,
+    /// twice as many frames are allowed in a short burst
+    pub max_messages_per_sec: u32,
+    /// `None` allows any number of connections from one IP address, which is needed
+    /// when the students connect through a NAT
+    pub max_connections_per_ip: Option<usize>,
+    /// the connection is closed if the client does not finish the handshakes in time
+    pub handshake_timeout: Duration,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            max_messages_per_sec: DEFAULT_MAX_MESSAGES_PER_SEC,
+            max_connections_per_ip: None,
+            handshake_timeout: Duration::from_secs(DEFAULT_HANDSHAKE_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl ConnectionLimits {
+    /// Configuration of the websocket, frames and messages bigger than the limit are refused
+    #[must_use]
+    pub fn websocket_config(&self) -> WebSocketConfig {
+        WebSocketConfig::default()
+            .max_message_size(Some(self.max_frame_size))
+            .max_frame_size(Some(self.max_frame_size))
+    }
+}
+
+/// Token bucket, refilled with `per_sec` tokens every second up to twice as many
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    per_sec: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(per_sec: u32) -> Self {
+        let per_sec = f64::from(per_sec);
+        Self {
+            per_sec,
+            burst: per_sec * 2.0,
+            tokens: per_sec * 2.0,
+            last: Instant::now(),
+        }
+    }
+
+    /// Takes a token for a message which came at `now`, `false` if there is none left
+    pub fn allow_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.last = now;
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.burst);
+
+        if self.tokens < 1.0 {
+            return false;
+        }
+
+        self.tokens -= 1.0;
+        true
+    }
+
+    pub fn allow(&mut self) -> bool {
+        self.allow_at(Instant::now())
+    }
+}
+
+/// Counts the open connections from each IP address
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionCounter(Arc<Mutex<HashMap<IpAddr, usize>>>);
+
+impl ConnectionCounter {
+    /// Counts a new connection from the address, `None` if there are already `max` of them,
+    /// the connection is counted until the returned guard is dropped
+    #[must_use]
+    pub fn acquire(&self, ip: IpAddr, max: Option<usize>) -> Option<ConnectionGuard> {
+        let mut counts = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let count = counts.entry(ip).or_default();
+
+        if max.is_some_and(|max| *count >= max) {
+            return None;
+        }
+
+        *count += 1;
+        Some(ConnectionGuard {
+            counter: self.clone(),
+            ip,
+        })
+    }
+
+    /// Number of the open connections from the address
+    #[must_use]
+    pub fn count(&self, ip: IpAddr) -> usize {
+        let counts = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        counts.get(&ip).copied().unwrap_or_default()
+    }
+}
+
+/// An open connection counted by the [`ConnectionCounter`]
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    counter: ConnectionCounter,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut counts = self
+            .counter
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(count) = counts.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&self.ip);
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 pub mod handlers;
+mod limits;
+mod outbox;
 mod websocket_impl;
-mod ws_utils;
 
 pub use self::websocket_impl::*;
-pub use handlers::*;
-pub use ws_utils::*;
+pub use limits::*;
+pub use outbox::*;
@@ -0,0 +1,263 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use common::messages::ServerNetworkMessage;
+use futures_util::{stream::SplitSink, SinkExt};
+use log::{debug, info};
+use tokio::sync::Notify;
+use tungstenite::Message;
+
+use super::Connection;
+
+pub type Sender = SplitSink<tokio_tungstenite::WebSocketStream<Connection>, Message>;
+
+/// How many frames may wait for a client before it is disconnected as too slow
+pub const DEFAULT_OUTBOX_CAPACITY: usize = 256;
+
+/// Frames of the same kind supersede each other, only the latest one is worth sending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coalesce {
+    /// number of players who answered the question with the index
+    QuestionUpdate(usize),
+    /// the whole list of players
+    PlayersUpdate,
+    Ping,
+}
+
+impl Coalesce {
+    /// The kind of the message, `None` if every message of the kind must be delivered
+    #[must_use]
+    pub fn of(msg: &ServerNetworkMessage) -> Option<Self> {
+        match msg {
+            ServerNetworkMessage::QuestionUpdate(update) => {
+                Some(Self::QuestionUpdate(update.question_index))
+            }
+            ServerNetworkMessage::PlayersUpdate(_) => Some(Self::PlayersUpdate),
+            _ => None,
+        }
+    }
+}
+
+/// The client does not read its messages fast enough
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboxFull;
+
+impl fmt::Display for OutboxFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The outbound queue of the client is full")
+    }
+}
+
+impl std::error::Error for OutboxFull {}
+
+/// Statistics of the outbound queues of all the clients
+#[derive(Debug, Clone, Default)]
+pub struct OutboxMetrics(Arc<OutboxCounters>);
+
+#[derive(Debug, Default)]
+struct OutboxCounters {
+    sent: AtomicU64,
+    coalesced: AtomicU64,
+    slow_clients: AtomicU64,
+    peak_len: AtomicUsize,
+}
+
+impl OutboxMetrics {
+    /// frames written to the sockets
+    #[must_use]
+    pub fn sent(&self) -> u64 {
+        self.0.sent.load(Ordering::Relaxed)
+    }
+
+    /// frames replaced by a newer frame of the same kind before they were sent
+    #[must_use]
+    pub fn coalesced(&self) -> u64 {
+        self.0.coalesced.load(Ordering::Relaxed)
+    }
+
+    /// clients disconnected because their queue was full
+    #[must_use]
+    pub fn slow_clients(&self) -> u64 {
+        self.0.slow_clients.load(Ordering::Relaxed)
+    }
+
+    /// the longest any queue has been
+    #[must_use]
+    pub fn peak_len(&self) -> usize {
+        self.0.peak_len.load(Ordering::Relaxed)
+    }
+
+    pub fn record_slow_client(&self) {
+        self.0.slow_clients.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for OutboxMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} frames sent, {} coalesced, {} slow clients disconnected, longest queue {}",
+            self.sent(),
+            self.coalesced(),
+            self.slow_clients(),
+            self.peak_len()
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct Queue {
+    frames: VecDeque<(Option<Coalesce>, Message)>,
+    /// no more frames are accepted, the writer stops when the queue is empty
+    closed: bool,
+}
+
+/// Bounded queue of the frames waiting to be written to one client,
+/// the websocket actor pushes to it and never waits for the client
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    queue: Arc<Mutex<Queue>>,
+    notify: Arc<Notify>,
+    capacity: usize,
+    metrics: OutboxMetrics,
+}
+
+impl Outbox {
+    #[must_use]
+    pub fn new(capacity: usize, metrics: OutboxMetrics) -> Self {
+        Self {
+            queue: Arc::default(),
+            notify: Arc::default(),
+            capacity,
+            metrics,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Queue> {
+        // the queue stays consistent even if a thread panicked while holding the lock
+        self.queue
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Queues the frame, a queued frame of the same kind is replaced by it
+    /// # Errors
+    /// - if the queue is full, the client is too slow
+    pub fn push(&self, frame: Message, kind: Option<Coalesce>) -> Result<(), OutboxFull> {
+        let mut queue = self.lock();
+        if queue.closed {
+            return Ok(());
+        }
+
+        if let Some(kind) = kind {
+            if let Some(queued) = queue.frames.iter_mut().find(|(k, _)| *k == Some(kind)) {
+                queued.1 = frame;
+                self.metrics.0.coalesced.fetch_add(1, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        if queue.frames.len() >= self.capacity {
+            return Err(OutboxFull);
+        }
+
+        queue.frames.push_back((kind, frame));
+        self.metrics
+            .0
+            .peak_len
+            .fetch_max(queue.frames.len(), Ordering::Relaxed);
+        drop(queue);
+
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// Queues the last frame (e.g. the close frame), even if the queue is full,
+    /// nothing is accepted after it
+    pub fn close(&self, last: Option<Message>) {
+        let mut queue = self.lock();
+        if queue.closed {
+            return;
+        }
+
+        queue.closed = true;
+        if let Some(last) = last {
+            queue.frames.push_back((None, last));
+        }
+        drop(queue);
+
+        self.notify.notify_one();
+    }
+
+    /// Drops the queued frames, e.g. when the client is disconnected for being too slow
+    pub fn discard(&self) {
+        let mut queue = self.lock();
+        queue.closed = true;
+        queue.frames.clear();
+        drop(queue);
+
+        self.notify.notify_one();
+    }
+
+    #[must_use]
+    pub fn metrics(&self) -> &OutboxMetrics {
+        &self.metrics
+    }
+
+    /// Number of the frames waiting to be written
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().frames.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits for the next frame, `None` when the queue is closed and empty
+    pub async fn next(&self) -> Option<Message> {
+        loop {
+            {
+                let mut queue = self.lock();
+                if let Some((_, frame)) = queue.frames.pop_front() {
+                    return Some(frame);
+                }
+                if queue.closed {
+                    return None;
+                }
+            }
+
+            self.notify.notified().await;
+        }
+    }
+}
+
+/// Writes the frames of the outbox to the socket until the outbox is closed,
+/// a client which does not take a frame within `timeout` is given up on
+pub async fn write_frames(outbox: Outbox, mut sender: Sender, timeout: Duration) {
+    while let Some(frame) = outbox.next().await {
+        match tokio::time::timeout(timeout, sender.send(frame)).await {
+            Ok(Ok(())) => {
+                outbox.metrics.0.sent.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(Err(err)) => {
+                debug!("Writing to the socket failed: {err}");
+                break;
+            }
+            Err(_) => {
+                info!("The client did not take a frame for {timeout:?}, giving up");
+                break;
+            }
+        }
+    }
+
+    let _ = sender.close().await;
+}
@@ -1,57 +1,216 @@
 use actix::AsyncContext;
-use actix::{Actor, Addr, Running};
+use actix::{Actor, ActorContext, Addr, Running};
 
-use crate::messages::websocket::GracefulStop;
+use crate::journal::{Journal, JournalEvent};
+use crate::messages::websocket::{ClientMisbehaved, GracefulStop};
+use crate::rooms::Rooms;
 use crate::Lobby;
-use common::messages::ClientNetworkMessage;
+use common::heartbeat::Heartbeat;
+use common::messages::{
+    encoding::{decode, Encoding},
+    protocol::{Capability, ProtocolVersion},
+    ClientNetworkMessage, ServerNetworkMessage,
+};
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
-use tokio::sync::Mutex;
 
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::time::Instant;
 
-use tokio::net::TcpStream;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::task::JoinHandle;
 
-use crate::messages::websocket::{DisconnectFromLobby, HardStop};
-use log::{debug, error, info};
-use tungstenite::Message;
+use crate::messages::websocket::{ClientActive, DisconnectFromLobby, HardStop, SetPlayerAway};
+use log::{debug, error, info, warn};
+use tungstenite::{Bytes, Message};
 use uuid::Uuid;
 
-use super::Sender;
-type Receiver = SplitStream<tokio_tungstenite::WebSocketStream<TcpStream>>;
+use super::{
+    write_frames, Coalesce, ConnectionGuard, ConnectionLimits, Outbox, OutboxMetrics, RateLimiter,
+};
+type Receiver = SplitStream<tokio_tungstenite::WebSocketStream<Connection>>;
+
+/// Stream the websocket runs over, a plain TCP stream or a TLS stream
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+pub type Connection = Box<dyn Stream>;
+
+/// What every connection of the lobby is set up with
+#[derive(Debug, Clone)]
+pub struct ConnectionSettings {
+    pub journal: Option<Journal>,
+    pub heartbeat: Heartbeat,
+    pub limits: ConnectionLimits,
+    pub outbox_capacity: usize,
+    pub outbox_metrics: OutboxMetrics,
+}
 
 pub struct Websocket {
-    pub lobby_addr: Addr<Lobby>,
+    /// lobbies the client can join
+    pub rooms: Rooms,
+    /// the lobby of the game the client joins, `None` until its `TryJoinRequest` finds one
+    pub lobby_addr: Option<Addr<Lobby>>,
     pub player_id: Option<Uuid>,
     pub receiver: Option<Receiver>,
-    pub sender: Sender,
+    /// frames waiting to be written to the client by the writer task
+    pub outbox: Outbox,
     pub reader_task: Option<JoinHandle<()>>,
+    pub writer_task: Option<JoinHandle<()>>,
     pub who: SocketAddr,
+    pub journal: Option<Journal>,
+    /// what the client announced in its `TryJoinRequest`
+    pub capabilities: Vec<Capability>,
+    /// secret of the client from its `TryJoinRequest`, for resuming the game
+    pub resume_token: Option<String>,
+    pub heartbeat: Heartbeat,
+    /// when anything came from the client last time
+    pub last_heard: Instant,
+    /// whether the lobby was told that the client stopped responding
+    pub away: bool,
+    /// how the messages for the client are encoded, negotiated when the client tries to join
+    pub encoding: Encoding,
+    pub limits: ConnectionLimits,
+    /// keeps the connection counted for its IP address while the websocket lives
+    pub connection: ConnectionGuard,
 }
 
 impl Websocket {
     pub async fn new(
-        lobby: Addr<Lobby>,
-        socket: TcpStream,
+        rooms: Rooms,
+        socket: Connection,
         who: SocketAddr,
+        settings: ConnectionSettings,
+        connection: ConnectionGuard,
     ) -> anyhow::Result<Websocket> {
-        let socket = tokio_tungstenite::accept_async(socket).await?;
+        let config = settings.limits.websocket_config();
+        let socket = tokio_tungstenite::accept_async_with_config(socket, Some(config)).await?;
 
         let (sender, receiver) = socket.split();
+        let outbox = Outbox::new(settings.outbox_capacity, settings.outbox_metrics);
+        let heartbeat = settings.heartbeat;
+
+        // a client which does not read for the whole heartbeat timeout is not coming back
+        let writer_task = tokio::spawn(write_frames(outbox.clone(), sender, heartbeat.timeout));
 
         Ok(Websocket {
             player_id: None,
-            lobby_addr: lobby,
+            rooms,
+            lobby_addr: None,
             receiver: Some(receiver),
-            sender: Arc::new(Mutex::new(sender)),
+            outbox,
             reader_task: None,
+            writer_task: Some(writer_task),
             who,
+            journal: settings.journal,
+            capabilities: Vec::new(),
+            resume_token: None,
+            heartbeat,
+            last_heard: Instant::now(),
+            away: false,
+            encoding: Encoding::default(),
+            limits: settings.limits,
+            connection,
         })
     }
 }
 
+impl Websocket {
+    /// Lobbies which should know about the client, all of them until it chooses one
+    #[must_use]
+    pub fn lobbies(&self) -> Vec<Addr<Lobby>> {
+        match &self.lobby_addr {
+            Some(lobby) => vec![lobby.clone()],
+            None => self.rooms.lobbies(),
+        }
+    }
+
+    /// Records the event to the journal, if there is one
+    pub fn record(&self, event: &JournalEvent) {
+        if let Some(journal) = &self.journal {
+            journal.record(event);
+        }
+    }
+
+    /// Sends the message to the client in the negotiated encoding, `frame` encodes it,
+    /// the messages of a capability the client did not announce are skipped
+    /// # Errors
+    /// - if the message cannot be encoded
+    pub fn send_to_client(
+        &mut self,
+        msg: &ServerNetworkMessage,
+        frame: impl FnOnce(Encoding) -> anyhow::Result<Message>,
+        ctx: &mut <Self as Actor>::Context,
+    ) -> anyhow::Result<()> {
+        // an older client would not be able to parse the message
+        if let Some(capability) = msg.required_capability() {
+            if !self.capabilities.contains(&capability) {
+                debug!("Client {} does not support {capability:?}", self.who);
+                return Ok(());
+            }
+        }
+
+        if self.journal.is_some() {
+            self.record(&JournalEvent::ToClient {
+                player: self.player_id,
+                message: msg.clone(),
+            });
+        }
+
+        self.push(frame(self.encoding)?, Coalesce::of(msg), ctx);
+
+        Ok(())
+    }
+
+    /// Queues the frame for the client, a client which fell too far behind is disconnected
+    pub fn push(
+        &mut self,
+        frame: Message,
+        kind: Option<Coalesce>,
+        ctx: &mut <Self as Actor>::Context,
+    ) {
+        if self.outbox.push(frame, kind).is_ok() {
+            return;
+        }
+
+        warn!(
+            "Disconnecting '{}', it has {} messages waiting",
+            self.who,
+            self.outbox.len()
+        );
+        self.outbox.metrics().record_slow_client();
+
+        // the client would not read the close frame anyway
+        self.outbox.discard();
+        if let Some(writer_task) = &self.writer_task {
+            writer_task.abort();
+        }
+
+        ctx.stop();
+    }
+
+    /// Tells the lobby when the client stops responding and pings it
+    fn check_heartbeat(&mut self, ctx: &mut <Self as Actor>::Context) {
+        if !self.away && self.heartbeat.is_stale(self.last_heard) {
+            info!(
+                "'{}' did not respond for {:?}",
+                self.who, self.heartbeat.timeout
+            );
+            self.away = true;
+
+            if let (Some(player_id), Some(lobby)) = (self.player_id, &self.lobby_addr) {
+                lobby.do_send(SetPlayerAway {
+                    player_id,
+                    away: true,
+                });
+            }
+        }
+
+        self.push(Message::Ping(Bytes::new()), Some(Coalesce::Ping), ctx);
+    }
+}
+
 impl Actor for Websocket {
     type Context = actix::Context<Self>;
 
@@ -64,8 +223,11 @@ impl Actor for Websocket {
         let receiver = self.receiver.take().expect("Could not take receiver"); // take ownership of the receiver, expect is fine
 
         // Spawn a Tokio task which will read from the socket and generate messages for this actor
-        let reader_task = tokio::spawn(read_messages_from_socket(receiver, who, addr));
+        let limiter = RateLimiter::new(self.limits.max_messages_per_sec);
+        let reader_task = tokio::spawn(read_messages_from_socket(receiver, who, addr, limiter));
         self.reader_task = Some(reader_task);
+
+        ctx.run_interval(self.heartbeat.interval, Self::check_heartbeat);
     }
 
     fn stopping(&mut self, _: &mut Self::Context) -> Running {
@@ -73,8 +235,11 @@ impl Actor for Websocket {
             reader_task.abort();
         }
 
-        if let Some(player_id) = self.player_id {
-            self.lobby_addr.do_send(DisconnectFromLobby { player_id });
+        // the writer task stops after the queued frames are written
+        self.outbox.close(None);
+
+        if let (Some(player_id), Some(lobby)) = (self.player_id, &self.lobby_addr) {
+            lobby.do_send(DisconnectFromLobby { player_id });
         }
 
         Running::Stop
@@ -85,28 +250,62 @@ impl Actor for Websocket {
     }
 }
 
-async fn read_messages_from_socket<'a>(
-    mut receiver: SplitStream<tokio_tungstenite::WebSocketStream<TcpStream>>,
+async fn read_messages_from_socket(
+    mut receiver: Receiver,
     who: SocketAddr,
     addr: Addr<Websocket>,
+    mut limiter: RateLimiter,
 ) {
     while let Some(msg) = receiver.next().await {
-        let Ok(msg) = msg else {
-            info!("Hanging up on '{}' because reading from socket failed", who);
-            addr.do_send(HardStop);
-            return;
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(tungstenite::Error::Capacity(err)) => {
+                info!("Hanging up on '{who}' because it sent a too big message: {err}");
+                let reason = "The message was too big".to_owned();
+                addr.do_send(ClientMisbehaved {
+                    goodbye: Some(reason.clone()),
+                    reason,
+                });
+                return;
+            }
+            Err(_) => {
+                info!("Hanging up on '{}' because reading from socket failed", who);
+                addr.do_send(HardStop);
+                return;
+            }
         };
 
+        // also pings and pongs count, a client could flood the server with them as well
+        if !limiter.allow() {
+            info!("Hanging up on '{who}' because it sent too many messages");
+            let reason = "Too many messages were sent".to_owned();
+            addr.do_send(ClientMisbehaved {
+                goodbye: Some(reason.clone()),
+                reason,
+            });
+            return;
+        }
+
+        // also pings and pongs count, the client is alive
+        addr.do_send(ClientActive);
+
         match msg {
-            Message::Text(msg) => {
-                // try to parse the JSON s to a `NetworkMessage`
-                match serde_json::from_str::<ClientNetworkMessage>(&msg) {
+            Message::Text(_) | Message::Binary(_) => {
+                // try to parse the JSON or MessagePack to a `NetworkMessage`
+                match decode::<ClientNetworkMessage>(&msg) {
                     Ok(msg) => {
                         addr.do_send(msg);
                     }
                     Err(e) => {
                         error!("Hanging up on the client bcs parsing message failed: {}", e);
-                        addr.do_send(GracefulStop { reason: None });
+                        // most likely a client from another release
+                        addr.do_send(GracefulStop {
+                            reason: Some(format!(
+                                "The server does not understand your client, \
+                                please update it to protocol {}",
+                                ProtocolVersion::CURRENT
+                            )),
+                        });
                     }
                 }
             }
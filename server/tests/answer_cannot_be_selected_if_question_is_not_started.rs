@@ -2,7 +2,7 @@ mod fixtures;
 mod mocks;
 mod utils;
 
-use std::{borrow::Cow, collections::HashSet, thread::JoinHandle, time::Duration};
+use std::{collections::HashSet, thread::JoinHandle, time::Duration};
 
 use actix::Addr;
 use common::{
@@ -47,7 +47,7 @@ async fn answer_cannot_be_selected_if_question_is_not_started(
 
     // try to answer before the question is started
     sender
-        .send(Message::Text(serde_json::to_string(&answer)?))
+        .send(Message::text(serde_json::to_string(&answer)?))
         .await?;
 
     // The server should disconnect, because the player tried to cheat
@@ -57,7 +57,7 @@ async fn answer_cannot_be_selected_if_question_is_not_started(
         close,
         CloseFrame {
             code: CloseCode::Normal,
-            reason: Cow::from(DEFAULT_GOODBYE_MESSAGE),
+            reason: DEFAULT_GOODBYE_MESSAGE.into(),
         }
     );
 
@@ -0,0 +1,64 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use common::{heartbeat::Heartbeat, terminal::messages::Stop};
+use rstest::rstest;
+use server::{
+    lobby::{Lobby, Phase},
+    messages::lobby::{self, StartQuestion},
+};
+
+use crate::{
+    fixtures::{create_server::start_server, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn away_player_is_not_waited_for() -> anyhow::Result<()> {
+    let heartbeat = Heartbeat {
+        interval: Duration::from_millis(100),
+        timeout: Duration::from_millis(300),
+    };
+    let (server_thread, server) =
+        start_server(Lobby::new(utils::sample_questions()).with_heartbeat(heartbeat));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (mut sender1, mut receiver1, player1) = utils::join_new_player().await?;
+    let (_sender2, mut receiver2, player2) = utils::join_new_player().await?;
+    let _ = utils::receive_players_delta(&mut receiver1).await?;
+
+    server.send(StartQuestion).await??;
+    let question = utils::receive_next_question(&mut receiver1).await?;
+    let _ = utils::receive_next_question(&mut receiver2).await?;
+
+    // the test clients answer the pings only when they read, so now both go silent
+    tokio::time::sleep(Duration::from_millis(800)).await;
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.away_players.contains(&player1.uuid));
+    assert!(state.away_players.contains(&player2.uuid));
+    // nobody is left to answer, but the question does not end until the timer does
+    assert_eq!(state.phase, Phase::ActiveQuestion(0));
+
+    // the first player comes back and answers, the second one is not waited for
+    utils::send_question_answer(&mut sender1, &player1, &question.question, 0, vec![1]).await?;
+    let _ = utils::receive_question_ended(&mut receiver1).await?;
+
+    let state = server.send(GetServerState).await?;
+    assert!(!state.away_players.contains(&player1.uuid));
+    assert!(state.away_players.contains(&player2.uuid));
+    assert_eq!(state.phase, Phase::AfterQuestion(0));
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
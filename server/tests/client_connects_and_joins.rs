@@ -7,7 +7,10 @@ use std::{thread::JoinHandle, time::Duration};
 use actix::Addr;
 use common::{
     constants::DEFAULT_QUIZ_NAME,
-    messages::network::{CanJoin, JoinResponse, TryJoinResponse},
+    messages::{
+        network::{CanJoin, JoinResponse, TryJoinResponse},
+        protocol::{Capability, ProtocolVersion},
+    },
 };
 use rstest::rstest;
 use server::{
@@ -37,6 +40,10 @@ async fn client_connects_and_joins(
             can_join: CanJoin::Yes,
             uuid: id,
             quiz_name: DEFAULT_QUIZ_NAME.to_string(),
+            custom_syntaxes: vec![],
+            protocol: ProtocolVersion::CURRENT,
+            capabilities: Capability::supported(),
+            student_id_required: false,
         }
     );
 
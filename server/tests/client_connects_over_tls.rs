@@ -0,0 +1,64 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use common::{constants::DEFAULT_PORT, messages::network::CanJoin, terminal::messages::Stop, tls};
+use futures_util::StreamExt;
+use rstest::rstest;
+use server::{lobby::Lobby, messages::lobby, tls::TlsSettings};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async_tls_with_config, Connector};
+
+use crate::fixtures::{create_server::start_server, create_server_and_teacher::start_teacher};
+
+fn pinned(fingerprint: &str) -> anyhow::Result<Option<Connector>> {
+    Ok(tls::client_config(None, Some(fingerprint))?.map(Connector::Rustls))
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn client_connects_over_tls() -> anyhow::Result<()> {
+    let settings = TlsSettings::self_signed()?;
+    let fingerprint = settings.fingerprint.clone();
+
+    let (server_thread, server) =
+        start_server(Lobby::new(utils::sample_questions()).with_tls(Some(settings)));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let url = format!("wss://localhost:{DEFAULT_PORT}");
+
+    // a certificate with another fingerprint is refused
+    let other_fingerprint = TlsSettings::self_signed()?.fingerprint;
+    let refused =
+        connect_async_tls_with_config(&url, None, false, pinned(&other_fingerprint)?).await;
+    assert!(refused.is_err());
+
+    // plain websocket cannot talk to the server
+    let plain = tokio_tungstenite::connect_async(format!("ws://localhost:{DEFAULT_PORT}")).await;
+    assert!(plain.is_err());
+
+    // a client stalled before the TLS handshake does not keep the others from joining
+    let _stalled = TcpStream::connect(format!("localhost:{DEFAULT_PORT}")).await?;
+
+    // the fingerprint may be typed without the colons and in lowercase
+    let typed = fingerprint.replace(':', "").to_lowercase();
+    let (conn, _) = connect_async_tls_with_config(&url, None, false, pinned(&typed)?).await?;
+    let (mut sender, mut receiver) = conn.split();
+
+    let (id, msg) = utils::try_join_server(&mut sender, &mut receiver).await?;
+    assert_eq!(msg.can_join, CanJoin::Yes);
+
+    let (_, msg) = utils::join_server(&mut sender, &mut receiver, id).await?;
+    assert_eq!(msg.can_join, CanJoin::Yes);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{thread::JoinHandle, time::Duration};
+
+use actix::Addr;
+use anyhow::bail;
+use common::messages::{
+    network::{CanJoin, TryJoinRequest, TryJoinResponse},
+    protocol::ProtocolVersion,
+    ClientNetworkMessage, ServerNetworkMessage,
+};
+use futures_util::SinkExt;
+use rstest::rstest;
+use server::{
+    lobby::Lobby,
+    messages::lobby::{self, StartQuestion, SwitchToLeaderboard},
+    Teacher,
+};
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::{
+    fixtures::create_server_and_teacher::create_server_and_teacher, mocks::GetServerState,
+};
+
+async fn send_raw_try_join(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    request: String,
+) -> anyhow::Result<TryJoinResponse> {
+    sender.send(Message::text(request)).await?;
+
+    let Message::Text(msg) = utils::receive_frame(receiver).await? else {
+        bail!("Expected Text message")
+    };
+
+    let ServerNetworkMessage::TryJoinResponse(msg) = serde_json::from_str(&msg)? else {
+        bail!("Expected TryJoinResponse")
+    };
+
+    Ok(msg)
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn client_protocol_is_checked(
+    create_server_and_teacher: (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>),
+) -> anyhow::Result<()> {
+    let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
+
+    // a client of an old major version is asked to update
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let request = ClientNetworkMessage::TryJoinRequest(TryJoinRequest {
+        protocol: ProtocolVersion { major: 0, minor: 9 },
+        ..TryJoinRequest::new(Uuid::new_v4())
+    });
+    let response =
+        send_raw_try_join(&mut sender, &mut receiver, serde_json::to_string(&request)?).await?;
+
+    let CanJoin::No(reason) = response.can_join else {
+        bail!("The outdated client was let in");
+    };
+    assert!(reason.contains(&format!(
+        "update your client to protocol {}",
+        ProtocolVersion::CURRENT
+    )));
+    assert!(server
+        .send(GetServerState)
+        .await?
+        .waiting_players
+        .is_empty());
+
+    // a client from before the handshake is of the previous minor version
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let id = Uuid::new_v4();
+    let response = send_raw_try_join(
+        &mut sender,
+        &mut receiver,
+        format!(r#"{{"TryJoinRequest":{{"uuid":"{id}"}}}}"#),
+    )
+    .await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+    assert_eq!(response.protocol, ProtocolVersion::CURRENT);
+
+    let (player, _) = utils::join_server(&mut sender, &mut receiver, id).await?;
+
+    server.send(StartQuestion).await??;
+    let question = utils::receive_next_question(&mut receiver).await?;
+
+    // the only player answers, so the question ends right away
+    utils::send_question_answer(&mut sender, &player, &question.question, 0, vec![1]).await?;
+    let _ = utils::receive_question_ended(&mut receiver).await?;
+
+    server.send(SwitchToLeaderboard).await??;
+    let leaderboard = utils::receive_show_leaderboard(&mut receiver).await?;
+    assert!(leaderboard.was_final_round);
+
+    // the old client would not understand the personal report
+    let next = tokio::time::timeout(
+        Duration::from_millis(500),
+        utils::receive_frame(&mut receiver),
+    )
+    .await;
+    assert!(next.is_err(), "The old client received {next:?}");
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(common::terminal::messages::Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{path::Path, thread::JoinHandle, time::Duration};
+
+use actix::Addr;
+use common::{questions::QuestionSet, syntax};
+use rstest::rstest;
+use server::{
+    messages::lobby::{self},
+    Lobby,
+};
+
+use crate::fixtures::create_server::create_server;
+
+fn custom_syntax_questions() -> QuestionSet {
+    // custom syntaxes have to be loaded before the questions that use them
+    syntax::load_custom_dir(Path::new("../common/tests/files/syntaxes"))
+        .expect("Failed to load custom syntaxes");
+
+    QuestionSet::from_file(Path::new("../common/tests/files/ok_custom_syntax.yaml"))
+        .expect("Failed to load questions with custom syntax")
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn client_receives_custom_syntaxes(
+    #[with(custom_syntax_questions())] create_server: (JoinHandle<()>, Addr<Lobby>),
+) -> anyhow::Result<()> {
+    let (server_thread, server) = create_server;
+
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+
+    let (_, msg) = utils::try_join_server(&mut sender, &mut receiver).await?;
+
+    assert_eq!(msg.custom_syntaxes, syntax::custom_syntaxes(["While"]));
+    assert_eq!(msg.custom_syntaxes.len(), 1);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    Ok(())
+}
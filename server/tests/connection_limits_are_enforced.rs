@@ -0,0 +1,55 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use common::{constants::DEFAULT_PORT, terminal::messages::Stop};
+use futures_util::SinkExt;
+use rstest::rstest;
+use server::{lobby::Lobby, messages::lobby, websocket::ConnectionLimits};
+use tungstenite::{Bytes, Message};
+
+use crate::fixtures::{create_server::start_server, create_server_and_teacher::start_teacher};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn connection_limits_are_enforced() -> anyhow::Result<()> {
+    let lobby = Lobby::new(utils::sample_questions()).with_connection_limits(ConnectionLimits {
+        max_frame_size: 4096,
+        max_messages_per_sec: 10,
+        max_connections_per_ip: Some(2),
+        ..ConnectionLimits::default()
+    });
+    let (server_thread, server) = start_server(lobby);
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // a message bigger than the limit
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    sender.send(Message::text("x".repeat(8192))).await?;
+    let close = utils::receive_close_frame(&mut receiver).await?;
+    assert_eq!(close.reason, "The message was too big");
+
+    // more messages than allowed even in a burst
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    for _ in 0..30 {
+        sender.send(Message::Ping(Bytes::new())).await?;
+    }
+    let close = utils::receive_close_frame(&mut receiver).await?;
+    assert_eq!(close.reason, "Too many messages were sent");
+
+    // the disconnected clients do not count, two more may connect, but not a third one
+    let _first = utils::connect_to_server().await;
+    let _second = utils::connect_to_server().await;
+    let third = tokio_tungstenite::connect_async(format!("ws://localhost:{DEFAULT_PORT}")).await;
+    assert!(third.is_err());
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
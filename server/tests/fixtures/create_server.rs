@@ -4,7 +4,7 @@ use std::{
 };
 
 use crate::{fixtures::sample_questions::sample_questions, utils};
-use ::server::lobby::{run_server, Lobby};
+use ::server::lobby::{run_rooms, run_server, AttachSettings, Lobby};
 use actix::Addr;
 use common::{constants::DEFAULT_PORT, questions::QuestionSet};
 use rstest::fixture;
@@ -14,6 +14,12 @@ use rstest::fixture;
 #[must_use]
 #[fixture]
 pub fn create_server(sample_questions: QuestionSet) -> (JoinHandle<()>, Addr<Lobby>) {
+    start_server(Lobby::new(sample_questions))
+}
+
+/// Starts a server thread with the given lobby, for the tests which need to configure it.
+#[must_use]
+pub fn start_server(lobby: Lobby) -> (JoinHandle<()>, Addr<Lobby>) {
     assert!(
         utils::is_port_available(DEFAULT_PORT),
         "Port {DEFAULT_PORT} is not available"
@@ -25,7 +31,7 @@ pub fn create_server(sample_questions: QuestionSet) -> (JoinHandle<()>, Addr<Lob
         .expect("Failed to parse address");
 
     let server_thread = thread::spawn(move || {
-        run_server(tx, sample_questions, addr).expect("Failed to run server");
+        run_server(&tx, lobby, addr).expect("Failed to run server");
     });
 
     let server = rx.recv().expect("Failed to receive server address");
@@ -33,6 +39,33 @@ pub fn create_server(sample_questions: QuestionSet) -> (JoinHandle<()>, Addr<Lob
     (server_thread, server)
 }
 
+#[allow(dead_code)]
+/// Starts a server thread hosting a game in each of the lobbies,
+/// the teachers can attach to it with the attach settings.
+#[must_use]
+pub fn start_rooms(
+    lobbies: Vec<Lobby>,
+    attach: Option<AttachSettings>,
+) -> (JoinHandle<()>, Vec<Addr<Lobby>>) {
+    assert!(
+        utils::is_port_available(DEFAULT_PORT),
+        "Port {DEFAULT_PORT} is not available"
+    );
+
+    let (tx, rx) = mpsc::channel();
+    let addr = format!("0.0.0.0:{DEFAULT_PORT}")
+        .parse()
+        .expect("Failed to parse address");
+
+    let server_thread = thread::spawn(move || {
+        run_rooms(&tx, lobbies, addr, attach).expect("Failed to run server");
+    });
+
+    let servers = rx.recv().expect("Failed to receive server addresses");
+
+    (server_thread, servers)
+}
+
 /////////////// DO NOT DO THIS: ///////////////
 // #[must_use]
 // #[fixture]
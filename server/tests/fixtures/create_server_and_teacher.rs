@@ -19,12 +19,19 @@ pub fn create_server_and_teacher(
     create_server: (JoinHandle<()>, Addr<Lobby>),
 ) -> (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>) {
     let (server_thread, server) = create_server;
+    let (teacher_thread, teacher) = start_teacher(&server);
 
+    (server_thread, server, teacher_thread, teacher)
+}
+
+/// Starts a teacher thread registered in the given lobby.
+#[must_use]
+pub fn start_teacher(server: &Addr<Lobby>) -> (JoinHandle<()>, Addr<Teacher>) {
     let (tx, rx) = mpsc::channel();
 
     let server_address = server.clone();
     let teacher_thread = thread::spawn(move || {
-        run_teacher(server_address, tx, DEFAULT_QUIZ_NAME, Theme::default())
+        run_teacher(server_address, &tx, DEFAULT_QUIZ_NAME, Theme::default())
             .expect("Failed to run teacher");
     });
 
@@ -32,5 +39,5 @@ pub fn create_server_and_teacher(
 
     thread::sleep(std::time::Duration::from_millis(100));
 
-    (server_thread, server, teacher_thread, teacher)
+    (teacher_thread, teacher)
 }
@@ -2,6 +2,9 @@ pub mod create_server;
 pub mod create_server_and_teacher;
 pub mod sample_questions;
 
+#[allow(unused_imports)]
 pub use create_server::*;
+#[allow(unused_imports)]
 pub use create_server_and_teacher::*;
+#[allow(unused_imports)]
 pub use sample_questions::*;
@@ -0,0 +1,174 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{
+    path::Path,
+    sync::mpsc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use actix::Addr;
+use common::{
+    constants::{DEFAULT_PORT, DEFAULT_QUIZ_NAME, NICKNAME_ALREADY_TAKEN_MSG},
+    messages::network::{CanJoin, PlayerData, TryJoinRequest, TryJoinResponse},
+    questions::QuestionSet,
+    terminal::{highlight::Theme, messages::Stop},
+};
+use rstest::rstest;
+use server::{
+    lobby::{run_server, Lobby, LobbySnapshot, Phase},
+    messages::lobby::{self, StartQuestion, SwitchToLeaderboard},
+    teacher::{run_teacher, Teacher},
+};
+use uuid::Uuid;
+
+use crate::{fixtures::create_server::create_server, mocks::GetServerState};
+
+fn multiple_questions() -> QuestionSet {
+    QuestionSet::from_file(Path::new("../common/tests/files/ok_multiple.yaml"))
+        .expect("Failed to load sample questions")
+}
+
+fn start_teacher(server: &Addr<Lobby>) -> (JoinHandle<()>, Addr<Teacher>) {
+    let (tx, rx) = mpsc::channel();
+    let server = server.clone();
+    let teacher_thread = thread::spawn(move || {
+        run_teacher(server, &tx, DEFAULT_QUIZ_NAME, Theme::default())
+            .expect("Failed to run teacher");
+    });
+
+    let teacher = rx.recv().expect("Failed to receive teacher address");
+    thread::sleep(Duration::from_millis(100));
+
+    (teacher_thread, teacher)
+}
+
+/// Tries to join the server from a client with the given resume token, returns the uuid
+async fn try_join_with_token(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    resume_token: Option<&str>,
+) -> anyhow::Result<(Uuid, TryJoinResponse)> {
+    let id = Uuid::new_v4();
+    let request = TryJoinRequest {
+        resume_token: resume_token.map(str::to_owned),
+        ..TryJoinRequest::new(id)
+    };
+
+    let response = utils::try_join_server_with_request(sender, receiver, request).await?;
+    Ok((id, response))
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn game_can_be_resumed_from_snapshot(
+    #[with(multiple_questions())] create_server: (JoinHandle<()>, Addr<Lobby>),
+) -> anyhow::Result<()> {
+    let (server_thread, server) = create_server;
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // the client keeps its secret, so it can prove who it is after the server restarts
+    let resume_token = Uuid::new_v4().to_string();
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let (id, _) = try_join_with_token(&mut sender, &mut receiver, Some(&resume_token)).await?;
+    let (player, response) = utils::join_server(&mut sender, &mut receiver, id).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    server.send(StartQuestion).await??;
+    let question = utils::receive_next_question(&mut receiver).await?;
+
+    // the only player answers, so the question ends right away
+    utils::send_question_answer(&mut sender, &player, &question.question, 0, vec![1]).await?;
+    let _ = utils::receive_question_ended(&mut receiver).await?;
+
+    server.send(SwitchToLeaderboard).await??;
+    let _ = utils::receive_show_leaderboard(&mut receiver).await?;
+
+    let state = server.send(GetServerState).await?;
+    let points = state.game_results().summary[0].total_points;
+    assert!(points > 0);
+
+    let snapshot_file =
+        std::env::temp_dir().join(format!("clihoot-snapshot-{}.json", Uuid::new_v4()));
+    state.snapshot().write_to(&snapshot_file)?;
+
+    // the server dies
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+    drop((sender, receiver));
+
+    let snapshot = LobbySnapshot::load(&snapshot_file)?;
+    std::fs::remove_file(&snapshot_file)?;
+    assert_eq!(snapshot.phase, Phase::ShowingLeaderboard(0));
+    assert_eq!(snapshot.questions, state.questions);
+    assert_eq!(snapshot.results, state.results);
+
+    let (tx, rx) = mpsc::channel();
+    let addr = format!("0.0.0.0:{DEFAULT_PORT}").parse()?;
+    let server_thread = thread::spawn(move || {
+        run_server(&tx, Lobby::from_snapshot(snapshot), addr).expect("Failed to run server");
+    });
+    let server = rx.recv()?;
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // anybody else cannot take over the results by joining with the nickname first
+    for token in [None, Some("guessed")] {
+        let (mut sender, mut receiver) = utils::connect_to_server().await;
+        let (id, _) = try_join_with_token(&mut sender, &mut receiver, token).await?;
+        let (_, response) = utils::join_server_as(
+            &mut sender,
+            &mut receiver,
+            PlayerData {
+                uuid: id,
+                ..player.clone()
+            },
+        )
+        .await?;
+        assert_eq!(
+            response.can_join,
+            CanJoin::No(NICKNAME_ALREADY_TAKEN_MSG.to_owned())
+        );
+    }
+
+    // the player comes back with a new uuid, but the same nickname and from the same client
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let (id, _) = try_join_with_token(&mut sender, &mut receiver, Some(&resume_token)).await?;
+    let (_, response) = utils::join_server_as(
+        &mut sender,
+        &mut receiver,
+        PlayerData {
+            uuid: id,
+            ..player.clone()
+        },
+    )
+    .await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let state = server.send(GetServerState).await?;
+    assert_eq!(state.phase, Phase::ShowingLeaderboard(0));
+    assert!(state.resumed_players.is_empty());
+    assert_eq!(state.resume_tokens.get(&id), Some(&resume_token));
+
+    let results = state.game_results();
+    assert_eq!(results.summary.len(), 1);
+    assert_eq!(results.summary[0].player_uuid, id);
+    assert_eq!(results.summary[0].total_points, points);
+
+    // the game continues with the second question
+    server.send(StartQuestion).await??;
+    let question = utils::receive_next_question(&mut receiver).await?;
+    assert_eq!(question.question_index, 1);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
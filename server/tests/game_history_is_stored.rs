@@ -0,0 +1,145 @@
+use std::path::Path;
+
+use chrono::Utc;
+use common::questions::QuestionSet;
+use server::{
+    history::History,
+    lobby::{AnswerRow, GameResults, SummaryRow},
+};
+use uuid::Uuid;
+
+fn questions() -> QuestionSet {
+    QuestionSet::from_file(Path::new("../common/tests/files/ok_minimal.yaml"))
+        .expect("Failed to load sample questions")
+}
+
+fn answer(player: &SummaryRow, selected: &str, is_correct: bool, points: usize) -> AnswerRow {
+    AnswerRow {
+        question: 1,
+        question_text: questions()[0].text.clone(),
+        player_uuid: player.player_uuid,
+        nickname: player.nickname.clone(),
+        student_id: None,
+        student_name: None,
+        selected_choices: vec![selected.to_string()],
+        is_correct,
+        answer_order: Some(1),
+        answered_at: Some(Utc::now()),
+        points,
+    }
+}
+
+fn player(nickname: &str, rank: usize, points: usize, correct: usize) -> SummaryRow {
+    SummaryRow {
+        rank,
+        player_uuid: Uuid::new_v4(),
+        nickname: nickname.to_string(),
+        student_id: None,
+        student_name: None,
+        total_points: points,
+        correct_answers: correct,
+        answered_questions: 1,
+        left_game: false,
+    }
+}
+
+fn results(players: Vec<SummaryRow>, answers: Vec<AnswerRow>) -> GameResults {
+    GameResults {
+        quiz_name: questions().quiz_name,
+        exported_at: Utc::now(),
+        questions_asked: 1,
+        answers,
+        summary: players,
+        analysis: Vec::new(),
+    }
+}
+
+#[test]
+fn game_history_is_stored() -> anyhow::Result<()> {
+    let db = std::env::temp_dir().join(format!("clihoot-history-{}.sqlite", Uuid::new_v4()));
+
+    // alice joined with her ID from the roster
+    let alice = SummaryRow {
+        student_id: Some("xnovak42".to_string()),
+        ..player("alice", 1, 100, 1)
+    };
+    let bob = player("bob", 2, 0, 0);
+    let first_game = results(
+        vec![alice.clone(), bob.clone()],
+        vec![
+            answer(&alice, "42", true, 100),
+            answer(&bob, "sleep", false, 0),
+        ],
+    );
+
+    let carol = player("carol", 1, 0, 0);
+    let second_game = results(vec![carol.clone()], vec![answer(&carol, "food", false, 0)]);
+
+    let first_id = History::open(&db)?.save_game(&questions(), &first_game)?;
+    // the database is opened again for every game, the games must not overwrite each other
+    let second_id = History::open(&db)?.save_game(&questions(), &second_game)?;
+    assert_ne!(first_id, second_id);
+
+    let history = History::open(&db)?;
+
+    let sessions = history.sessions()?;
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].id, second_id);
+    assert_eq!(sessions[1].players_count, 2);
+    assert_eq!(sessions[1].questions_count, 1);
+
+    assert_eq!(history.players(first_id)?, first_game.summary);
+
+    let summaries = history.question_summaries(first_id)?;
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].question, 1);
+    assert_eq!(summaries[0].answered, 2);
+    assert_eq!(summaries[0].correct, 1);
+    assert!((summaries[0].average_points - 50.0).abs() < f64::EPSILON);
+
+    // the same question was asked in both games
+    let hardest = history.hardest_questions(10)?;
+    assert_eq!(hardest.len(), 1);
+    assert_eq!(hardest[0].sessions, 2);
+    assert_eq!(hardest[0].answers, 3);
+    assert_eq!(hardest[0].correct, 1);
+
+    assert!(history.session(second_id + 1)?.is_none());
+
+    std::fs::remove_file(&db)?;
+
+    Ok(())
+}
+
+#[test]
+fn old_history_gets_student_ids() -> anyhow::Result<()> {
+    let db = std::env::temp_dir().join(format!("clihoot-history-{}.sqlite", Uuid::new_v4()));
+
+    // the players table as it was before the roster
+    rusqlite::Connection::open(&db)?.execute_batch(
+        "CREATE TABLE players (
+            session_id INTEGER NOT NULL,
+            uuid TEXT NOT NULL,
+            nickname TEXT NOT NULL,
+            rank INTEGER NOT NULL,
+            total_points INTEGER NOT NULL,
+            correct_answers INTEGER NOT NULL,
+            answered_questions INTEGER NOT NULL,
+            left_game INTEGER NOT NULL,
+            PRIMARY KEY (session_id, uuid)
+        );",
+    )?;
+
+    let alice = SummaryRow {
+        student_id: Some("xnovak42".to_string()),
+        ..player("alice", 1, 100, 1)
+    };
+    let game = results(vec![alice.clone()], vec![answer(&alice, "42", true, 100)]);
+    let id = History::open(&db)?.save_game(&questions(), &game)?;
+
+    assert_eq!(History::open(&db)?.players(id)?, game.summary);
+
+    std::fs::remove_file(&db)?;
+
+    Ok(())
+}
@@ -0,0 +1,160 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{
+    sync::mpsc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use actix::Addr;
+use common::{
+    constants::{DEFAULT_PORT, DEFAULT_QUIZ_NAME},
+    messages::{ClientNetworkMessage, ServerNetworkMessage},
+    terminal::{highlight::Theme, messages::Stop},
+};
+use server::{
+    journal::{Journal, JournalEntry, JournalEvent},
+    lobby::{run_server, Lobby, Phase},
+    messages::lobby::{self, StartQuestion, StartReplay, SwitchToLeaderboard},
+    teacher::{run_teacher, Teacher},
+};
+use uuid::Uuid;
+
+use crate::mocks::GetServerState;
+
+fn start_server(lobby: Lobby) -> (JoinHandle<()>, Addr<Lobby>) {
+    assert!(
+        utils::is_port_available(DEFAULT_PORT),
+        "Port {DEFAULT_PORT} is not available"
+    );
+
+    let (tx, rx) = mpsc::channel();
+    let addr = format!("0.0.0.0:{DEFAULT_PORT}")
+        .parse()
+        .expect("Failed to parse address");
+    let server_thread = thread::spawn(move || {
+        run_server(&tx, lobby, addr).expect("Failed to run server");
+    });
+
+    (
+        server_thread,
+        rx.recv().expect("Failed to receive server address"),
+    )
+}
+
+fn start_teacher(server: &Addr<Lobby>) -> (JoinHandle<()>, Addr<Teacher>) {
+    let (tx, rx) = mpsc::channel();
+    let server = server.clone();
+    let teacher_thread = thread::spawn(move || {
+        run_teacher(server, &tx, DEFAULT_QUIZ_NAME, Theme::default())
+            .expect("Failed to run teacher");
+    });
+
+    let teacher = rx.recv().expect("Failed to receive teacher address");
+    thread::sleep(Duration::from_millis(100));
+
+    (teacher_thread, teacher)
+}
+
+#[tokio::test]
+async fn game_is_recorded_and_replayed() -> anyhow::Result<()> {
+    let journal_file =
+        std::env::temp_dir().join(format!("clihoot-journal-{}.jsonl", Uuid::new_v4()));
+
+    let questions = utils::sample_questions();
+    let journal = Journal::create(&journal_file, &questions)?;
+    let (server_thread, server) = start_server(Lobby::new(questions).with_journal(Some(journal)));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (mut sender, mut receiver, player) = utils::join_new_player().await?;
+
+    server.send(StartQuestion).await??;
+    let question = utils::receive_next_question(&mut receiver).await?;
+
+    utils::send_question_answer(&mut sender, &player, &question.question, 0, vec![1]).await?;
+    let _ = utils::receive_question_ended(&mut receiver).await?;
+
+    server.send(SwitchToLeaderboard).await??;
+    let _ = utils::receive_show_leaderboard(&mut receiver).await?;
+
+    let recorded = server.send(GetServerState).await?.game_results();
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+    drop((sender, receiver));
+
+    let entries = JournalEntry::read_all(&journal_file)?;
+    std::fs::remove_file(&journal_file)?;
+
+    assert!(matches!(entries[0].event, JournalEvent::GameStarted { .. }));
+    assert!(entries.windows(2).all(|pair| pair[0].at <= pair[1].at));
+    assert!(entries.iter().any(|entry| matches!(
+        &entry.event,
+        JournalEvent::FromClient {
+            player: Some(uuid),
+            message: ClientNetworkMessage::AnswerSelected(_),
+        } if *uuid == player.uuid
+    )));
+    assert!(entries.iter().any(|entry| matches!(
+        &entry.event,
+        JournalEvent::ToClient {
+            message: ServerNetworkMessage::JoinResponse(_),
+            ..
+        }
+    )));
+    assert!(entries.iter().any(|entry| matches!(
+        &entry.event,
+        JournalEvent::ToClient {
+            message: ServerNetworkMessage::NextQuestion(_),
+            ..
+        }
+    )));
+    assert!(entries
+        .iter()
+        .any(|entry| matches!(entry.event, JournalEvent::StartQuestion)));
+    assert!(entries
+        .iter()
+        .any(|entry| matches!(entry.event, JournalEvent::SwitchToLeaderboard)));
+
+    // the replayed game ends the same way
+    let JournalEvent::GameStarted { questions } = entries[0].event.clone() else {
+        unreachable!()
+    };
+    let (server_thread, server) = start_server(Lobby::new(questions));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    server.do_send(StartReplay {
+        entries,
+        speed: 10.0,
+    });
+
+    let mut state = server.send(GetServerState).await?;
+    for _ in 0..50 {
+        if state.phase == Phase::GameEnded {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        state = server.send(GetServerState).await?;
+    }
+    assert_eq!(state.phase, Phase::GameEnded);
+
+    let replayed = state.game_results();
+    assert_eq!(replayed.answers.len(), 1);
+    assert_eq!(replayed.answers[0].player_uuid, player.uuid);
+    assert_eq!(
+        replayed.answers[0].selected_choices,
+        recorded.answers[0].selected_choices
+    );
+    assert_eq!(replayed.summary, recorded.summary);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{
+    fs,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use actix::Addr;
+use futures_util::SinkExt;
+use rstest::rstest;
+use server::{
+    lobby::{Lobby, Phase},
+    messages::lobby::{self, ExportResults, StartQuestion, SwitchToLeaderboard},
+    Teacher,
+};
+use uuid::Uuid;
+
+use crate::{
+    fixtures::create_server_and_teacher::create_server_and_teacher, mocks::GetServerState,
+};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn game_results_can_be_exported(
+    create_server_and_teacher: (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>),
+) -> anyhow::Result<()> {
+    let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
+
+    let (mut sender1, mut receiver1, player1) = utils::join_new_player().await?;
+    let (mut sender2, mut receiver2, player2) = utils::join_new_player().await?;
+
+    // the first player is notified about the second one
+    let _ = utils::receive_players_delta(&mut receiver1).await?;
+
+    server.send(StartQuestion).await??;
+
+    let question = utils::receive_next_question(&mut receiver1).await?;
+    let _ = utils::receive_next_question(&mut receiver2).await?;
+
+    // the first player answers correctly, the second one does not
+    utils::send_question_answer(&mut sender1, &player1, &question.question, 0, vec![1]).await?;
+    thread::sleep(Duration::from_millis(100));
+    utils::send_question_answer(&mut sender2, &player2, &question.question, 0, vec![0]).await?;
+    thread::sleep(Duration::from_millis(100));
+
+    // the second player leaves, but their results must not be lost
+    sender2.close().await?;
+    thread::sleep(Duration::from_millis(100));
+
+    server.send(SwitchToLeaderboard).await??;
+
+    let state = server.send(GetServerState).await?;
+    assert_eq!(state.phase, Phase::GameEnded);
+
+    let results = state.game_results();
+    assert_eq!(results.questions_asked, 1);
+    assert_eq!(results.answers.len(), 2);
+
+    let answer1 = &results.answers[0];
+    assert_eq!(answer1.nickname, player1.nickname);
+    assert_eq!(answer1.selected_choices, vec!["42".to_string()]);
+    assert!(answer1.is_correct);
+    assert_eq!(answer1.answer_order, Some(1));
+    assert!(answer1.points > 0);
+
+    let answer2 = &results.answers[1];
+    assert_eq!(answer2.nickname, player2.nickname);
+    assert_eq!(answer2.selected_choices, vec!["sleep".to_string()]);
+    assert!(!answer2.is_correct);
+    assert_eq!(answer2.answer_order, Some(2));
+    assert_eq!(answer2.points, 0);
+
+    assert_eq!(results.summary.len(), 2);
+    assert_eq!(results.summary[0].player_uuid, player1.uuid);
+    assert_eq!(results.summary[0].rank, 1);
+    assert_eq!(results.summary[0].total_points, answer1.points);
+    assert_eq!(results.summary[0].correct_answers, 1);
+    assert!(!results.summary[0].left_game);
+    assert_eq!(results.summary[1].player_uuid, player2.uuid);
+    assert_eq!(results.summary[1].rank, 2);
+    assert!(results.summary[1].left_game);
+
+    // one player in each of the extreme groups
+    assert_eq!(results.analysis.len(), 1);
+    let analysis = &results.analysis[0];
+    assert_eq!(analysis.answered, 2);
+    assert_eq!(analysis.difficulty, Some(0.5));
+    assert_eq!(analysis.discrimination, Some(1.0));
+    assert!(analysis.mean_response_time.is_some());
+
+    let correct_choice = &analysis.choices[1];
+    assert!(correct_choice.is_correct);
+    assert_eq!(
+        (
+            correct_choice.picked_total,
+            correct_choice.picked_by_upper,
+            correct_choice.picked_by_lower
+        ),
+        (1, 1, 0)
+    );
+    let distractor = &analysis.choices[0];
+    assert!(!distractor.is_correct);
+    assert_eq!(
+        (
+            distractor.picked_total,
+            distractor.picked_by_upper,
+            distractor.picked_by_lower
+        ),
+        (1, 0, 1)
+    );
+
+    // the server in the tests has no results directory, so it cannot export on its own
+    assert!(server.send(ExportResults).await?.is_err());
+
+    let dir = std::env::temp_dir().join(format!("clihoot-results-{}", Uuid::new_v4()));
+    let paths = results.write_to_dir(&dir)?;
+    assert_eq!(paths.len(), 4);
+
+    let answers_csv = fs::read_to_string(&paths[0])?;
+    assert_eq!(answers_csv.lines().count(), 3); // header + 2 answers
+    assert!(answers_csv.starts_with("question,question_text,player_uuid,nickname"));
+
+    let summary_csv = fs::read_to_string(&paths[1])?;
+    assert_eq!(summary_csv.lines().count(), 3);
+
+    let analysis_csv = fs::read_to_string(&paths[2])?;
+    assert_eq!(analysis_csv.lines().count(), 5); // header + 4 choices
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&paths[3])?)?;
+    assert_eq!(json["answers"].as_array().map(Vec::len), Some(2));
+    assert_eq!(json["summary"][0]["rank"], 1);
+    assert_eq!(json["analysis"][0]["difficulty"], 0.5);
+
+    fs::remove_dir_all(&dir)?;
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(common::terminal::messages::Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
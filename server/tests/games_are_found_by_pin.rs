@@ -0,0 +1,111 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use anyhow::bail;
+use common::{
+    constants::{PIN_REQUIRED_MSG, UNKNOWN_PIN_MSG},
+    messages::{
+        network::{CanJoin, TryJoinRequest, TryJoinResponse},
+        ClientNetworkMessage, ServerNetworkMessage,
+    },
+    terminal::messages::Stop,
+};
+use futures_util::SinkExt;
+use rstest::rstest;
+use server::{lobby::Lobby, messages::lobby};
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::{
+    fixtures::{create_server::start_rooms, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+async fn try_join_with_pin(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    uuid: Uuid,
+    pin: Option<&str>,
+) -> anyhow::Result<TryJoinResponse> {
+    let request = ClientNetworkMessage::TryJoinRequest(TryJoinRequest {
+        pin: pin.map(ToOwned::to_owned),
+        ..TryJoinRequest::new(uuid)
+    });
+    sender
+        .send(Message::text(serde_json::to_string(&request)?))
+        .await?;
+
+    let ServerNetworkMessage::TryJoinResponse(response) =
+        utils::receive_server_network_msg(receiver).await?
+    else {
+        bail!("Expected TryJoinResponse");
+    };
+
+    Ok(response)
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn games_are_found_by_pin() -> anyhow::Result<()> {
+    let (server_thread, servers) = start_rooms(
+        vec![
+            Lobby::new(utils::sample_questions()).with_pin(Some("1111".to_owned())),
+            Lobby::new(utils::sample_questions()).with_pin(Some("2222".to_owned())),
+        ],
+        None,
+    );
+    let (teacher_thread1, teacher1) = start_teacher(&servers[0]);
+    let (teacher_thread2, teacher2) = start_teacher(&servers[1]);
+
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let uuid = Uuid::new_v4();
+
+    // with more games, the PIN is needed
+    let response = try_join_with_pin(&mut sender, &mut receiver, uuid, None).await?;
+    assert_eq!(response.can_join, CanJoin::No(PIN_REQUIRED_MSG.to_owned()));
+
+    // the client can try again on the same connection
+    let response = try_join_with_pin(&mut sender, &mut receiver, uuid, Some("3333")).await?;
+    assert_eq!(response.can_join, CanJoin::No(UNKNOWN_PIN_MSG.to_owned()));
+
+    let response = try_join_with_pin(&mut sender, &mut receiver, uuid, Some("2222")).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let (_, response) = utils::join_server(&mut sender, &mut receiver, uuid).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    // the player is only in the second game
+    let state = servers[1].send(GetServerState).await?;
+    assert!(state.joined_players.contains_key(&uuid));
+    let state = servers[0].send(GetServerState).await?;
+    assert!(state.joined_players.is_empty());
+    assert!(state.waiting_players.is_empty());
+
+    // the games go on independently
+    servers[0].send(lobby::StartQuestion).await??;
+    let state = servers[1].send(GetServerState).await?;
+    assert_eq!(state.phase, server::lobby::Phase::WaitingForPlayers);
+
+    // stopping one game leaves the others running
+    servers[0].send(lobby::HardStop).await?;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!servers[0].connected());
+    assert!(!server_thread.is_finished());
+    let state = servers[1].send(GetServerState).await?;
+    assert!(state.joined_players.contains_key(&uuid));
+
+    // and the server stops with the last one
+    servers[1].send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher1.send(Stop).await??;
+    teacher_thread1.join().expect("Teacher thread panicked");
+    teacher2.send(Stop).await??;
+    teacher_thread2.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{collections::HashMap, thread::JoinHandle, time::Duration};
+
+use actix::Addr;
+use common::{
+    constants::DEFAULT_GOODBYE_MESSAGE,
+    messages::{network::AnswerSelected, ClientNetworkMessage},
+};
+use futures_util::SinkExt;
+use rstest::rstest;
+use server::{
+    lobby::Lobby,
+    messages::lobby::{self, StartQuestion},
+    Teacher,
+};
+use tungstenite::{
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Message,
+};
+use uuid::Uuid;
+
+use crate::{
+    fixtures::create_server_and_teacher::create_server_and_teacher, mocks::GetServerState,
+};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn invalid_answers_are_rejected(
+    create_server_and_teacher: (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>),
+) -> anyhow::Result<()> {
+    let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
+
+    let (mut sender1, mut receiver1, player1) = utils::join_new_player().await?;
+    let (mut sender2, mut receiver2, player2) = utils::join_new_player().await?;
+    let _ = utils::receive_players_delta(&mut receiver1).await?;
+
+    server.send(StartQuestion).await??;
+    let question = utils::receive_next_question(&mut receiver1).await?;
+    let _ = utils::receive_next_question(&mut receiver2).await?;
+
+    // the first player selects something which is not a choice of the question
+    let answer = ClientNetworkMessage::AnswerSelected(AnswerSelected {
+        player_uuid: player1.uuid,
+        question_index: 0,
+        answers: [Uuid::new_v4()].into(),
+    });
+    sender1
+        .send(Message::text(serde_json::to_string(&answer)?))
+        .await?;
+
+    // the second one selects more answers than there are choices
+    let choices = question.question.choices.len();
+    let answer = ClientNetworkMessage::AnswerSelected(AnswerSelected {
+        player_uuid: player2.uuid,
+        question_index: 0,
+        answers: (0..=choices).map(|_| Uuid::new_v4()).collect(),
+    });
+    sender2
+        .send(Message::text(serde_json::to_string(&answer)?))
+        .await?;
+
+    let goodbye = CloseFrame {
+        code: CloseCode::Normal,
+        reason: DEFAULT_GOODBYE_MESSAGE.into(),
+    };
+    assert_eq!(utils::receive_close_frame(&mut receiver1).await?, goodbye);
+    assert_eq!(utils::receive_close_frame(&mut receiver2).await?, goodbye);
+
+    // neither of the answers got into the results
+    let state = server.send(GetServerState).await?;
+    assert!(state.results.get(&0).is_none_or(HashMap::is_empty));
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(common::terminal::messages::Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
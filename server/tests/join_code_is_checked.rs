@@ -0,0 +1,140 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use anyhow::bail;
+use common::{
+    constants::{JOIN_CODE_REQUIRED_MSG, TOO_MANY_WRONG_JOIN_CODES_MSG, WRONG_JOIN_CODE_MSG},
+    messages::{
+        network::{CanJoin, TryJoinRequest, TryJoinResponse},
+        ClientNetworkMessage, ServerNetworkMessage,
+    },
+    terminal::messages::Stop,
+};
+use futures_util::SinkExt;
+use rstest::rstest;
+use server::{
+    lobby::{Lobby, FREE_WRONG_JOIN_CODES},
+    messages::lobby,
+};
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::{
+    fixtures::{create_server::start_server, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+async fn try_join_with_code(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    uuid: Uuid,
+    join_code: Option<&str>,
+) -> anyhow::Result<TryJoinResponse> {
+    let request = ClientNetworkMessage::TryJoinRequest(TryJoinRequest {
+        join_code: join_code.map(ToOwned::to_owned),
+        ..TryJoinRequest::new(uuid)
+    });
+    sender
+        .send(Message::text(serde_json::to_string(&request)?))
+        .await?;
+
+    let ServerNetworkMessage::TryJoinResponse(response) =
+        utils::receive_server_network_msg(receiver).await?
+    else {
+        bail!("Expected TryJoinResponse");
+    };
+
+    Ok(response)
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn join_code_is_checked() -> anyhow::Result<()> {
+    let (server_thread, server) = start_server(
+        Lobby::new(utils::sample_questions()).with_join_code(Some("KV7Q2".to_owned())),
+    );
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let uuid = Uuid::new_v4();
+
+    let response = try_join_with_code(&mut sender, &mut receiver, uuid, None).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(JOIN_CODE_REQUIRED_MSG.to_owned())
+    );
+
+    // the client can try again on the same connection
+    let response = try_join_with_code(&mut sender, &mut receiver, uuid, Some("AAAAA")).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(WRONG_JOIN_CODE_MSG.to_owned())
+    );
+
+    // the case does not matter
+    let response = try_join_with_code(&mut sender, &mut receiver, uuid, Some(" kv7q2 ")).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let (_, response) = utils::join_server(&mut sender, &mut receiver, uuid).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.joined_players.contains_key(&uuid));
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn wrong_join_codes_are_throttled() -> anyhow::Result<()> {
+    let (server_thread, server) = start_server(
+        Lobby::new(utils::sample_questions()).with_join_code(Some("KV7Q2".to_owned())),
+    );
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let uuid = Uuid::new_v4();
+
+    for _ in 0..FREE_WRONG_JOIN_CODES {
+        let response = try_join_with_code(&mut sender, &mut receiver, uuid, Some("AAAAA")).await?;
+        assert_eq!(
+            response.can_join,
+            CanJoin::No(WRONG_JOIN_CODE_MSG.to_owned())
+        );
+    }
+
+    // even the right code has to wait, also on a new connection from the same address
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let response = try_join_with_code(&mut sender, &mut receiver, uuid, Some("KV7Q2")).await?;
+    let CanJoin::No(reason) = response.can_join else {
+        bail!("The address should have been blocked");
+    };
+    assert!(reason.starts_with(TOO_MANY_WRONG_JOIN_CODES_MSG));
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.waiting_players.is_empty());
+
+    // the block is short after the first few codes
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let response = try_join_with_code(&mut sender, &mut receiver, uuid, Some("KV7Q2")).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
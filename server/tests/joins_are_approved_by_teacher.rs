@@ -0,0 +1,233 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use anyhow::bail;
+use common::{
+    constants::{APPROVAL_CLOSED_MSG, JOIN_NOT_APPROVED_MSG},
+    messages::{
+        network::{
+            CanJoin, JoinRequest, JoinResponse, PlayerData, TryJoinRequest, WaitingForApproval,
+        },
+        ClientNetworkMessage, ServerNetworkMessage,
+    },
+    terminal::messages::Stop,
+};
+use futures_util::SinkExt;
+use rand::random;
+use ratatui::style::Color;
+use rstest::rstest;
+use server::{
+    lobby::Lobby,
+    messages::lobby::{self, DecideJoins},
+};
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::{
+    fixtures::{create_server::start_server, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+/// Connects a new player and sends its join request, returns the first answer of the server
+async fn ask_to_join(
+) -> anyhow::Result<(utils::Sender, utils::Receiver, Uuid, ServerNetworkMessage)> {
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let (uuid, response) = utils::try_join_server(&mut sender, &mut receiver).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let request = ClientNetworkMessage::JoinRequest(JoinRequest {
+        player_data: PlayerData {
+            color: Color::Rgb(random(), random(), random()),
+            color_index: None,
+            nickname: Uuid::new_v4().to_string(),
+            uuid,
+            avatar: None,
+        },
+        student_id: None,
+    });
+    sender
+        .send(Message::text(serde_json::to_string(&request)?))
+        .await?;
+
+    let answer = utils::receive_server_network_msg(&mut receiver).await?;
+    Ok((sender, receiver, uuid, answer))
+}
+
+async fn receive_join_response(receiver: &mut utils::Receiver) -> anyhow::Result<JoinResponse> {
+    let ServerNetworkMessage::JoinResponse(response) =
+        utils::receive_server_network_msg(receiver).await?
+    else {
+        bail!("Expected JoinResponse");
+    };
+
+    Ok(response)
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn players_join_when_teacher_lets_them_in() -> anyhow::Result<()> {
+    let (server_thread, server) =
+        start_server(Lobby::new(utils::sample_questions()).with_approve_joins(true));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (_sender1, mut receiver1, uuid1, answer) = ask_to_join().await?;
+    let ServerNetworkMessage::WaitingForApproval(waiting) = answer else {
+        bail!("Expected WaitingForApproval");
+    };
+    assert_eq!(waiting, WaitingForApproval { uuid: uuid1 });
+
+    let (_sender2, mut receiver2, uuid2, answer) = ask_to_join().await?;
+    assert!(matches!(
+        answer,
+        ServerNetworkMessage::WaitingForApproval(_)
+    ));
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.joined_players.is_empty());
+    assert_eq!(
+        state
+            .get_awaiting_approval()
+            .iter()
+            .map(|player| player.uuid)
+            .collect::<Vec<_>>(),
+        vec![uuid1, uuid2]
+    );
+
+    server
+        .send(DecideJoins {
+            players: vec![uuid1],
+            approved: true,
+        })
+        .await?;
+    let response = receive_join_response(&mut receiver1).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+    assert!(response.players.iter().any(|player| player.uuid == uuid1));
+
+    server
+        .send(DecideJoins {
+            players: vec![uuid2],
+            approved: false,
+        })
+        .await?;
+    let response = receive_join_response(&mut receiver2).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(JOIN_NOT_APPROVED_MSG.to_owned())
+    );
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.joined_players.contains_key(&uuid1));
+    assert!(!state.joined_players.contains_key(&uuid2));
+    assert!(state.awaiting_approval.is_empty());
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn waiting_players_are_refused_when_game_starts() -> anyhow::Result<()> {
+    let (server_thread, server) =
+        start_server(Lobby::new(utils::sample_questions()).with_approve_joins(true));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (_sender, mut receiver, _, answer) = ask_to_join().await?;
+    assert!(matches!(
+        answer,
+        ServerNetworkMessage::WaitingForApproval(_)
+    ));
+
+    server.send(lobby::StartQuestion).await??;
+
+    let response = receive_join_response(&mut receiver).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(APPROVAL_CLOSED_MSG.to_owned())
+    );
+
+    // nobody is let in once the game runs
+    let (_sender, _receiver, _, answer) = ask_to_join().await?;
+    let ServerNetworkMessage::JoinResponse(response) = answer else {
+        bail!("Expected JoinResponse");
+    };
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(APPROVAL_CLOSED_MSG.to_owned())
+    );
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.joined_players.is_empty());
+    assert!(state.awaiting_approval.is_empty());
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn approved_player_reconnects_after_game_starts() -> anyhow::Result<()> {
+    let (server_thread, server) =
+        start_server(Lobby::new(utils::sample_questions()).with_approve_joins(true));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (sender, mut receiver, uuid, answer) = ask_to_join().await?;
+    assert!(matches!(
+        answer,
+        ServerNetworkMessage::WaitingForApproval(_)
+    ));
+    server
+        .send(DecideJoins {
+            players: vec![uuid],
+            approved: true,
+        })
+        .await?;
+    let response = receive_join_response(&mut receiver).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    server.send(lobby::StartQuestion).await??;
+    utils::receive_next_question(&mut receiver).await?;
+
+    // the player loses the connection during the first question
+    drop(sender);
+    drop(receiver);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let state = server.send(GetServerState).await?;
+    assert!(!state.joined_players.contains_key(&uuid));
+
+    // and comes back without asking the teacher again
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let response =
+        utils::try_join_server_with_request(&mut sender, &mut receiver, TryJoinRequest::new(uuid))
+            .await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+    let (_, response) = utils::join_server(&mut sender, &mut receiver, uuid).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.joined_players.contains_key(&uuid));
+    assert!(state.awaiting_approval.is_empty());
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -7,7 +7,10 @@ use std::{thread::JoinHandle, time::Duration};
 use actix::Addr;
 use common::{
     constants::{DEFAULT_QUIZ_NAME, LOBBY_LOCKED_MSG},
-    messages::network::{CanJoin, TryJoinResponse},
+    messages::{
+        network::{CanJoin, TryJoinResponse},
+        protocol::{Capability, ProtocolVersion},
+    },
 };
 use rstest::rstest;
 use server::{
@@ -35,6 +38,10 @@ async fn lobby_locked_client_cannot_connect(
             can_join: CanJoin::No(LOBBY_LOCKED_MSG.to_string()),
             uuid: id,
             quiz_name: DEFAULT_QUIZ_NAME.to_string(),
+            custom_syntaxes: vec![],
+            protocol: ProtocolVersion::CURRENT,
+            capabilities: Capability::supported(),
+            student_id_required: false,
         }
     );
 
@@ -0,0 +1,139 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{thread::JoinHandle, time::Duration};
+
+use actix::Addr;
+use anyhow::bail;
+use common::messages::{
+    encoding::{decode, Encoding},
+    network::{AnswerSelected, CanJoin, JoinRequest, PlayerData, TryJoinRequest},
+    ClientNetworkMessage, ServerNetworkMessage,
+};
+use futures_util::SinkExt;
+use rand::random;
+use ratatui::style::Color;
+use rstest::rstest;
+use server::{
+    lobby::Lobby,
+    messages::lobby::{self, StartQuestion},
+    Teacher,
+};
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::{
+    fixtures::create_server_and_teacher::create_server_and_teacher, mocks::GetServerState,
+};
+
+/// Joins with the given request, the `JoinRequest` is sent in the given encoding,
+/// returns the frame with the `JoinResponse`
+async fn join(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    request: TryJoinRequest,
+    encoding: Encoding,
+) -> anyhow::Result<(PlayerData, Message)> {
+    let player = PlayerData {
+        uuid: request.uuid,
+        nickname: request.uuid.to_string()[..8].to_string(),
+        color: Color::Rgb(random(), random(), random()),
+        color_index: None,
+        avatar: None,
+    };
+
+    // the try join request and its response are always JSON
+    let request = ClientNetworkMessage::TryJoinRequest(request);
+    sender.send(Encoding::Json.encode(&request)?).await?;
+
+    let frame = utils::receive_frame(receiver).await?;
+    assert!(matches!(frame, Message::Text(_)));
+    let ServerNetworkMessage::TryJoinResponse(response) = decode(&frame)? else {
+        bail!("Expected TryJoinResponse");
+    };
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let request = ClientNetworkMessage::JoinRequest(JoinRequest {
+        player_data: player.clone(),
+        student_id: None,
+    });
+    sender.send(encoding.encode(&request)?).await?;
+
+    Ok((player, utils::receive_frame(receiver).await?))
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn messages_are_binary_when_negotiated(
+    create_server_and_teacher: (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>),
+) -> anyhow::Result<()> {
+    let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
+
+    // this client can use MessagePack, and so it does
+    let (mut sender1, mut receiver1) = utils::connect_to_server().await;
+    let request = TryJoinRequest::new(Uuid::new_v4());
+    let (player1, frame) =
+        join(&mut sender1, &mut receiver1, request, Encoding::MessagePack).await?;
+    assert!(matches!(frame, Message::Binary(_)));
+    let ServerNetworkMessage::JoinResponse(response) = decode(&frame)? else {
+        bail!("Expected JoinResponse");
+    };
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    // this one announces no capabilities, it gets only JSON
+    let (mut sender2, mut receiver2) = utils::connect_to_server().await;
+    let request = TryJoinRequest {
+        capabilities: vec![],
+        ..TryJoinRequest::new(Uuid::new_v4())
+    };
+    let (_, frame) = join(&mut sender2, &mut receiver2, request, Encoding::Json).await?;
+    assert!(matches!(frame, Message::Text(_)));
+    let ServerNetworkMessage::JoinResponse(response) = decode(&frame)? else {
+        bail!("Expected JoinResponse");
+    };
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    // the first player is notified about the second one
+    let frame = utils::receive_frame(&mut receiver1).await?;
+    assert!(matches!(frame, Message::Binary(_)));
+    let ServerNetworkMessage::PlayersDelta(delta) = decode(&frame)? else {
+        bail!("Expected PlayersDelta");
+    };
+    assert_eq!(delta.joined.len(), 1);
+
+    server.send(StartQuestion).await??;
+
+    let frame = utils::receive_frame(&mut receiver1).await?;
+    assert!(matches!(frame, Message::Binary(_)));
+    let ServerNetworkMessage::NextQuestion(question) = decode(&frame)? else {
+        bail!("Expected NextQuestion");
+    };
+    assert!(matches!(
+        utils::receive_frame(&mut receiver2).await?,
+        Message::Text(_)
+    ));
+
+    // the server understands the binary answer
+    let answer = ClientNetworkMessage::AnswerSelected(AnswerSelected {
+        player_uuid: player1.uuid,
+        question_index: 0,
+        answers: [question.question.choices[1].id].into(),
+    });
+    sender1.send(Encoding::MessagePack.encode(&answer)?).await?;
+
+    let _ = utils::receive_question_update(&mut receiver1).await?;
+    let _ = utils::receive_question_update(&mut receiver2).await?;
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.results[&0].contains_key(&player1.uuid));
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(common::terminal::messages::Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
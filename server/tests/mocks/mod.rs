@@ -1,3 +1,4 @@
 mod get_server_state_handler;
 
+#[allow(unused_imports)]
 pub use get_server_state_handler::*;
@@ -0,0 +1,68 @@
+use common::messages::{
+    encoding::decode,
+    network::{QuestionUpdate, TeacherDisconnected},
+    ServerNetworkMessage,
+};
+use server::websocket::{Coalesce, Outbox, OutboxFull, OutboxMetrics};
+use tungstenite::Message;
+
+fn question_update(question_index: usize, players_answered_count: usize) -> ServerNetworkMessage {
+    ServerNetworkMessage::QuestionUpdate(QuestionUpdate {
+        question_index,
+        players_answered_count,
+    })
+}
+
+fn push(outbox: &Outbox, msg: &ServerNetworkMessage) -> Result<(), OutboxFull> {
+    let frame = Message::text(serde_json::to_string(msg).expect("Failed to serialize"));
+    outbox.push(frame, Coalesce::of(msg))
+}
+
+#[tokio::test]
+async fn outbox_coalesces_superseded_messages() -> anyhow::Result<()> {
+    let metrics = OutboxMetrics::default();
+    let outbox = Outbox::new(3, metrics.clone());
+
+    // only the latest update of the question is kept, in the place of the first one
+    push(&outbox, &question_update(0, 1))?;
+    push(
+        &outbox,
+        &ServerNetworkMessage::TeacherDisconnected(TeacherDisconnected {}),
+    )?;
+    push(&outbox, &question_update(0, 2))?;
+    push(&outbox, &question_update(0, 3))?;
+    push(&outbox, &question_update(1, 1))?;
+
+    assert_eq!(outbox.len(), 3);
+    assert_eq!(metrics.coalesced(), 2);
+
+    // the queue is full, another message which cannot be coalesced does not fit
+    assert_eq!(push(&outbox, &question_update(2, 1)), Err(OutboxFull));
+    push(&outbox, &question_update(1, 2))?;
+
+    // the close frame always fits
+    outbox.close(Some(Message::Close(None)));
+
+    let mut frames = Vec::new();
+    while let Some(frame) = outbox.next().await {
+        frames.push(frame);
+    }
+
+    assert_eq!(frames.len(), 4);
+
+    let ServerNetworkMessage::QuestionUpdate(first) = decode(&frames[0])? else {
+        anyhow::bail!("Expected QuestionUpdate");
+    };
+    assert_eq!(first.players_answered_count, 3);
+
+    let ServerNetworkMessage::QuestionUpdate(third) = decode(&frames[2])? else {
+        anyhow::bail!("Expected QuestionUpdate");
+    };
+    assert_eq!(third.question_index, 1);
+    assert_eq!(third.players_answered_count, 2);
+
+    assert!(matches!(frames[3], Message::Close(_)));
+    assert_eq!(metrics.peak_len(), 3);
+
+    Ok(())
+}
@@ -26,15 +26,17 @@ async fn player_disconnects_and_server_sends_notices(
 
     // create scope to drop the sender automatically at the end
     {
-        // join and disconnect a second player
-        _ = utils::join_new_player().await?;
-    }
+        // join a second player
+        let (_snd_sender, _snd_receiver, snd_data) = utils::join_new_player().await?;
 
-    let msg = utils::receive_players_update(&mut fst_receiver).await?;
-    assert_eq!(msg.players.len(), 2);
+        let msg = utils::receive_players_delta(&mut fst_receiver).await?;
+        assert_eq!(msg.joined, vec![snd_data]);
+    }
 
-    let msg = utils::receive_players_update(&mut fst_receiver).await?;
-    assert_eq!(msg.players.len(), 1);
+    // the second player disconnected
+    let msg = utils::receive_players_delta(&mut fst_receiver).await?;
+    assert!(msg.joined.is_empty());
+    assert_eq!(msg.left.len(), 1);
 
     let state = server.send(GetServerState).await?;
 
@@ -0,0 +1,58 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use common::{messages::network::PlayersDelta, terminal::messages::Stop};
+use rstest::rstest;
+use server::{lobby::Lobby, messages::lobby};
+
+use crate::fixtures::{create_server::start_server, create_server_and_teacher::start_teacher};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn players_delta_is_coalesced() -> anyhow::Result<()> {
+    // the changes are collected for 2 seconds, the whole list is sent every 4 seconds
+    let lobby = Lobby::new(utils::sample_questions())
+        .with_players_update_timing(Duration::from_secs(2), Duration::from_secs(4));
+    let (server_thread, server) = start_server(lobby);
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // a burst of players joining
+    let (_fst_sender, mut fst_receiver, fst_data) = utils::join_new_player().await?;
+    let (_snd_sender, mut snd_receiver, snd_data) = utils::join_new_player().await?;
+    let (_thd_sender, _thd_receiver, thd_data) = utils::join_new_player().await?;
+
+    // is sent as a single message, without the player themselves
+    let delta = utils::receive_players_delta(&mut fst_receiver).await?;
+    assert_eq!(
+        delta,
+        PlayersDelta {
+            joined: vec![snd_data.clone(), thd_data.clone()],
+            left: vec![],
+        }
+    );
+
+    let delta = utils::receive_players_delta(&mut snd_receiver).await?;
+    assert_eq!(
+        delta,
+        PlayersDelta {
+            joined: vec![fst_data.clone(), thd_data.clone()],
+            left: vec![],
+        }
+    );
+
+    // then everybody gets the whole list, in case they missed something
+    let update = utils::receive_players_update(&mut fst_receiver).await?;
+    assert_eq!(update.players, vec![fst_data, snd_data, thd_data]);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{thread::JoinHandle, time::Duration};
+
+use actix::Addr;
+use common::{
+    constants::{AVATARS, COLOR_AND_AVATAR_TAKEN_MSG, INVALID_AVATAR_MSG},
+    messages::network::{CanJoin, PlayerData},
+};
+use ratatui::style::Color;
+use rstest::rstest;
+use server::{
+    messages::lobby::{self},
+    Lobby, Teacher,
+};
+use uuid::Uuid;
+
+use crate::{
+    fixtures::create_server_and_teacher::create_server_and_teacher,
+    mocks::GetServerState,
+    utils::{Receiver, Sender},
+};
+
+async fn try_to_join(
+    connections: &mut Vec<(Sender, Receiver)>,
+    color: Color,
+    avatar: Option<&str>,
+) -> anyhow::Result<CanJoin> {
+    try_to_join_with_index(connections, color, None, avatar).await
+}
+
+// the connections are kept, so the players stay in the lobby
+async fn try_to_join_with_index(
+    connections: &mut Vec<(Sender, Receiver)>,
+    color: Color,
+    color_index: Option<usize>,
+    avatar: Option<&str>,
+) -> anyhow::Result<CanJoin> {
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let (id, _) = utils::try_join_server(&mut sender, &mut receiver).await?;
+
+    let player_data = PlayerData {
+        uuid: id,
+        nickname: Uuid::new_v4().to_string(),
+        color,
+        color_index,
+        avatar: avatar.map(str::to_owned),
+    };
+    let (_, msg) = utils::join_server_as(&mut sender, &mut receiver, player_data).await?;
+
+    connections.push((sender, receiver));
+
+    Ok(msg.can_join)
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn players_must_look_different(
+    create_server_and_teacher: (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>),
+) -> anyhow::Result<()> {
+    let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
+    let mut connections = Vec::new();
+    let conns = &mut connections;
+
+    assert_eq!(
+        try_to_join(conns, Color::Red, Some(AVATARS[0])).await?,
+        CanJoin::Yes
+    );
+    assert_eq!(
+        try_to_join(conns, Color::Red, Some(AVATARS[0])).await?,
+        CanJoin::No(COLOR_AND_AVATAR_TAKEN_MSG.to_string())
+    );
+
+    // the same color with another avatar (or without one) is fine
+    assert_eq!(
+        try_to_join(conns, Color::Red, Some(AVATARS[1])).await?,
+        CanJoin::Yes
+    );
+    assert_eq!(try_to_join(conns, Color::Red, None).await?, CanJoin::Yes);
+    assert_eq!(
+        try_to_join(conns, Color::Red, None).await?,
+        CanJoin::No(COLOR_AND_AVATAR_TAKEN_MSG.to_string())
+    );
+
+    assert_eq!(
+        try_to_join(conns, Color::Blue, Some("not an avatar")).await?,
+        CanJoin::No(INVALID_AVATAR_MSG.to_string())
+    );
+
+    // the colors from the palette are told apart by their index, whatever the theme
+    // of the student who chose them
+    assert_eq!(
+        try_to_join_with_index(conns, Color::Green, Some(2), Some(AVATARS[0])).await?,
+        CanJoin::Yes
+    );
+    assert_eq!(
+        try_to_join_with_index(conns, Color::Rgb(0, 120, 0), Some(2), Some(AVATARS[0])).await?,
+        CanJoin::No(COLOR_AND_AVATAR_TAKEN_MSG.to_string())
+    );
+
+    let state = server.send(GetServerState).await?;
+    assert_eq!(state.joined_players.len(), 4);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(common::terminal::messages::Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{thread::JoinHandle, time::Duration};
+
+use actix::Addr;
+use common::messages::network::ChoiceReport;
+use rstest::rstest;
+use server::{
+    lobby::Lobby,
+    messages::lobby::{self, StartQuestion, SwitchToLeaderboard},
+    Teacher,
+};
+
+use crate::fixtures::create_server_and_teacher::create_server_and_teacher;
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn players_receive_personal_reports(
+    create_server_and_teacher: (JoinHandle<()>, Addr<Lobby>, JoinHandle<()>, Addr<Teacher>),
+) -> anyhow::Result<()> {
+    let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
+
+    let (mut sender1, mut receiver1, player1) = utils::join_new_player().await?;
+    let (_sender2, mut receiver2, player2) = utils::join_new_player().await?;
+
+    // the first player is notified about the second one
+    let _ = utils::receive_players_delta(&mut receiver1).await?;
+
+    server.send(StartQuestion).await??;
+
+    let question = utils::receive_next_question(&mut receiver1).await?;
+    let _ = utils::receive_next_question(&mut receiver2).await?;
+
+    // only the first player answers (correctly)
+    utils::send_question_answer(&mut sender1, &player1, &question.question, 0, vec![1]).await?;
+    let _ = utils::receive_question_update(&mut receiver1).await?;
+    let _ = utils::receive_question_update(&mut receiver2).await?;
+
+    server.send(lobby::EndQuestion { index: 0 }).await??;
+    let _ = utils::receive_question_ended(&mut receiver1).await?;
+    let _ = utils::receive_question_ended(&mut receiver2).await?;
+
+    server.send(SwitchToLeaderboard).await??;
+
+    // the report comes right after the final leaderboard
+    let leaderboard = utils::receive_show_leaderboard(&mut receiver1).await?;
+    assert!(leaderboard.was_final_round);
+    let report1 = utils::receive_personal_report(&mut receiver1).await?;
+
+    let _ = utils::receive_show_leaderboard(&mut receiver2).await?;
+    let report2 = utils::receive_personal_report(&mut receiver2).await?;
+
+    assert_eq!(report1.nickname, player1.nickname);
+    assert_eq!(report1.rank, 1);
+    assert_eq!(report1.players_count, 2);
+    assert_eq!(report1.questions.len(), 1);
+    assert!(report1.questions[0].is_correct);
+    let answer = ChoiceReport {
+        text: "42".to_string(),
+        code: None,
+    };
+    assert_eq!(
+        report1.questions[0].selected_choices,
+        Some(vec![answer.clone()])
+    );
+    assert_eq!(report1.questions[0].correct_choices, vec![answer]);
+    assert_eq!(report1.total_points, report1.questions[0].points);
+    assert!((report1.accuracy() - 100.0).abs() < f64::EPSILON);
+
+    assert_eq!(report2.nickname, player2.nickname);
+    assert_eq!(report2.rank, 2);
+    assert_eq!(report2.questions[0].selected_choices, None);
+    assert!(!report2.questions[0].is_correct);
+    assert_eq!(report2.total_points, 0);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(common::terminal::messages::Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
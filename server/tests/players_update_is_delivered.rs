@@ -6,7 +6,7 @@ use std::{thread::JoinHandle, time::Duration};
 
 use actix::Addr;
 
-use common::messages::network::PlayersUpdate;
+use common::messages::{network::PlayersUpdate, protocol::Capability};
 
 use rstest::rstest;
 use server::{messages::lobby, Lobby, Teacher};
@@ -23,13 +23,14 @@ async fn players_update_is_delivered(
 ) -> anyhow::Result<()> {
     let (server_thread, server, teacher_thread, teacher) = create_server_and_teacher;
 
-    // join a first player
-    let (_fst_sender, mut fst_receiver, fst_data) = utils::join_new_player().await?;
+    // join a first player, with a client which does not understand the `PlayersDelta`
+    let (_fst_sender, mut fst_receiver, fst_data) =
+        utils::join_new_player_with(vec![Capability::PersonalReport]).await?;
 
     // join a second player
     let (_snd_sender, _snd_receiver, snd_data) = utils::join_new_player().await?;
 
-    // assert that the first player gets a players update message
+    // assert that the first player gets the whole list of players
     let msg = utils::receive_players_update(&mut fst_receiver).await?;
 
     assert_eq!(
@@ -28,6 +28,7 @@ async fn question_ends_itself_after_timeout_test_implementation() -> anyhow::Res
                 id: Uuid::new_v4(),
                 is_correct: true,
                 text: "right".to_string(),
+                code: None,
             }],
             code_block: None,
             text: "question".to_string(),
@@ -42,8 +43,8 @@ async fn question_ends_itself_after_timeout_test_implementation() -> anyhow::Res
     let (mut fst_sender, mut fst_receiver, fst_player) = utils::join_new_player().await?;
     let (_snd_sender, mut snd_receiver, _snd_player) = utils::join_new_player().await?;
 
-    // first receives PlayersUpdate
-    let _fst_players_update = utils::receive_players_update(&mut fst_receiver).await?;
+    // first receives PlayersDelta
+    let _fst_players_delta = utils::receive_players_delta(&mut fst_receiver).await?;
 
     // start the round
     server.send(StartQuestion).await??;
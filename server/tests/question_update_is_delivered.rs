@@ -27,8 +27,8 @@ async fn answer_can_be_selected(
     let (mut fst_sender, mut fst_receiver, fst_player) = utils::join_new_player().await?;
     let (mut snd_sender, mut snd_receiver, snd_player) = utils::join_new_player().await?;
 
-    // first receives PlayersUpdate
-    let _fst_players_update = utils::receive_players_update(&mut fst_receiver).await?;
+    // first receives PlayersDelta
+    let _fst_players_delta = utils::receive_players_delta(&mut fst_receiver).await?;
 
     // start the round
     server.send(StartQuestion).await??;
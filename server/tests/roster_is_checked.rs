@@ -0,0 +1,244 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    time::Duration,
+};
+
+use anyhow::bail;
+use chrono::Utc;
+use common::{
+    constants::{
+        NICKNAME_ALREADY_TAKEN_MSG, STUDENT_ID_REQUIRED_MSG, STUDENT_ID_TAKEN_MSG,
+        UNKNOWN_STUDENT_ID_MSG,
+    },
+    messages::{
+        network::{CanJoin, JoinRequest, JoinResponse, PlayerData},
+        ClientNetworkMessage, ServerNetworkMessage,
+    },
+    terminal::messages::Stop,
+};
+use futures_util::SinkExt;
+use rand::random;
+use ratatui::style::Color;
+use rstest::rstest;
+use server::{
+    lobby::{Lobby, Phase, PlayerQuestionRecord, Roster, RosterStudent},
+    messages::lobby,
+};
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::{
+    fixtures::{create_server::start_server, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+fn student(id: &str, name: &str) -> RosterStudent {
+    RosterStudent {
+        id: id.to_string(),
+        name: name.to_string(),
+    }
+}
+
+fn roster() -> Roster {
+    Roster::from_students(vec![
+        student("S01", "Alice Novak"),
+        student("S02", "Bob Dvorak"),
+    ])
+    .expect("Failed to create the roster")
+}
+
+/// Sends the join request with the student ID and returns the response of the server
+async fn join_with_student_id(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    uuid: Uuid,
+    student_id: Option<&str>,
+) -> anyhow::Result<JoinResponse> {
+    let nickname = Uuid::new_v4().to_string();
+    join_as_student(sender, receiver, uuid, &nickname, student_id).await
+}
+
+/// Sends the join request with the nickname and the student ID and returns the response of the server
+async fn join_as_student(
+    sender: &mut utils::Sender,
+    receiver: &mut utils::Receiver,
+    uuid: Uuid,
+    nickname: &str,
+    student_id: Option<&str>,
+) -> anyhow::Result<JoinResponse> {
+    let request = ClientNetworkMessage::JoinRequest(JoinRequest {
+        player_data: PlayerData {
+            color: Color::Rgb(random(), random(), random()),
+            color_index: None,
+            nickname: nickname.to_string(),
+            uuid,
+            avatar: None,
+        },
+        student_id: student_id.map(str::to_owned),
+    });
+    sender
+        .send(Message::text(serde_json::to_string(&request)?))
+        .await?;
+
+    let ServerNetworkMessage::JoinResponse(response) =
+        utils::receive_server_network_msg(receiver).await?
+    else {
+        bail!("Expected JoinResponse");
+    };
+
+    Ok(response)
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn players_join_with_student_id_from_roster() -> anyhow::Result<()> {
+    let (server_thread, server) =
+        start_server(Lobby::new(utils::sample_questions()).with_roster(Some(roster())));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    let (mut sender1, mut receiver1) = utils::connect_to_server().await;
+    let (uuid1, response) = utils::try_join_server(&mut sender1, &mut receiver1).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+    assert!(response.student_id_required);
+
+    // the player may try again until the ID is right
+    let response = join_with_student_id(&mut sender1, &mut receiver1, uuid1, None).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(STUDENT_ID_REQUIRED_MSG.to_owned())
+    );
+
+    let response = join_with_student_id(&mut sender1, &mut receiver1, uuid1, Some("S99")).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(UNKNOWN_STUDENT_ID_MSG.to_owned())
+    );
+
+    let response = join_with_student_id(&mut sender1, &mut receiver1, uuid1, Some(" s01 ")).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    // nobody else can join as the same student
+    let (mut sender2, mut receiver2) = utils::connect_to_server().await;
+    let (uuid2, _) = utils::try_join_server(&mut sender2, &mut receiver2).await?;
+    let response = join_with_student_id(&mut sender2, &mut receiver2, uuid2, Some("S01")).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(STUDENT_ID_TAKEN_MSG.to_owned())
+    );
+
+    let state = server.send(GetServerState).await?;
+    assert_eq!(state.student_ids.get(&uuid1), Some(&"S01".to_owned()));
+    assert!(!state.joined_players.contains_key(&uuid2));
+    assert_eq!(state.missing_students(), vec![student("S02", "Bob Dvorak")]);
+
+    let results = state.game_results();
+    let row = &results.summary[0];
+    assert_eq!(row.player_uuid, uuid1);
+    assert_eq!(row.student_id.as_deref(), Some("S01"));
+    assert_eq!(row.student_name.as_deref(), Some("Alice Novak"));
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn resumed_players_are_matched_by_student_id() -> anyhow::Result<()> {
+    // Alice answered the first question before the server died
+    let alice = PlayerData {
+        uuid: Uuid::new_v4(),
+        nickname: "alice".to_string(),
+        color: Color::Rgb(10, 20, 30),
+        color_index: None,
+        avatar: None,
+    };
+    let record = PlayerQuestionRecord {
+        answer_order: 0,
+        timestamp: Utc::now(),
+        selected_answers: HashSet::new(),
+        points_awarded: 800,
+    };
+
+    let mut snapshot = Lobby::new(utils::sample_questions()).snapshot();
+    snapshot.phase = Phase::ShowingLeaderboard(0);
+    snapshot.players = vec![alice.clone()];
+    snapshot.results = HashMap::from([(0, HashMap::from([(alice.uuid, record.clone())]))]);
+    snapshot.student_ids = HashMap::from([(alice.uuid, "S01".to_string())]);
+
+    let (server_thread, server) =
+        start_server(Lobby::from_snapshot(snapshot).with_roster(Some(roster())));
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // Bob cannot take over her results by joining with her nickname
+    let (mut sender1, mut receiver1) = utils::connect_to_server().await;
+    let (uuid1, _) = utils::try_join_server(&mut sender1, &mut receiver1).await?;
+    let response =
+        join_as_student(&mut sender1, &mut receiver1, uuid1, "alice", Some("S02")).await?;
+    assert_eq!(
+        response.can_join,
+        CanJoin::No(NICKNAME_ALREADY_TAKEN_MSG.to_owned())
+    );
+
+    // she continues with her student ID, even with another nickname
+    let (mut sender2, mut receiver2) = utils::connect_to_server().await;
+    let (uuid2, _) = utils::try_join_server(&mut sender2, &mut receiver2).await?;
+    let response =
+        join_as_student(&mut sender2, &mut receiver2, uuid2, "Alice N.", Some("S01")).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    let state = server.send(GetServerState).await?;
+    assert!(state.resumed_players.is_empty());
+    assert_eq!(state.results[&0].get(&uuid2), Some(&record));
+    assert!(!state.results[&0].contains_key(&alice.uuid));
+    assert_eq!(
+        state.student_ids,
+        HashMap::from([(uuid2, "S01".to_string())])
+    );
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
+
+#[test]
+fn roster_is_loaded_from_csv() -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join(format!("clihoot-roster-{}.csv", Uuid::new_v4()));
+
+    fs::write(&path, "id,name\nS01, Alice Novak\n S02 ,Bob Dvorak\n")?;
+    let roster = Roster::load(&path)?;
+    assert_eq!(
+        roster.students(),
+        [student("S01", "Alice Novak"), student("S02", "Bob Dvorak")]
+    );
+    assert_eq!(roster.find("s02"), Some(&student("S02", "Bob Dvorak")));
+    assert_eq!(roster.find("S03"), None);
+
+    // the IDs must tell the students apart
+    fs::write(&path, "id,name\nS01,Alice Novak\ns01,Bob Dvorak\n")?;
+    assert!(Roster::load(&path).is_err());
+
+    fs::write(&path, "id,name\n,Alice Novak\n")?;
+    assert!(Roster::load(&path).is_err());
+
+    fs::write(&path, "id,name\n")?;
+    assert!(Roster::load(&path).is_err());
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use common::{
+    messages::{
+        network::{PlayerData, PlayersDelta},
+        ServerNetworkMessage,
+    },
+    terminal::messages::Stop,
+};
+use ratatui::style::Color;
+use rstest::rstest;
+use server::{lobby::Lobby, messages::lobby};
+use uuid::Uuid;
+
+use crate::{
+    fixtures::{create_server::start_server, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn slow_client_is_disconnected() -> anyhow::Result<()> {
+    let lobby = Lobby::new(utils::sample_questions()).with_outbox_capacity(8);
+    let (server_thread, server) = start_server(lobby);
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // the player never reads anything after joining
+    let (_sender, _receiver, player) = utils::join_new_player().await?;
+
+    let state = server.send(GetServerState).await?;
+    let socket = state.joined_players[&player.uuid].addr.clone();
+
+    // a big message, so the buffers of the connection fill up soon
+    let message = ServerNetworkMessage::PlayersDelta(PlayersDelta {
+        joined: (0..1000)
+            .map(|i| PlayerData {
+                uuid: Uuid::new_v4(),
+                nickname: format!("player {i}"),
+                color: Color::Rgb(1, 2, 3),
+                color_index: None,
+                avatar: None,
+            })
+            .collect(),
+        left: vec![],
+    });
+
+    for _ in 0..1000 {
+        socket.do_send(message.clone());
+    }
+
+    // the server gives up on the client instead of queueing the messages forever
+    loop {
+        let state = server.send(GetServerState).await?;
+        if state.joined_players.is_empty() {
+            assert_eq!(state.outbox_metrics.slow_clients(), 1);
+            assert!(state.outbox_metrics.peak_len() <= 8);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
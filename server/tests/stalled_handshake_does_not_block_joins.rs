@@ -0,0 +1,49 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::time::Duration;
+
+use common::{constants::DEFAULT_PORT, terminal::messages::Stop};
+use rstest::rstest;
+use server::{lobby::Lobby, messages::lobby, websocket::ConnectionLimits};
+use tokio::{io::AsyncReadExt, net::TcpStream};
+
+use crate::{
+    fixtures::{create_server::start_server, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn stalled_handshake_does_not_block_joins() -> anyhow::Result<()> {
+    let lobby = Lobby::new(utils::sample_questions()).with_connection_limits(ConnectionLimits {
+        handshake_timeout: Duration::from_secs(2),
+        ..ConnectionLimits::default()
+    });
+    let (server_thread, server) = start_server(lobby);
+    let (teacher_thread, teacher) = start_teacher(&server);
+
+    // connects, but never starts the websocket handshake
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut stalled = TcpStream::connect(format!("localhost:{DEFAULT_PORT}")).await?;
+
+    // the others can still join
+    let (_sender, _receiver, player) = utils::join_new_player().await?;
+    let state = server.send(GetServerState).await?;
+    assert!(state.joined_players.contains_key(&player.uuid));
+
+    // and the stalled connection is closed after the timeout
+    let mut buffer = [0; 16];
+    let read = tokio::time::timeout(Duration::from_secs(5), stalled.read(&mut buffer)).await?;
+    assert_eq!(read?, 0);
+
+    server.send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
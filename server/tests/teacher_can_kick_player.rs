@@ -2,11 +2,11 @@ mod fixtures;
 mod mocks;
 mod utils;
 
-use std::{borrow::Cow, thread::JoinHandle, time::Duration, vec};
+use std::{thread::JoinHandle, time::Duration, vec};
 
 use actix::Addr;
 
-use common::messages::network::PlayersUpdate;
+use common::messages::network::PlayersDelta;
 
 use rstest::rstest;
 use server::{
@@ -29,10 +29,10 @@ async fn teacher_can_kick_player(
     let (_fst_sender, mut fst_receiver, fst_data) = utils::join_new_player().await?;
 
     // join a second player
-    let (_snd_sender, mut snd_receiver, snd_data) = utils::join_new_player().await?;
+    let (_snd_sender, mut snd_receiver, _) = utils::join_new_player().await?;
 
-    // drain the players update message which comes after the joining the second player
-    let _ = utils::receive_players_update(&mut fst_receiver).await?;
+    // drain the players delta message which comes after the joining the second player
+    let _ = utils::receive_players_delta(&mut fst_receiver).await?;
 
     let kick_reason = "Bad nickname";
 
@@ -51,17 +51,18 @@ async fn teacher_can_kick_player(
         msg,
         CloseFrame {
             code: CloseCode::Normal,
-            reason: Cow::from(kick_reason),
+            reason: kick_reason.into(),
         }
     );
 
     // assert that the second player gets a players update message
-    let msg = utils::receive_players_update(&mut snd_receiver).await?;
+    let msg = utils::receive_players_delta(&mut snd_receiver).await?;
 
     assert_eq!(
         msg,
-        PlayersUpdate {
-            players: vec![snd_data.clone(),]
+        PlayersDelta {
+            joined: vec![],
+            left: vec![fst_data.uuid],
         }
     );
 
@@ -8,7 +8,10 @@ use actix::Addr;
 
 use common::{
     constants::{DEFAULT_QUIZ_NAME, LOBBY_LOCKED_MSG},
-    messages::network::{CanJoin, TryJoinResponse},
+    messages::{
+        network::{CanJoin, TryJoinResponse},
+        protocol::{Capability, ProtocolVersion},
+    },
 };
 use rstest::rstest;
 use server::{
@@ -47,6 +50,10 @@ async fn teacher_can_lock_game(
         TryJoinResponse {
             can_join: CanJoin::No(LOBBY_LOCKED_MSG.to_string()),
             quiz_name: DEFAULT_QUIZ_NAME.to_string(),
+            custom_syntaxes: vec![],
+            protocol: ProtocolVersion::CURRENT,
+            capabilities: Capability::supported(),
+            student_id_required: false,
             uuid
         }
     );
@@ -0,0 +1,167 @@
+mod fixtures;
+mod mocks;
+mod utils;
+
+use std::{
+    io::BufReader,
+    net::{Ipv4Addr, SocketAddr, TcpStream},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::bail;
+use common::{
+    constants::UNKNOWN_PIN_MSG,
+    messages::network::{CanJoin, TryJoinRequest},
+    questions::QuestionSet,
+    terminal::messages::Stop,
+};
+use rstest::rstest;
+use server::{
+    attach::{read_line, write_line},
+    lobby::{AttachSettings, Lobby, Phase},
+    messages::{
+        attach::{AttachRequest, AttachResponse, ToLobby, ToTeacher},
+        lobby::{self, StartQuestion},
+    },
+};
+use uuid::Uuid;
+
+const ATTACH_PORT: u16 = 8081;
+
+use crate::{
+    fixtures::{create_server::start_rooms, create_server_and_teacher::start_teacher},
+    mocks::GetServerState,
+};
+
+struct AttachedTeacher {
+    connection: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl AttachedTeacher {
+    fn send(&mut self, msg: impl Into<ToLobby>) -> anyhow::Result<()> {
+        write_line(&mut self.connection, &msg.into())
+    }
+
+    /// Reads the messages from the lobby until the one the teacher waits for
+    fn wait_for<T>(&mut self, find: impl Fn(ToTeacher) -> Option<T>) -> anyhow::Result<T> {
+        loop {
+            let Some(msg) = read_line::<ToTeacher>(&mut self.reader)? else {
+                bail!("The server closed the connection");
+            };
+            if let Some(found) = find(msg) {
+                return Ok(found);
+            }
+        }
+    }
+}
+
+fn attach(
+    pin: &str,
+    questions: Option<QuestionSet>,
+    key: Option<&str>,
+) -> anyhow::Result<(AttachResponse, AttachedTeacher)> {
+    let mut connection = TcpStream::connect((Ipv4Addr::LOCALHOST, ATTACH_PORT))?;
+    connection.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let request = AttachRequest {
+        pin: Some(pin.to_owned()),
+        questions,
+        key: key.map(ToOwned::to_owned),
+    };
+    write_line(&mut connection, &request)?;
+
+    let mut reader = BufReader::new(connection.try_clone()?);
+    let Some(response) = read_line(&mut reader)? else {
+        bail!("The server closed the connection");
+    };
+
+    Ok((response, AttachedTeacher { connection, reader }))
+}
+
+#[rstest]
+#[tokio::test]
+#[timeout(Duration::from_secs(10))]
+async fn teachers_attach_to_games() -> anyhow::Result<()> {
+    let attach_settings = AttachSettings {
+        addr: SocketAddr::from((Ipv4Addr::LOCALHOST, ATTACH_PORT)),
+        new_room: Arc::new(|questions, pin| Lobby::new(questions).with_pin(Some(pin))),
+    };
+    let (server_thread, servers) = start_rooms(
+        vec![Lobby::new(utils::sample_questions()).with_pin(Some("1111".to_owned()))],
+        Some(attach_settings),
+    );
+    let (teacher_thread, teacher) = start_teacher(&servers[0]);
+
+    // the teacher starts a new game while the server runs
+    let (response, mut attached) = attach("2222", Some(utils::sample_questions()), None)?;
+    let Ok(game) = response else {
+        bail!("The teacher could not start the game: {response:?}");
+    };
+    assert_eq!(game.quiz_name, utils::sample_questions().quiz_name);
+    assert_eq!(game.pin, "2222");
+    let pin = attached.wait_for(|msg| match msg {
+        ToTeacher::ShowPin(show) => Some(show.pin),
+        _ => None,
+    })?;
+    assert_eq!(pin, "2222");
+
+    // nobody else can take the game from its teacher, not even with the key
+    let (response, _) = attach("2222", None, Some(&game.key))?;
+    assert!(response.is_err());
+
+    let (mut sender, mut receiver) = utils::connect_to_server().await;
+    let uuid = Uuid::new_v4();
+    let request = TryJoinRequest {
+        pin: Some("2222".to_owned()),
+        ..TryJoinRequest::new(uuid)
+    };
+    let response = utils::try_join_server_with_request(&mut sender, &mut receiver, request).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+    let (_, response) = utils::join_server(&mut sender, &mut receiver, uuid).await?;
+    assert_eq!(response.can_join, CanJoin::Yes);
+
+    attached.send(StartQuestion)?;
+    attached.wait_for(|msg| match msg {
+        ToTeacher::NextQuestion(question) => Some(question),
+        _ => None,
+    })?;
+
+    // the teacher who lost the connection attaches again and is brought up to date
+    drop(attached);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (response, _) = attach("2222", None, None)?;
+    assert!(response.is_err());
+    let (response, _) = attach("2222", None, Some("wrong key"))?;
+    assert!(response.is_err());
+    let (response, mut attached) = attach("2222", None, Some(&game.key))?;
+    assert_eq!(response, Ok(game.clone()));
+    let players = attached.wait_for(|msg| match msg {
+        ToTeacher::PlayersUpdate(update) => Some(update.players),
+        _ => None,
+    })?;
+    assert!(players.iter().any(|player| player.uuid == uuid));
+    let question = attached.wait_for(|msg| match msg {
+        ToTeacher::NextQuestion(question) => Some(question),
+        _ => None,
+    })?;
+    assert_eq!(question.question_index, 0);
+
+    // stopping the game leaves the first one running
+    attached.send(lobby::HardStop)?;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (response, _) = attach("2222", None, Some(&game.key))?;
+    assert_eq!(response, Err(UNKNOWN_PIN_MSG.to_owned()));
+    let state = servers[0].send(GetServerState).await?;
+    assert_eq!(state.phase, Phase::WaitingForPlayers);
+    assert!(!server_thread.is_finished());
+
+    servers[0].send(lobby::HardStop).await?;
+    server_thread.join().expect("Server thread panicked");
+
+    teacher.send(Stop).await??;
+    teacher_thread.join().expect("Teacher thread panicked");
+
+    Ok(())
+}
@@ -41,24 +41,28 @@ fn get_questions() -> QuestionSet {
     //// QUESTION DATA ////
     let q1_choice1 = Choice {
         text: "10".to_string(),
+        code: None,
         id: Uuid::new_v4(),
         is_correct: false,
     };
 
     let q1_choice2 = Choice {
         text: "20".to_string(),
+        code: None,
         id: Uuid::new_v4(),
         is_correct: true,
     };
 
     let q1_choice3 = Choice {
         text: "30".to_string(),
+        code: None,
         id: Uuid::new_v4(),
         is_correct: false,
     };
 
     let q1_choice4 = Choice {
         text: "40".to_string(),
+        code: None,
         id: Uuid::new_v4(),
         is_correct: false,
     };
@@ -75,18 +79,21 @@ fn get_questions() -> QuestionSet {
         id: Uuid::new_v4(),
         is_correct: true,
         text: "Print \"Hello, world!\"".to_string(),
+        code: None,
     };
 
     let q2_choice2 = Choice {
         id: Uuid::new_v4(),
         is_correct: true,
         text: "Print \"Hello, world!\" and exit".to_string(),
+        code: None,
     };
 
     let q2 = Question {
         code_block: Some(CodeBlock {
             language: "C".to_string(),
             code: Q2_CODEBLOCK.to_string(),
+            highlight_lines: vec![],
         }),
         text: Q2_TEXT.to_string(),
         time_seconds: Q2_TIME,
@@ -124,7 +131,7 @@ async fn init_game(questions: QuestionSet) -> anyhow::Result<Game> {
     let (fst_sender, mut fst_receiver, fst_player) = utils::join_new_player().await?;
     let (snd_sender, snd_receiver, snd_player) = utils::join_new_player().await?;
 
-    utils::receive_players_update(&mut fst_receiver).await?;
+    utils::receive_players_delta(&mut fst_receiver).await?;
 
     Ok(Game {
         questions,
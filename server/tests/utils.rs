@@ -2,10 +2,11 @@ use std::{path::Path, thread, time::Duration};
 
 use anyhow::{bail, Ok};
 use common::messages::network::{
-    AnswerSelected, CanJoin, JoinRequest, JoinResponse, NextQuestion, PlayerData, PlayersUpdate,
-    QuestionEnded, QuestionUpdate, ShowLeaderboard, TryJoinRequest, TryJoinResponse,
+    AnswerSelected, CanJoin, JoinRequest, JoinResponse, NextQuestion, PersonalReport, PlayerData,
+    PlayersDelta, PlayersUpdate, QuestionEnded, QuestionUpdate, ShowLeaderboard, TryJoinRequest,
+    TryJoinResponse,
 };
-use common::messages::ServerNetworkMessage;
+use common::messages::{encoding::decode, protocol::Capability, ServerNetworkMessage};
 use common::questions;
 use common::{constants::DEFAULT_PORT, messages::ClientNetworkMessage};
 use futures_util::SinkExt;
@@ -57,6 +58,19 @@ pub async fn connect_to_server() -> (Sender, Receiver) {
     (sender, receiver)
 }
 
+/// Receives the next frame from the server, skipping the heartbeat pings and pongs.
+/// # Panics
+/// - if failed to receive message, will panic.
+pub async fn receive_frame(receiver: &mut Receiver) -> anyhow::Result<Message> {
+    loop {
+        let msg = receiver.next().await.expect("Failed to receive message")?;
+
+        if !matches!(msg, Message::Ping(_) | Message::Pong(_)) {
+            return Ok(msg);
+        }
+    }
+}
+
 #[allow(dead_code)]
 /// Tries to join the server and returns the uuid and the response.
 /// # Panics
@@ -65,28 +79,53 @@ pub async fn try_join_server(
     sender: &mut Sender,
     receiver: &mut Receiver,
 ) -> anyhow::Result<(Uuid, TryJoinResponse)> {
-    thread::sleep(Duration::from_millis(100));
+    try_join_server_with(sender, receiver, Capability::supported()).await
+}
 
+#[allow(dead_code)]
+/// Tries to join the server announcing the given capabilities,
+/// returns the uuid and the response.
+/// # Panics
+/// - if the server cannot be joined, will panic.
+pub async fn try_join_server_with(
+    sender: &mut Sender,
+    receiver: &mut Receiver,
+    capabilities: Vec<Capability>,
+) -> anyhow::Result<(Uuid, TryJoinResponse)> {
     let id = Uuid::new_v4();
-    let msg = ClientNetworkMessage::TryJoinRequest(TryJoinRequest { uuid: id });
+    let request = TryJoinRequest {
+        capabilities,
+        ..TryJoinRequest::new(id)
+    };
 
-    sender
-        .send(Message::Text(serde_json::to_string(&msg)?))
-        .await?;
+    let response = try_join_server_with_request(sender, receiver, request).await?;
+    Ok((id, response))
+}
+
+#[allow(dead_code)]
+/// Sends the given try join request and returns the response.
+/// # Panics
+/// - if the server cannot be joined, will panic.
+pub async fn try_join_server_with_request(
+    sender: &mut Sender,
+    receiver: &mut Receiver,
+    request: TryJoinRequest,
+) -> anyhow::Result<TryJoinResponse> {
+    thread::sleep(Duration::from_millis(100));
 
-    let msg = receiver.next().await.expect("Failed to receive message")?;
+    let msg = ClientNetworkMessage::TryJoinRequest(request);
 
-    let Message::Text(msg) = msg else {
-        bail!("Expected Text message")
-    };
+    sender
+        .send(Message::text(serde_json::to_string(&msg)?))
+        .await?;
 
-    let msg = serde_json::from_str::<ServerNetworkMessage>(&msg)?;
+    let msg = decode::<ServerNetworkMessage>(&receive_frame(receiver).await?)?;
 
     let ServerNetworkMessage::TryJoinResponse(msg) = msg else {
         bail!("Expected TryJoinResponse")
     };
 
-    Ok((id, msg))
+    Ok(msg)
 }
 
 /// Generates a new player uuid, connects to the server and joins it.
@@ -97,32 +136,40 @@ pub async fn join_server(
     receiver: &mut Receiver,
     id: Uuid,
 ) -> anyhow::Result<(PlayerData, JoinResponse)> {
-    thread::sleep(Duration::from_millis(100));
-
     let random_color = Color::Rgb(random(), random(), random());
     let random_string_nickname = Uuid::new_v4().to_string();
 
     let player_data = PlayerData {
         color: random_color,
+        color_index: None,
         nickname: random_string_nickname,
         uuid: id,
+        avatar: None,
     };
 
+    join_server_as(sender, receiver, player_data).await
+}
+
+/// Joins the server with the given player data.
+/// # Panics
+/// - if failed to receive message, will panic.
+pub async fn join_server_as(
+    sender: &mut Sender,
+    receiver: &mut Receiver,
+    player_data: PlayerData,
+) -> anyhow::Result<(PlayerData, JoinResponse)> {
+    thread::sleep(Duration::from_millis(100));
+
     let msg = ClientNetworkMessage::JoinRequest(JoinRequest {
         player_data: player_data.clone(),
+        student_id: None,
     });
 
     sender
-        .send(Message::Text(serde_json::to_string(&msg)?))
+        .send(Message::text(serde_json::to_string(&msg)?))
         .await?;
 
-    let msg = receiver.next().await.expect("Failed to receive message")?;
-
-    let Message::Text(msg) = msg else {
-        bail!("Expected Text message")
-    };
-
-    let msg = serde_json::from_str::<ServerNetworkMessage>(&msg)?;
+    let msg = decode::<ServerNetworkMessage>(&receive_frame(receiver).await?)?;
 
     let ServerNetworkMessage::JoinResponse(msg) = msg else {
         bail!("Expected JoinResponse")
@@ -136,8 +183,19 @@ pub async fn join_server(
 /// - if the server returns bad response, will panic.
 #[allow(dead_code)]
 pub async fn join_new_player() -> anyhow::Result<(Sender, Receiver, PlayerData)> {
+    join_new_player_with(Capability::supported()).await
+}
+
+/// Generates a new player uuid, connects to the server and joins it, announcing
+/// the given capabilities.
+/// # Panics
+/// - if the server returns bad response, will panic.
+#[allow(dead_code)]
+pub async fn join_new_player_with(
+    capabilities: Vec<Capability>,
+) -> anyhow::Result<(Sender, Receiver, PlayerData)> {
     let (mut sender, mut receiver) = connect_to_server().await;
-    let (id, _msg) = try_join_server(&mut sender, &mut receiver).await?;
+    let (id, _msg) = try_join_server_with(&mut sender, &mut receiver, capabilities).await?;
     let (player_data, res) = join_server(&mut sender, &mut receiver, id).await?;
 
     // And it must be correct
@@ -154,9 +212,7 @@ pub async fn join_new_player() -> anyhow::Result<(Sender, Receiver, PlayerData)>
 pub async fn receive_server_network_msg(
     receiver: &mut Receiver,
 ) -> anyhow::Result<ServerNetworkMessage> {
-    let msg = receiver.next().await.expect("Failed to receive message")?;
-    let msg = msg.to_text()?;
-    let msg = serde_json::from_str::<ServerNetworkMessage>(msg)?;
+    let msg = decode::<ServerNetworkMessage>(&receive_frame(receiver).await?)?;
 
     Ok(msg)
 }
@@ -166,7 +222,7 @@ pub async fn receive_server_network_msg(
 /// # Panics
 /// - if failed to receive message, will panic.
 pub async fn receive_close_frame(receiver: &mut Receiver) -> anyhow::Result<CloseFrame> {
-    let msg = receiver.next().await.expect("Failed to receive message")?;
+    let msg = receive_frame(receiver).await?;
 
     let Message::Close(Some(msg)) = msg else {
         bail!("Expected CloseFrame")
@@ -215,6 +271,16 @@ pub async fn receive_players_update(receiver: &mut Receiver) -> anyhow::Result<P
     Ok(update)
 }
 
+#[allow(dead_code)]
+pub async fn receive_players_delta(receiver: &mut Receiver) -> anyhow::Result<PlayersDelta> {
+    let ServerNetworkMessage::PlayersDelta(delta) = receive_server_network_msg(receiver).await?
+    else {
+        bail!("Expected PlayersDelta")
+    };
+
+    Ok(delta)
+}
+
 #[allow(dead_code)]
 pub async fn receive_show_leaderboard(receiver: &mut Receiver) -> anyhow::Result<ShowLeaderboard> {
     let ServerNetworkMessage::ShowLeaderboard(show) = receive_server_network_msg(receiver).await?
@@ -225,6 +291,16 @@ pub async fn receive_show_leaderboard(receiver: &mut Receiver) -> anyhow::Result
     Ok(show)
 }
 
+#[allow(dead_code)]
+pub async fn receive_personal_report(receiver: &mut Receiver) -> anyhow::Result<PersonalReport> {
+    let ServerNetworkMessage::PersonalReport(report) = receive_server_network_msg(receiver).await?
+    else {
+        bail!("Expected PersonalReport")
+    };
+
+    Ok(report)
+}
+
 #[allow(dead_code)]
 pub async fn send_question_answer(
     sender: &mut Sender,
@@ -252,7 +328,7 @@ pub async fn send_question_answer(
 
     // send the answer
     sender
-        .send(Message::Text(serde_json::to_string(&answer)?))
+        .send(Message::text(serde_json::to_string(&answer)?))
         .await?;
 
     Ok(())